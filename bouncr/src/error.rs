@@ -0,0 +1,27 @@
+use std::io;
+use thiserror::Error;
+
+// == everything bouncr can fail with
+// - the terminal is put back before any of them is printed, see Screen
+#[derive(Debug, Error)]
+pub enum Error {
+    // --speed, --balls, --glyph
+    #[error("illegal {what} -- {value}")]
+    InvalidValue { what: &'static str, value: String },
+
+    // redirected to a file or a pipe : nowhere to draw
+    #[error("stdout is not a terminal")]
+    NotATerminal,
+
+    #[error("terminal: {0}")]
+    TerminalFailed(#[source] io::Error),
+}
+
+impl ior::Failure for Error {
+    fn exit_code(&self) -> i32 {
+        match self {
+            Error::InvalidValue { .. } => 2,
+            Error::NotATerminal | Error::TerminalFailed(_) => 6,
+        }
+    }
+}
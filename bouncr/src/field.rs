@@ -0,0 +1,192 @@
+use crate::Config;
+
+// == the animation without a terminal : balls on a width x height grid,
+// moved a cell per step
+// - a ball turns around on the edge cell, the way bounce.sh's does : it
+// reaches column width - 1, then heads back
+// - run() draws it, tests step it and look at frame()
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ball {
+    pub x: u16,
+    pub y: u16,
+    // -1, 0 or 1 cell per step, dy is 0 without --2d
+    pub dx: i8,
+    pub dy: i8,
+}
+
+#[derive(Debug, Clone)]
+pub struct Field {
+    width: u16,
+    height: u16,
+    glyph: char,
+    balls: Vec<Ball>,
+}
+
+impl Field {
+    // -- balls spread out so they don't start on top of each other : ball 0
+    // at the top left like bounce.sh, every other one heading left
+    // - without --2d each ball has a row of its own
+    // - one ball per cell at most : `-n 1000000000` gets width x height
+    pub fn new(config: &Config, width: u16, height: u16) -> Field {
+        let cells = width as usize * height as usize;
+        let balls = (0..config.balls.min(cells))
+            .map(|i| {
+                let i = i as u64;
+                let spread = |n: u64, len: u16| (n % len.max(1) as u64) as u16;
+                Ball {
+                    x: spread(i * 7, width),
+                    y: match config.two_d {
+                        true => spread(i * 3, height),
+                        false => spread(i, height),
+                    },
+                    dx: if i.is_multiple_of(2) { 1 } else { -1 },
+                    dy: config.two_d as i8,
+                }
+            })
+            .collect();
+        Field {
+            width,
+            height,
+            glyph: config.glyph,
+            balls,
+        }
+    }
+
+    pub fn size(&self) -> (u16, u16) {
+        (self.width, self.height)
+    }
+
+    pub fn glyph(&self) -> char {
+        self.glyph
+    }
+
+    pub fn balls(&self) -> &[Ball] {
+        &self.balls
+    }
+
+    // -- one cell on for every ball, turning on the edges first
+    pub fn step(&mut self) {
+        for ball in &mut self.balls {
+            advance(&mut ball.x, &mut ball.dx, self.width);
+            advance(&mut ball.y, &mut ball.dy, self.height);
+        }
+    }
+
+    // -- the terminal changed size : balls outside it move onto its edge
+    // and carry on from there
+    pub fn resize(&mut self, width: u16, height: u16) {
+        self.width = width;
+        self.height = height;
+        for ball in &mut self.balls {
+            ball.x = ball.x.min(width.saturating_sub(1));
+            ball.y = ball.y.min(height.saturating_sub(1));
+        }
+    }
+
+    // -- the grid as text, a row a line, balls on top of each other drawn
+    // once
+    pub fn frame(&self) -> Vec<String> {
+        let mut rows = vec![vec![' '; self.width as usize]; self.height as usize];
+        for ball in &self.balls {
+            if let Some(cell) = rows
+                .get_mut(ball.y as usize)
+                .and_then(|row| row.get_mut(ball.x as usize))
+            {
+                *cell = self.glyph;
+            }
+        }
+        rows.into_iter().map(|row| row.into_iter().collect()).collect()
+    }
+}
+
+// -- one axis of a ball : a direction of 0 stays put
+fn advance(pos: &mut u16, dir: &mut i8, len: u16) {
+    if *dir == 0 || len <= 1 {
+        return;
+    }
+    if *pos >= len - 1 {
+        *dir = -1;
+    } else if *pos == 0 {
+        *dir = 1;
+    }
+    *pos = pos.saturating_add_signed(*dir as i16).min(len - 1);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Field;
+    use crate::Config;
+
+    fn field(balls: usize, two_d: bool, width: u16, height: u16) -> Field {
+        let config = Config {
+            balls,
+            two_d,
+            ..Config::default()
+        };
+        Field::new(&config, width, height)
+    }
+
+    #[test]
+    fn test_bounce() {
+        // bounce.sh : across, back, across
+        let mut field = field(1, false, 4, 1);
+        let mut xs = vec![];
+        for _ in 0..8 {
+            xs.push(field.balls()[0].x);
+            field.step();
+        }
+        assert_eq!(xs, [0, 1, 2, 3, 2, 1, 0, 1]);
+        assert_eq!(field.frame(), ["  0 "]);
+    }
+
+    #[test]
+    fn test_ball_cap() {
+        assert_eq!(field(1_000_000_000, true, 10, 5).balls().len(), 50);
+        assert_eq!(field(3, false, 10, 5).balls().len(), 3);
+    }
+
+    #[test]
+    fn test_2d() {
+        let mut field = field(1, true, 3, 2);
+        let mut cells = vec![];
+        for _ in 0..5 {
+            let ball = field.balls()[0];
+            cells.push((ball.x, ball.y));
+            field.step();
+        }
+        assert_eq!(cells, [(0, 0), (1, 1), (2, 0), (1, 1), (0, 0)]);
+        assert_eq!(field.frame(), ["   ", " 0 "]);
+    }
+
+    #[test]
+    fn test_balls() {
+        // a row each without --2d, every other one heading left
+        let mut field = field(3, false, 10, 3);
+        assert_eq!(field.frame(), ["0         ", "       0  ", "    0     "]);
+        field.step();
+        assert_eq!(field.frame(), [" 0        ", "      0   ", "     0    "]);
+        // more balls than rows share them
+        let field = self::field(3, false, 10, 1);
+        assert_eq!(field.frame(), ["0   0  0  "]);
+    }
+
+    #[test]
+    fn test_resize() {
+        let mut field = field(1, true, 10, 10);
+        for _ in 0..8 {
+            field.step();
+        }
+        field.resize(5, 3);
+        assert_eq!(field.size(), (5, 3));
+        let ball = field.balls()[0];
+        assert_eq!((ball.x, ball.y), (4, 2));
+        field.step();
+        let ball = field.balls()[0];
+        assert_eq!((ball.x, ball.y), (3, 1));
+        // nothing to draw on, nothing breaks
+        field.resize(0, 0);
+        field.step();
+        assert!(field.frame().is_empty());
+    }
+}
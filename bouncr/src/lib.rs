@@ -0,0 +1,155 @@
+use clap::{App, Arg};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
+use crossterm::terminal;
+use std::io::{self, IsTerminal};
+use std::time::{Duration, Instant};
+use unicode_width::UnicodeWidthChar;
+
+mod error;
+mod field;
+mod term;
+
+pub use error::Error;
+pub use field::{Ball, Field};
+pub use term::Screen;
+
+type MyResult<T> = Result<T, Error>;
+
+// == bounce : bounce.sh's ball, in Rust
+// - built by get_args(), or by hand when bouncr is used as a library
+// - runs until ctrl-C, q or Esc, and leaves the terminal the way it was
+// found
+#[derive(Debug)]
+pub struct Config {
+    // -s : steps a second, bounce.sh's sleep sets its speed
+    pub speed: u32,
+    // -g : what a ball looks like, one character one cell wide
+    pub glyph: char,
+    // -n : no more than the terminal has cells, see Field::new()
+    pub balls: usize,
+    // --2d : bounce off the top and bottom too, not just the sides
+    pub two_d: bool,
+}
+
+impl Default for Config {
+    // -- bounce.sh : one `0`, side to side
+    fn default() -> Config {
+        Config {
+            speed: 60,
+            glyph: '0',
+            balls: 1,
+            two_d: false,
+        }
+    }
+}
+
+pub fn run(config: Config) -> MyResult<()> {
+    let stdout = io::stdout();
+    if !stdout.is_terminal() {
+        return Err(Error::NotATerminal);
+    }
+    let (width, height) = terminal::size().map_err(Error::TerminalFailed)?;
+    let mut field = Field::new(&config, width, height);
+    let mut screen = Screen::enter(stdout.lock()).map_err(Error::TerminalFailed)?;
+    animate(&config, &mut field, &mut screen).map_err(Error::TerminalFailed)
+}
+
+// -- a frame drawn, then keys and resizes handled until the next is due
+// - resizes come from SIGWINCH, which crossterm turns into Event::Resize
+// - raw mode leaves ctrl-C to us as a key : returning drops the Screen,
+// which restores the terminal
+fn animate<W: io::Write>(config: &Config, field: &mut Field, screen: &mut Screen<W>) -> io::Result<()> {
+    let delay = Duration::from_secs(1) / config.speed;
+    loop {
+        let start = Instant::now();
+        screen.draw(field)?;
+        while event::poll(delay.saturating_sub(start.elapsed()))? {
+            match event::read()? {
+                Event::Key(key) if is_quit(key) => return Ok(()),
+                Event::Resize(width, height) => {
+                    field.resize(width, height);
+                    screen.clear()?;
+                }
+                _ => {}
+            }
+        }
+        field.step();
+    }
+}
+
+fn is_quit(key: KeyEvent) -> bool {
+    match key.code {
+        KeyCode::Char('c') => key.modifiers.contains(KeyModifiers::CONTROL),
+        KeyCode::Char('q') | KeyCode::Esc => true,
+        _ => false,
+    }
+}
+
+pub fn get_args() -> MyResult<Config> {
+    let matches = App::new("bouncr")
+        .version("0.1.0")
+        .author("MTON <mton@aol.com>")
+        .about("Rust bounce.sh")
+        .arg(
+            Arg::with_name("speed")
+                .short("s")
+                .long("speed")
+                .value_name("STEPS")
+                .help("Steps a second")
+                .default_value("60")
+        )
+        .arg(
+            Arg::with_name("glyph")
+                .short("g")
+                .long("glyph")
+                .value_name("CHAR")
+                .help("The ball")
+                .default_value("0")
+        )
+        .arg(
+            Arg::with_name("balls")
+                .short("n")
+                .long("balls")
+                .value_name("N")
+                .help("Number of balls")
+                .default_value("1")
+        )
+        .arg(
+            Arg::with_name("two_d")
+                .short("2")
+                .long("2d")
+                .help("Bounce off the top and bottom too")
+                .takes_value(false)
+        )
+        .get_matches();
+
+    let glyph = matches.value_of("glyph").unwrap();
+    let mut chars = glyph.chars();
+    // a wide char (CJK, most emoji) takes 2 cells, but a ball is erased
+    // with a single ' '
+    let glyph = match (chars.next(), chars.next()) {
+        (Some(c), None) if c.width() == Some(1) => c,
+        _ => return Err(invalid("glyph", glyph)),
+    };
+
+    Ok(Config {
+        speed: parse_positive(matches.value_of("speed").unwrap(), "speed")?,
+        glyph,
+        balls: parse_positive(matches.value_of("balls").unwrap(), "ball count")?,
+        two_d: matches.is_present("two_d"),
+    })
+}
+
+fn parse_positive<T: std::str::FromStr + PartialOrd + Default>(val: &str, what: &'static str) -> MyResult<T> {
+    match val.parse() {
+        Ok(n) if n > T::default() => Ok(n),
+        _ => Err(invalid(what, val)),
+    }
+}
+
+fn invalid(what: &'static str, value: &str) -> Error {
+    Error::InvalidValue {
+        what,
+        value: value.to_string(),
+    }
+}
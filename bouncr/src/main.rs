@@ -0,0 +1,9 @@
+use ior::Failure;
+
+fn main() {
+    if let Err(e) = bouncr::get_args().and_then(bouncr::run) {
+        eprintln!("{}", e);
+        // each kind of failure has its own status, see bouncr::Error
+        std::process::exit(e.exit_code());
+    }
+}
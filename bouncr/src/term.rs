@@ -0,0 +1,61 @@
+use crate::field::{Ball, Field};
+use crossterm::{cursor, execute, queue, style, terminal};
+use std::io::{self, Write};
+
+// == the terminal while the animation runs
+// - raw mode : keys arrive one by one and ctrl-C as a key, not SIGINT, so
+// the loop can end on its own terms and put everything back
+// - the alternate screen keeps the shell's scrollback untouched, and the
+// hidden cursor stops it blinking next to the ball
+// - only the cells that change are written, and a frame goes out in one
+// flush : no clear-and-redraw flicker like bounce.sh's `tput el`
+
+pub struct Screen<W: Write> {
+    out: W,
+    // where the balls were drawn last, to erase
+    drawn: Vec<Ball>,
+}
+
+impl<W: Write> Screen<W> {
+    pub fn enter(out: W) -> io::Result<Screen<W>> {
+        terminal::enable_raw_mode()?;
+        let mut screen = Screen {
+            out,
+            drawn: vec![],
+        };
+        // on failure `screen` is dropped, which undoes raw mode
+        execute!(screen.out, terminal::EnterAlternateScreen, cursor::Hide)?;
+        Ok(screen)
+    }
+
+    // -- the balls moved since the last frame : erase, then draw
+    pub fn draw(&mut self, field: &Field) -> io::Result<()> {
+        for ball in &self.drawn {
+            queue!(self.out, cursor::MoveTo(ball.x, ball.y), style::Print(' '))?;
+        }
+        let (width, height) = field.size();
+        for ball in field.balls() {
+            // a ball can't be drawn on a terminal 0 cells wide
+            if ball.x < width && ball.y < height {
+                queue!(self.out, cursor::MoveTo(ball.x, ball.y), style::Print(field.glyph()))?;
+            }
+        }
+        self.drawn = field.balls().to_vec();
+        self.out.flush()
+    }
+
+    // -- after a resize the old cells may be gone or wrapped : start over
+    pub fn clear(&mut self) -> io::Result<()> {
+        self.drawn.clear();
+        queue!(self.out, terminal::Clear(terminal::ClearType::All))
+    }
+}
+
+impl<W: Write> Drop for Screen<W> {
+    // -- however the loop ends, a panic included : best effort, there's
+    // nothing left to report a failure to
+    fn drop(&mut self) {
+        let _ = execute!(self.out, cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
@@ -0,0 +1,39 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+
+type TestResult = Result<(), Box<dyn Error>>;
+
+// the animation itself is tested headless, through Field (see field.rs) :
+// here stdout is a pipe, never a terminal
+const PRG: &str = "bouncr";
+
+// ----------------------------------------------------------------------------
+#[test]
+fn dies_not_a_terminal() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .assert()
+        .code(6)
+        .stderr("stdout is not a terminal\n");
+    Ok(())
+}
+
+#[test]
+fn dies_bad_values() -> TestResult {
+    for (args, message) in [
+        (&["-s", "0"][..], "illegal speed -- 0"),
+        (&["--speed", "fast"], "illegal speed -- fast"),
+        (&["-n", "0"], "illegal ball count -- 0"),
+        (&["-g", "oo"], "illegal glyph -- oo"),
+        (&["--glyph", ""], "illegal glyph -- "),
+        (&["-g", "\u{754c}"], "illegal glyph -- \u{754c}"),
+        (&["-g", "\u{7}"], "illegal glyph -- \u{7}"),
+    ] {
+        Command::cargo_bin(PRG)?
+            .args(args)
+            .assert()
+            .code(2)
+            .stderr(predicate::str::contains(message));
+    }
+    Ok(())
+}
@@ -0,0 +1,65 @@
+use catr::Config;
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufReader, BufWriter, LineWriter, Write};
+use std::path::PathBuf;
+
+// -- `catr -n` on a large file, output to /dev/null
+// - line_buffered : what println! / a bare StdoutLock did, a write(2)
+// per line
+// - default_buffered : user-041's BufWriter::new() around the lock, 8 KiB
+// - buffered : ior::stdout() when stdout isn't a terminal, 64 KiB
+// run with : cargo bench --bench number
+
+const SIZE_MIB: usize = 1024;
+
+// -- ~SIZE_MIB of text lines, written once per run to the temp dir
+fn big_file() -> PathBuf {
+    let path = std::env::temp_dir().join(format!("catr-bench-{}MiB.txt", SIZE_MIB));
+    if fs::metadata(&path)
+        .map(|m| m.len() as usize >= SIZE_MIB << 20)
+        .unwrap_or(false)
+    {
+        return path;
+    }
+    let mut out = BufWriter::new(File::create(&path).unwrap());
+    let line = "The quick brown fox jumps over the lazy dog, again and again.\n";
+    for _ in 0..(SIZE_MIB << 20) / line.len() + 1 {
+        out.write_all(line.as_bytes()).unwrap();
+    }
+    out.flush().unwrap();
+    path
+}
+
+fn number(path: &PathBuf, out: &mut impl Write) {
+    let config = Config {
+        number_lines: true,
+        ..Config::default()
+    };
+    let input = BufReader::new(File::open(path).unwrap());
+    catr::cat(input, "-", &config, out).unwrap();
+    out.flush().unwrap();
+}
+
+fn bench_number(c: &mut Criterion) {
+    let path = big_file();
+    let null = || OpenOptions::new().write(true).open("/dev/null").unwrap();
+
+    let mut group = c.benchmark_group("number_lines");
+    group.throughput(Throughput::Bytes(fs::metadata(&path).unwrap().len()));
+    group.sample_size(10);
+
+    group.bench_function("line_buffered", |b| {
+        b.iter(|| number(&path, &mut LineWriter::new(null())))
+    });
+    group.bench_function("default_buffered", |b| {
+        b.iter(|| number(&path, &mut BufWriter::new(null())))
+    });
+    group.bench_function("buffered", |b| {
+        b.iter(|| number(&path, &mut BufWriter::with_capacity(64 * 1024, null())))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_number);
+criterion_main!(benches);
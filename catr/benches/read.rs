@@ -0,0 +1,65 @@
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use catr::Config;
+use ior::Input;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Write};
+use std::ops::Deref;
+use std::path::PathBuf;
+
+// -- `catr -n` on a large regular file read vs mapped, output thrown away
+// - buffered : cat() on a BufReader, the way catr reads every input
+// - mapped : the same cat() on the slice ior::open_input() would map
+// run with : cargo bench --bench read
+
+const SIZE_MIB: usize = 256;
+
+// -- ~SIZE_MIB of text lines, written once per run to the temp dir
+fn big_file() -> PathBuf {
+    let path = std::env::temp_dir().join(format!("catr-read-bench-{}MiB.txt", SIZE_MIB));
+    if fs::metadata(&path)
+        .map(|m| m.len() as usize >= SIZE_MIB << 20)
+        .unwrap_or(false)
+    {
+        return path;
+    }
+    let mut out = BufWriter::new(File::create(&path).unwrap());
+    let line = "The quick brown fox jumps over the lazy dog, again and again.\n";
+    for _ in 0..(SIZE_MIB << 20) / line.len() + 1 {
+        out.write_all(line.as_bytes()).unwrap();
+    }
+    out.flush().unwrap();
+    path
+}
+
+fn mapped(filename: &str) -> impl Deref<Target = [u8]> {
+    match ior::open_input(filename, false).unwrap() {
+        Input::Mapped(map) => map,
+        Input::Stream(_) => panic!("{} was not mapped", filename),
+    }
+}
+
+fn bench_read(c: &mut Criterion) {
+    let path = big_file();
+    let filename = path.to_str().unwrap();
+    let config = Config {
+        number_lines: true,
+        ..Config::default()
+    };
+
+    let mut group = c.benchmark_group("number_lines_read");
+    group.throughput(Throughput::Bytes(fs::metadata(&path).unwrap().len()));
+    group.sample_size(10);
+    group.bench_function("buffered", |b| {
+        b.iter(|| {
+            let input = BufReader::new(File::open(filename).unwrap());
+            catr::cat(input, filename, &config, &mut io::sink()).unwrap()
+        })
+    });
+    group.bench_function("mapped", |b| {
+        b.iter(|| catr::cat(&mapped(filename)[..], filename, &config, &mut io::sink()).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_read);
+criterion_main!(benches);
@@ -27,3 +27,42 @@ cat -b $ALL > ${OUT_DIR}/all.b.out
 cat    < $BUSTLE > $OUT_DIR/$(basename $BUSTLE).stdin.out
 cat -n < $BUSTLE > $OUT_DIR/$(basename $BUSTLE).stdin.n.out
 cat -b < $BUSTLE > $OUT_DIR/$(basename $BUSTLE).stdin.b.out
+
+# compressed copies : tests expect the same output as the plain files
+gzip -nc $FOX > $ROOT/fox.txt.gz
+xz -c $BUSTLE > $ROOT/the-bustle.txt.xz
+
+# no flags is a byte for byte copy : CRLF, no final newline
+printf 'one\r\ntwo' > $ROOT/crlf.txt
+cat $ROOT/crlf.txt > $OUT_DIR/crlf.txt.out
+
+# --hex matches xxd, a binary input covers every byte value
+BYTES="$ROOT/bytes.bin"
+printf "$(printf '\\%03o' $(seq 0 255))" > $BYTES
+xxd $FOX > $OUT_DIR/fox.txt.hex.out
+xxd -g 1 -c 10 $BUSTLE > $OUT_DIR/the-bustle.txt.hex.g1.c10.out
+xxd -s 5 -l 20 $SPIDER > $OUT_DIR/spiders.txt.hex.s5.l20.out
+xxd -g 0 $BYTES > $OUT_DIR/bytes.bin.hex.g0.out
+
+# --decorations has no cat equivalent : the grid fixtures are hand-kept,
+# drawn 40 columns wide (COLUMNS=40) so they don't depend on the terminal
+
+# --range / --from-regex keep each line's number from the whole file
+cat -n $BUSTLE | sed -n '2,3p' > $OUT_DIR/the-bustle.txt.range.2-3.n.out
+cat -b $BUSTLE | tail -n 4 > $OUT_DIR/the-bustle.txt.range.-4.b.out
+head -n -2 $BUSTLE > $OUT_DIR/the-bustle.txt.stdin.range.-3.out
+# /^Is/,/^The/ : lines 3 to 6
+cat -n $BUSTLE | sed -n '3,6p' > $OUT_DIR/the-bustle.txt.regex.n.out
+for FILE in $ALL; do sed -n 1p $FILE; done > $OUT_DIR/all.range.1.out
+
+# tacr matches GNU tac, a single character -r class so its backwards
+# regex search agrees with our forward one
+for FILE in $BUSTLE $SPIDER; do
+  BASENAME=$(basename "$FILE")
+  tac $FILE > ${OUT_DIR}/${BASENAME}.tac.out
+  tac -b $FILE > ${OUT_DIR}/${BASENAME}.tac.b.out
+  tac -r -s '[aeiou]' $FILE > ${OUT_DIR}/${BASENAME}.tac.r.out
+done
+tac -s e $BUSTLE > ${OUT_DIR}/the-bustle.txt.tac.s.out
+tac $ALL > ${OUT_DIR}/all.tac.out
+tac $ROOT/crlf.txt > ${OUT_DIR}/crlf.txt.tac.out
@@ -0,0 +1,14 @@
+use ior::Failure;
+
+fn main() {
+    // tacr shares catr's crate : same input layer (decompression, stdin),
+    // lines printed last to first, see catr::tac
+    if let Err(e) = catr::tac::get_args().and_then(catr::tac::run) {
+        // stdout closed early, exits like catr does
+        if e.is_broken_pipe() {
+            std::process::exit(ior::PipePolicy::from_env().exit_code());
+        }
+        eprintln!("{}", e);
+        std::process::exit(e.exit_code());
+    }
+}
@@ -0,0 +1,85 @@
+use std::io;
+use thiserror::Error;
+
+// == everything catr and tacr can fail with
+// - a file that fails to open is reported and skipped, not returned
+#[derive(Debug, Error)]
+pub enum Error {
+    // --group, --cols, --offset, --length
+    #[error("illegal {what} -- {value}")]
+    InvalidCount { what: &'static str, value: String },
+
+    #[error("illegal range -- {0}")]
+    InvalidRange(String),
+
+    // --from-regex, --to-regex, tacr -r -s
+    #[error("Invalid {flag} \"{pattern}\"")]
+    InvalidRegex {
+        flag: &'static str,
+        pattern: String,
+        source: regex::Error,
+    },
+
+    #[error("separator cannot be empty")]
+    EmptySeparator,
+
+    #[error("Failed to open {path}: {source}")]
+    OpenFailed { path: String, source: io::Error },
+
+    #[error("{path}: {source}")]
+    ReadFailed { path: String, source: io::Error },
+
+    // numbered, highlighted ... lines have to be text, a plain copy or
+    // --hex takes any bytes
+    #[error("{path}: stream did not contain valid UTF-8 at byte {offset}")]
+    InvalidUtf8 { path: String, offset: u64 },
+
+    // --hex --reverse input that isn't a dump
+    #[error("{path}: invalid hex dump, line {line}: {reason}")]
+    InvalidHexDump {
+        path: String,
+        line: usize,
+        reason: &'static str,
+    },
+
+    #[error("write error: {0}")]
+    WriteFailed(#[source] io::Error),
+}
+
+impl Error {
+    // -- a failed io::copy() doesn't say which side it was : a closed or
+    // full stdout shows up as one of these, anything else is the input's
+    pub fn copy_failed(path: &str, source: io::Error) -> Error {
+        match source.kind() {
+            io::ErrorKind::BrokenPipe | io::ErrorKind::WriteZero | io::ErrorKind::StorageFull => {
+                Error::WriteFailed(source)
+            }
+            _ => Error::ReadFailed {
+                path: path.to_string(),
+                source,
+            },
+        }
+    }
+}
+
+impl ior::Failure for Error {
+    fn exit_code(&self) -> i32 {
+        match self {
+            Error::InvalidCount { .. }
+            | Error::InvalidRange(_)
+            | Error::InvalidRegex { .. }
+            | Error::EmptySeparator => 2,
+            Error::OpenFailed { .. } => 3,
+            Error::ReadFailed { .. } => 4,
+            Error::InvalidUtf8 { .. } | Error::InvalidHexDump { .. } => 5,
+            Error::WriteFailed(_) => 6,
+        }
+    }
+
+    fn write_error(&self) -> Option<&io::Error> {
+        match self {
+            Error::WriteFailed(e) => Some(e),
+            _ => None,
+        }
+    }
+}
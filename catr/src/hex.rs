@@ -0,0 +1,236 @@
+use crate::{Error, MyResult};
+use std::io::{self, BufRead, Read, Write};
+
+// widest line xxd accepts, and what --cols is held to
+pub const MAX_COLS: usize = 256;
+
+// -- --hex options as parsed from the command line
+// - group : bytes per space separated group, 0 is one group per line
+// - cols : bytes per line
+// - offset / length : window of the input to dump, offsets in the left
+// column count from the start of the input, not the window
+#[derive(Debug, Clone)]
+pub struct HexConfig {
+    pub group: usize,
+    pub cols: usize,
+    pub offset: u64,
+    pub length: Option<u64>,
+}
+
+impl Default for HexConfig {
+    // -- xxd's defaults
+    fn default() -> HexConfig {
+        HexConfig {
+            group: 2,
+            cols: 16,
+            offset: 0,
+            length: None,
+        }
+    }
+}
+
+// == bytes -> xxd style dump
+// 00000000: 5468 6520 7175 6963 6b20 6272 6f77 6e20  The quick brown
+// - offset, hex bytes in groups, ASCII gutter with `.` for the unprintable
+// - a short last line is padded so the gutter stays in its column
+pub fn dump<R: Read, W: Write>(
+    input: R,
+    out: &mut W,
+    config: &HexConfig,
+    path: &str,
+) -> MyResult<()> {
+    let read_failed = |source| Error::ReadFailed {
+        path: path.to_string(),
+        source,
+    };
+    let mut input = input;
+    // stdin and pipes can't seek : read past the start offset instead
+    let skipped =
+        io::copy(&mut (&mut input).take(config.offset), &mut io::sink()).map_err(read_failed)?;
+    let mut input = input.take(config.length.unwrap_or(u64::MAX));
+
+    let group = match config.group {
+        0 => config.cols,
+        n => n,
+    };
+    // 2 digits a byte, a space after every group but the last
+    let width = config.cols * 2 + (config.cols - 1) / group;
+
+    let mut offset = skipped;
+    let mut buf = vec![0; config.cols];
+    let mut hex = String::with_capacity(width);
+    loop {
+        let n = fill(&mut input, &mut buf).map_err(read_failed)?;
+        if n == 0 {
+            return Ok(());
+        }
+        hex.clear();
+        for (i, byte) in buf[..n].iter().enumerate() {
+            if i > 0 && i % group == 0 {
+                hex.push(' ');
+            }
+            hex.push_str(&format!("{:02x}", byte));
+        }
+        let ascii: String = buf[..n]
+            .iter()
+            .map(|&b| match b {
+                0x20..=0x7e => b as char,
+                _ => '.',
+            })
+            .collect();
+        writeln!(out, "{:08x}: {:<width$}  {}", offset, hex, ascii).map_err(Error::WriteFailed)?;
+        offset += n as u64;
+    }
+}
+
+// -- read until `buf` is full or the input ends, a pipe can hand out
+// less than a line per read()
+fn fill<R: Read>(input: &mut R, buf: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match input.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
+
+// == xxd style dump -> bytes (--hex --reverse)
+// - the hex area ends at the two spaces before the gutter, so a gutter
+// that happens to read as hex ("dead beef") is not decoded
+// - a line whose offset is ahead of what was written is zero filled up to
+// it, like `xxd -r` writing to a pipe
+pub fn undump<R: BufRead, W: Write>(input: R, out: &mut W, path: &str) -> MyResult<()> {
+    let mut written: u64 = 0;
+    for (line_id, line) in input.lines().enumerate() {
+        let line = line.map_err(|source| Error::ReadFailed {
+            path: path.to_string(),
+            source,
+        })?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let invalid = |reason| Error::InvalidHexDump {
+            path: path.to_string(),
+            line: line_id + 1,
+            reason,
+        };
+
+        let (offset, rest) = line.split_once(':').ok_or_else(|| invalid("no offset"))?;
+        let offset =
+            u64::from_str_radix(offset.trim(), 16).map_err(|_| invalid("bad offset"))?;
+        let hex = rest.trim_start_matches(' ');
+        let hex = hex.split_once("  ").map_or(hex, |(hex, _)| hex);
+        let digits: Vec<u8> = hex.bytes().filter(|b| *b != b' ').collect();
+        if !digits.len().is_multiple_of(2) {
+            return Err(invalid("odd number of hex digits"));
+        }
+        let bytes = digits
+            .chunks(2)
+            .map(|pair| {
+                std::str::from_utf8(pair)
+                    .ok()
+                    .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                    .ok_or_else(|| invalid("bad hex byte"))
+            })
+            .collect::<MyResult<Vec<u8>>>()?;
+
+        if offset < written {
+            return Err(invalid("offset goes backwards"));
+        }
+        io::copy(&mut io::repeat(0).take(offset - written), out).map_err(Error::WriteFailed)?;
+        out.write_all(&bytes).map_err(Error::WriteFailed)?;
+        written = offset + bytes.len() as u64;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{dump, undump, HexConfig};
+    use crate::Error;
+
+    const FOX: &[u8] = b"The quick brown fox jumps over the lazy dog.\n";
+
+    fn dump_to_string(input: &[u8], config: &HexConfig) -> String {
+        let mut out = vec![];
+        dump(input, &mut out, config, "-").unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_dump() {
+        let text = dump_to_string(FOX, &HexConfig::default());
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(
+            lines[0],
+            "00000000: 5468 6520 7175 6963 6b20 6272 6f77 6e20  The quick brown "
+        );
+        assert_eq!(
+            lines[2],
+            "00000020: 6865 206c 617a 7920 646f 672e 0a         he lazy dog.."
+        );
+        assert_eq!(dump_to_string(b"", &HexConfig::default()), "");
+    }
+
+    #[test]
+    fn test_dump_window_and_layout() {
+        let config = HexConfig {
+            group: 3,
+            cols: 8,
+            offset: 4,
+            length: Some(10),
+        };
+        assert_eq!(
+            dump_to_string(FOX, &config),
+            "00000004: 717569 636b20 6272  quick br\n\
+             0000000c: 6f77                ow\n"
+        );
+        // group 0 : no spaces, unprintable bytes as `.`
+        let config = HexConfig {
+            group: 0,
+            cols: 4,
+            ..HexConfig::default()
+        };
+        assert_eq!(
+            dump_to_string(b"\x00\x7f\xff~", &config),
+            "00000000: 007fff7e  ...~\n"
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        for group in [0, 1, 2, 5] {
+            let config = HexConfig {
+                group,
+                cols: 7,
+                ..HexConfig::default()
+            };
+            let text = dump_to_string(&bytes, &config);
+            let mut out = vec![];
+            undump(text.as_bytes(), &mut out, "-").unwrap();
+            assert_eq!(out, bytes);
+        }
+    }
+
+    #[test]
+    fn test_undump_gaps_and_errors() {
+        // gutter reads as hex but is ignored, the gap is zero filled
+        let mut out = vec![];
+        undump(&b"00000002: 6162  dead beef\n"[..], &mut out, "-").unwrap();
+        assert_eq!(out, b"\0\0ab");
+
+        for bad in ["no offset here", "00000000: 616", "zz: 61", "00000000: 6g"] {
+            let err = undump(bad.as_bytes(), &mut vec![], "dump.txt").unwrap_err();
+            assert!(
+                matches!(err, Error::InvalidHexDump { line: 1, .. }),
+                "{}",
+                bad
+            );
+        }
+    }
+}
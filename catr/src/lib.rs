@@ -1,7 +1,19 @@
 use clap::{App, Arg};
-use std::error::Error;
-use std::fs::File;
-use std::io::{self, BufRead, BufReader};
+use ior::{Input, Stdout};
+use std::io::{self, BufRead, BufWriter, Write};
+
+mod error;
+mod hex;
+mod pretty;
+mod select;
+pub mod tac;
+
+pub use error::Error;
+pub use hex::HexConfig;
+use pretty::{Grid, Highlighter, Pager, When};
+use regex::Regex;
+pub use select::{Bound, Selection};
+use select::Selector;
 
 // == "channels" of an image
 // - r: number_lines
@@ -10,23 +22,108 @@ use std::io::{self, BufRead, BufReader};
 //  - so we switched it to line up with the green channel 
 //  because human vision is more indexed to green cones
 // - b: number_nonblank_lines
+// - built by get_args(), or by hand when catr is used as a library :
+// Config { number_lines: true, ..Config::default() }
 #[derive(Debug)]
 pub struct Config {
-    number_lines: bool,
-    files: Vec<String>,
-    number_nonblank_lines: bool,
+    pub number_lines: bool,
+    pub files: Vec<String>,
+    pub number_nonblank_lines: bool,
+    // decode gzip, bzip2, xz and zstd input (off with --no-decompress)
+    pub decompress: bool,
+    // --hex : dump bytes instead of lines
+    pub hex: Option<HexConfig>,
+    // --hex --reverse : turn a dump back into bytes
+    pub reverse: bool,
+    // --color / --decorations / --paging, auto already resolved against
+    // whether stdout is a terminal
+    pub color: bool,
+    pub decorations: bool,
+    pub paging: bool,
+    // --color / --decorations were only turned on by auto : input that
+    // isn't text is copied as it is rather than failing on bad UTF-8
+    pub sniff: bool,
+    // --range / --from-regex / --to-regex : print only these lines
+    pub selection: Option<Selection>,
+}
+
+impl Default for Config {
+    // -- plain cat of stdin
+    fn default() -> Config {
+        Config {
+            number_lines: false,
+            files: vec!["-".to_string()],
+            number_nonblank_lines: false,
+            decompress: true,
+            hex: None,
+            reverse: false,
+            color: false,
+            decorations: false,
+            paging: false,
+            sniff: false,
+            selection: None,
+        }
+    }
+}
+
+impl Config {
+    // -- no flag touches the lines : the bytes are copied as they are
+    fn passthrough(&self) -> bool {
+        !self.number_lines
+            && !self.number_nonblank_lines
+            && !self.color
+            && !self.decorations
+            && self.selection.is_none()
+    }
 }
 
 // == aliasing is a uniform way to manage "noise"
 // - where noise is analagous to errors
 // reduce verbosity of returning type and heap error address
-type MyResult<T> = Result<T, Box<dyn Error>>;
+type MyResult<T> = Result<T, Error>;
 
 // == akin to processing multiple image channels in a batch
 // - where each file it attempts to open and process
 // default all var and funcs are private
 // - using 'pub' here to grant main.rs visibility
 pub fn run(config: Config) -> MyResult<()> {
+    let mut err = io::stderr().lock();
+    // everything goes to stdout, or $PAGER with --paging, a buffer at a
+    // time rather than a write per line (but for a terminal)
+    match config.paging.then(Pager::spawn).flatten() {
+        Some(mut pager) => {
+            let result = pager.stdin().map_err(Error::WriteFailed).and_then(|stdin| {
+                let mut out = BufWriter::new(stdin);
+                run_with(&config, &mut out, &mut err)?;
+                out.flush().map_err(Error::WriteFailed)
+            });
+            pager.finish().map_err(Error::WriteFailed)?;
+            result?;
+        }
+        // matched on, not used as a Write : the plain copy needs the
+        // concrete type to splice
+        None => match ior::stdout() {
+            Stdout::Terminal(mut out) => {
+                run_with(&config, &mut out, &mut err)?;
+                out.flush().map_err(Error::WriteFailed)?;
+            }
+            Stdout::Buffered(mut out) => {
+                run_with(&config, &mut out, &mut err)?;
+                out.flush().map_err(Error::WriteFailed)?;
+            }
+        },
+    }
+    Ok(())
+}
+
+// -- run() with the output and the per-file errors going where the caller
+// says, e.g. into a Vec<u8>
+// - a file that fails to open is reported to `err` and skipped
+pub fn run_with<W: Write, E: Write>(config: &Config, out: &mut W, err: &mut E) -> MyResult<()> {
+    // loading the syntax definitions takes a moment, only do it for --color
+    // and when there are lines to color
+    let highlighter = (config.color && config.hex.is_none()).then(Highlighter::new);
+    let grid = Grid::new();
     // @udit-ok : why borrow?
     // ANSWER : iterating over filenames using a reference is typical
     // this avoids taking ownership unnecessarily
@@ -39,59 +136,280 @@ pub fn run(config: Config) -> MyResult<()> {
         // ANSWER : borrow @iteration is more idiomatic in Rust 
         // - more clarity that the value is being READ only, NO WRITE 
         // - good practice is to borrow at minimal scope necessary
-        match open(filename) {
-            // good form to eprint error to stderr
-            Err(err) => eprintln!("Failed to open {}: {}", filename, err),
+        if config.hex.is_none() && config.passthrough() {
+            // hand the bytes straight to `out`, see ior::Source for why this
+            // runs at disk speed
+            match ior::open_source(filename, config.decompress) {
+                Err(source) => report(
+                    err,
+                    &Error::OpenFailed {
+                        path: filename.to_string(),
+                        source,
+                    },
+                ),
+                Ok(source) => {
+                    source
+                        .copy_to(out)
+                        .map_err(|source| Error::copy_failed(filename, source))?;
+                }
+            }
+            continue;
+        }
+        match open(filename, config.decompress) {
+            Err(e) => report(err, &e),
             Ok(file) => {
-                let mut valid_line_id = 0; // skip if line is empty
-                // returning id and result from enumerate ~ perf diff
-                // - line_id : helps clarify when we want to print ALL line 
-                // number
-                for (line_id, line) in file.lines().enumerate(){
-                    // Unwrap once and store the value 
-                    // - error because calling line? a 2nd time tries to move 
-                    // a value that's no longer there
-                    // @udit-ok : shadowing is more Rustic, but is it more 
-                    // performant?
-                    // - reusing 'line' token reduces the naming table?
-                    //   - that would be bullshit tho, compiler should optimize
-                    //   that out!
-                    // - 'line_result' is arguably more explicit and readable
-                    // ANSWER : compiler is likely to optimize out diff in perf
-                    // with shadowing
-                    // ... but not GUARANTEED, so it's more Rustic to manually
-                    // shadow
-                    let line = line?;
-                    if config.number_lines{
-                        // {:>6} = text aligned to right with 6 characters
-                        // {:<6} = left justified
-                        // {:^6} = center justified
-                        println!("{:>6}\t{}", line_id+1, line);
-                    }
-                    else if config.number_nonblank_lines{
-                        // line.is_empty() == true if length = 0, false if 
-                        // there is whitespace tho
-                        // line.trim().is_empty() == true if only whitespace
-                        // (tabs...etc)
-                        if line.is_empty(){
-                            println!();
-                        }
-                        else{
-                            valid_line_id += 1;
-                            println!("{:>6}\t{}", valid_line_id, line);
-                        }
-                    }
-                    else{
-                        println!("{}", line);
-                    }
+                // a gutter as wide as the file's last line number, when
+                // there's a grid to draw
+                let grid = match config.decorations {
+                    true => grid.numbered_to(line_count(filename, config.decompress)),
+                    false => grid,
+                };
+                cat_with(file, filename, config, highlighter.as_ref(), &grid, out)?
+            }
+        }
+    }
+    Ok(())
+}
+
+// -- one input, everything run() does to a file : `filename` picks the
+// syntax for --color, heads the --decorations grid and names the input in
+// errors
+pub fn cat<R: BufRead, W: Write>(
+    input: R,
+    filename: &str,
+    config: &Config,
+    out: &mut W,
+) -> MyResult<()> {
+    let highlighter = (config.color && config.hex.is_none()).then(Highlighter::new);
+    cat_with(input, filename, config, highlighter.as_ref(), &Grid::new(), out)
+}
+
+// -- cat() with what's shared by every input of a run
+fn cat_with<R: BufRead, W: Write>(
+    mut file: R,
+    filename: &str,
+    config: &Config,
+    highlighter: Option<&Highlighter>,
+    grid: &Grid,
+    out: &mut W,
+) -> MyResult<()> {
+    if let Some(hex) = &config.hex {
+        return match config.reverse {
+            true => hex::undump(file, out, filename),
+            false => hex::dump(file, out, hex, filename),
+        };
+    }
+    if config.passthrough() || (config.sniff && !is_text(&mut file, filename)?) {
+        io::copy(&mut file, out).map_err(|source| Error::copy_failed(filename, source))?;
+        return Ok(());
+    }
+    // the first line picks the syntax of a script without an
+    // extension (#!/bin/sh), peeked at without consuming it
+    let mut highlight = match highlighter {
+        Some(highlighter) => {
+            let head = file.fill_buf().map_err(|source| Error::ReadFailed {
+                path: filename.to_string(),
+                source,
+            })?;
+            let first = head.split(|b| *b == b'\n').next().unwrap_or_default();
+            Some(highlighter.for_file(filename, &String::from_utf8_lossy(first)))
+        }
+        None => None,
+    };
+    // widened, past 9999 on a stream, as the numbers outgrow it
+    let mut grid = *grid;
+    if config.decorations {
+        grid.header(out, filename).map_err(Error::WriteFailed)?;
+    }
+    let mut valid_line_id = 0; // skip if line is empty
+    // --range / --from-regex : lines are numbered first, so the
+    // slice keeps the numbers they have in the whole file
+    let mut selector = config.selection.as_ref().map(Selector::new);
+    let mut selected = vec![];
+    let mut total = 0;
+    // returning id and result from enumerate ~ perf diff
+    // - line_id : helps clarify when we want to print ALL line 
+    // number
+    for (line_id, line) in Lines::new(file, filename).enumerate(){
+        // Unwrap once and store the value 
+        // - error because calling line? a 2nd time tries to move 
+        // a value that's no longer there
+        // @udit-ok : shadowing is more Rustic, but is it more 
+        // performant?
+        // - reusing 'line' token reduces the naming table?
+        //   - that would be bullshit tho, compiler should optimize
+        //   that out!
+        // - 'line_result' is arguably more explicit and readable
+        // ANSWER : compiler is likely to optimize out diff in perf
+        // with shadowing
+        // ... but not GUARANTEED, so it's more Rustic to manually
+        // shadow
+        let line = line?;
+        total = line_id + 1;
+        // None : this line gets no number
+        // - the grid numbers every line, like -n, unless -b
+        let number = if config.number_nonblank_lines {
+            // line.is_empty() == true if length = 0, false if 
+            // there is whitespace tho
+            // line.trim().is_empty() == true if only whitespace
+            // (tabs...etc)
+            match line.is_empty() {
+                true => None,
+                false => {
+                    valid_line_id += 1;
+                    Some(valid_line_id)
+                }
+            }
+        } else if config.number_lines || config.decorations {
+            Some(line_id + 1)
+        } else {
+            None
+        };
+        // every line is highlighted, selected or not, so the
+        // syntax state (an open comment ...) carries over
+        let colored = highlight.as_mut().map(|highlight| highlight.line(&line));
+        let line = Line { number, text: line, colored };
+        match &mut selector {
+            None => selected.push(line),
+            Some(selector) => {
+                if !selector.push(line_id + 1, line, &mut selected) {
+                    break;
                 }
             }
         }
+        for line in selected.drain(..) {
+            print_line(out, &mut grid, config.decorations, &line)?;
+        }
+    }
+    if let Some(selector) = selector {
+        selector.finish(total, &mut selected);
+    }
+    for line in selected.drain(..) {
+        print_line(out, &mut grid, config.decorations, &line)?;
+    }
+    if config.decorations {
+        grid.footer(out).map_err(Error::WriteFailed)?;
     }
-    dbg!(config);
     Ok(())
 }
 
+// -- lines in a plain regular file, counted on its mapping : sizes the
+// --decorations gutter before the first line is printed
+// - 0 for stdin, pipes and compressed files, which can't be counted
+// without reading them : their gutter starts at 4 digits and grows
+fn line_count(filename: &str, decompress: bool) -> usize {
+    let map = match ior::open_input(filename, false) {
+        Ok(Input::Mapped(map)) if filename != "-" => map,
+        _ => return 0,
+    };
+    if decompress && ior::Codec::detect(&map).is_some() {
+        return 0;
+    }
+    let newlines = map.iter().filter(|&&b| b == b'\n').count();
+    newlines + !map.ends_with(b"\n") as usize
+}
+
+// bytes looked at to tell text from binary, like git's 8000
+const SNIFF: usize = 8000;
+
+// -- text : the first SNIFF bytes have no NUL and are UTF-8, but for a
+// char cut off at the end
+fn is_text<R: BufRead>(file: &mut R, filename: &str) -> MyResult<bool> {
+    let head = file.fill_buf().map_err(|source| Error::ReadFailed {
+        path: filename.to_string(),
+        source,
+    })?;
+    let head = &head[..head.len().min(SNIFF)];
+    let utf8 = match std::str::from_utf8(head) {
+        Ok(_) => true,
+        Err(e) => e.error_len().is_none(),
+    };
+    Ok(utf8 && !head.contains(&0))
+}
+
+// -- the error sink is best effort, like eprintln! without the panic
+fn report<E: Write>(err: &mut E, e: &Error) {
+    let _ = writeln!(err, "{}", e);
+}
+
+// == one line on its way out : its number, the raw text and, with
+// --color, the highlighted text that gets printed instead
+struct Line {
+    number: Option<usize>,
+    text: String,
+    colored: Option<String>,
+}
+
+impl AsRef<str> for Line {
+    // -- what --from-regex / --to-regex match against
+    fn as_ref(&self) -> &str {
+        &self.text
+    }
+}
+
+fn print_line<W: Write>(out: &mut W, grid: &mut Grid, decorations: bool, line: &Line) -> MyResult<()> {
+    let text = line.colored.as_ref().unwrap_or(&line.text);
+    match (decorations, line.number) {
+        (true, number) => grid.line(out, number, text),
+        // {:>6} = text aligned to right with 6 characters
+        // {:<6} = left justified
+        // {:^6} = center justified
+        (false, Some(number)) => writeln!(out, "{:>6}\t{}", number, text),
+        (false, None) => writeln!(out, "{}", text),
+    }
+    .map_err(Error::WriteFailed)
+}
+
+// == BufRead::lines(), with errors that say which file and, for bad
+// UTF-8, where in it
+// - "\n" and "\r\n" are stripped the same way
+struct Lines<'a, R> {
+    input: R,
+    path: &'a str,
+    // bytes before the next line
+    offset: u64,
+}
+
+impl<'a, R: BufRead> Lines<'a, R> {
+    fn new(input: R, path: &'a str) -> Lines<'a, R> {
+        Lines {
+            input,
+            path,
+            offset: 0,
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for Lines<'_, R> {
+    type Item = MyResult<String>;
+
+    fn next(&mut self) -> Option<MyResult<String>> {
+        let mut buf = vec![];
+        let bytes = match self.input.read_until(b'\n', &mut buf) {
+            Ok(0) => return None,
+            Ok(bytes) => bytes,
+            Err(source) => {
+                return Some(Err(Error::ReadFailed {
+                    path: self.path.to_string(),
+                    source,
+                }))
+            }
+        };
+        let offset = self.offset;
+        self.offset += bytes as u64;
+        if buf.ends_with(b"\n") {
+            buf.pop();
+            if buf.ends_with(b"\r") {
+                buf.pop();
+            }
+        }
+        Some(String::from_utf8(buf).map_err(|e| Error::InvalidUtf8 {
+            path: self.path.to_string(),
+            offset: offset + e.utf8_error().valid_up_to() as u64,
+        }))
+    }
+}
+
 // == initializes color depth
 // - configures the options and flags of how output is rendered
 pub fn get_args() -> MyResult<Config> {
@@ -129,34 +447,330 @@ pub fn get_args() -> MyResult<Config> {
                 // this is a flag and does NOT take a value
                 .takes_value(false)
         )
+        .arg(
+            Arg::with_name("files0_from")
+                .long("files0-from")
+                .value_name("F")
+                .help("Read input file names from F, NUL separated (- for stdin)")
+                // the names come from F, not from FILE
+                .conflicts_with("files")
+        )
+        .arg(
+            Arg::with_name("no_decompress")
+                .long("no-decompress")
+                .help("Pass compressed input through undecoded")
+                .takes_value(false)
+        )
+        .arg(
+            Arg::with_name("hex")
+                .long("hex")
+                .help("Dump bytes as hex with an ASCII gutter, like xxd")
+                .takes_value(false)
+                .conflicts_with_all(&["line_numbers", "line_numbers_non_blank"])
+        )
+        .arg(
+            Arg::with_name("group")
+                .long("group")
+                .value_name("BYTES")
+                .help("[hex] Bytes per group, 0 for none [default: 2]")
+                .requires("hex")
+        )
+        .arg(
+            Arg::with_name("cols")
+                .long("cols")
+                .value_name("BYTES")
+                .help("[hex] Bytes per line [default: 16]")
+                .requires("hex")
+        )
+        .arg(
+            Arg::with_name("offset")
+                .long("offset")
+                .value_name("BYTES")
+                .help("[hex] Start dumping at this byte, decimal or 0x hex")
+                .requires("hex")
+        )
+        .arg(
+            Arg::with_name("length")
+                .long("length")
+                .value_name("BYTES")
+                .help("[hex] Stop after this many bytes, decimal or 0x hex")
+                .requires("hex")
+        )
+        .arg(
+            Arg::with_name("reverse")
+                .long("reverse")
+                .help("[hex] Convert a hex dump back into bytes")
+                .takes_value(false)
+                .requires("hex")
+                .conflicts_with_all(&["group", "cols", "offset", "length"])
+        )
+        .arg(
+            Arg::with_name("color")
+                .long("color")
+                .value_name("WHEN")
+                .help("Syntax highlight by file extension")
+                .possible_values(&["auto", "always", "never"])
+                .default_value("auto")
+        )
+        .arg(
+            Arg::with_name("decorations")
+                .long("decorations")
+                .value_name("WHEN")
+                .help("Frame each file with a header and numbered grid")
+                .possible_values(&["auto", "always", "never"])
+                .default_value("auto")
+        )
+        .arg(
+            Arg::with_name("paging")
+                .long("paging")
+                .value_name("WHEN")
+                .help("Page output through $PAGER (less -R -F -X)")
+                .possible_values(&["auto", "always", "never"])
+                .default_value("auto")
+        )
+        .arg(
+            Arg::with_name("range")
+                .long("range")
+                .alias("lines")
+                .value_name("START:END")
+                .help("Print lines START to END, 1 based, negative from the end, e.g. 10:20, -5:, :-2")
+                .allow_hyphen_values(true)
+                .conflicts_with_all(&["hex", "from_regex", "to_regex"])
+        )
+        .arg(
+            Arg::with_name("from_regex")
+                .long("from-regex")
+                .value_name("PAT")
+                .help("Print from each line matching PAT ...")
+                .conflicts_with("hex")
+        )
+        .arg(
+            Arg::with_name("to_regex")
+                .long("to-regex")
+                .value_name("PAT")
+                .help("... up to the next line matching PAT")
+                .conflicts_with("hex")
+        )
         .get_matches();
+
+    // [range] a bad pattern is reported with the flag it came from
+    let regex = |name: &str, flag: &'static str| {
+        matches
+            .value_of(name)
+            .map(|pat| {
+                Regex::new(pat).map_err(|source| Error::InvalidRegex {
+                    flag,
+                    pattern: pat.to_string(),
+                    source,
+                })
+            })
+            .transpose()
+    };
+    let from = regex("from_regex", "--from-regex")?;
+    let to = regex("to_regex", "--to-regex")?;
+    let selection = match matches.value_of("range") {
+        Some(val) => Some(Selection::parse_range(val).ok_or_else(|| Error::InvalidRange(val.to_string()))?),
+        None if from.is_some() || to.is_some() => Some(Selection::Regex { from, to }),
+        None => None,
+    };
+
+    // possible_values() already rejected anything When can't parse
+    let when = |name| {
+        matches
+            .value_of(name)
+            .and_then(When::parse)
+            .unwrap_or(When::Auto)
+    };
+    let (color, decorations) = (when("color"), when("decorations"));
+    let sniff = [color, decorations].contains(&When::Auto) && When::Auto.enabled();
+
+    // [hex] options only matter with --hex
+    let defaults = HexConfig::default();
+    let group = matches
+        .value_of("group")
+        .map(|val| val.parse().map_err(|_| invalid_count("group size", val)))
+        .transpose()?
+        .unwrap_or(defaults.group);
+    let cols = matches
+        .value_of("cols")
+        .map(|val| match val.parse() {
+            Ok(n) if n > 0 && n <= hex::MAX_COLS => Ok(n),
+            _ => Err(invalid_count("column count", val)),
+        })
+        .transpose()?
+        .unwrap_or(defaults.cols);
+    let offset = matches
+        .value_of("offset")
+        .map(|val| parse_size(val).map_err(|_| invalid_count("offset", val)))
+        .transpose()?
+        .unwrap_or(defaults.offset);
+    let length = matches
+        .value_of("length")
+        .map(|val| parse_size(val).map_err(|_| invalid_count("length", val)))
+        .transpose()?;
+    let hex = match matches.is_present("hex") {
+        true => Some(HexConfig {
+            group,
+            cols,
+            offset,
+            length,
+        }),
+        false => None,
+    };
     
-    Ok(Config{
+    // [files0-from] `findr --name '\.rs$' -print0 | catr --files0-from -`
+    let files = match matches.value_of("files0_from") {
+        Some(list) => {
+            let reader = open(list, false)?;
+            let names = ior::read_file_list(reader).map_err(|source| Error::ReadFailed {
+                path: list.to_string(),
+                source,
+            })?;
+            ior::utf8_file_names(names, &mut io::stderr())
+        }
         // because there's a default value, it should be safe to call unwrap()
-        files: matches.values_of_lossy("files").unwrap(),
+        None => matches.values_of_lossy("files").unwrap(),
+    };
+
+    Ok(Config{
+        files,
         number_lines: matches.is_present("line_numbers"),
-        number_nonblank_lines: matches.is_present("line_numbers_non_blank")
+        number_nonblank_lines: matches.is_present("line_numbers_non_blank"),
+        decompress: !matches.is_present("no_decompress"),
+        hex,
+        reverse: matches.is_present("reverse"),
+        color: color.enabled(),
+        decorations: decorations.enabled(),
+        paging: when("paging").enabled(),
+        sniff,
+        selection,
     })
 }
 
 // == handles input source
 // - either live stream (stdin) or from disc (file)
-fn open(filename: &str) -> MyResult<Box<dyn BufRead>> {
-    match filename {
-        // when filename is "-"
-        // - read from stdin()
-        "-" => Ok(
-                    Box::new(
-                        BufReader::new(io::stdin())
-                    )
-                ),
-        // else try to open given filename
-        _ => Ok(
-                Box::new(
-                    BufReader::new(
-                        File::open(filename)?
-                    )
-                )
-            ),
+// - compressed input is decoded on the fly, see ior::open()
+fn open(filename: &str, decompress: bool) -> MyResult<Box<dyn BufRead>> {
+    ior::open(filename, decompress).map_err(|source| Error::OpenFailed {
+        path: filename.to_string(),
+        source,
+    })
+}
+
+fn invalid_count(what: &'static str, val: &str) -> Error {
+    Error::InvalidCount {
+        what,
+        value: val.to_string(),
+    }
+}
+
+// -- byte counts for --offset / --length, decimal or 0x prefixed hex
+fn parse_size(val: &str) -> Result<u64, std::num::ParseIntError> {
+    match val.strip_prefix("0x").or_else(|| val.strip_prefix("0X")) {
+        Some(digits) => u64::from_str_radix(digits, 16),
+        None => val.parse(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cat, line_count, run_with, Config, Error, HexConfig, Selection};
+    use std::io::{Cursor, Write};
+
+    fn cat_str(input: &str, config: &Config) -> String {
+        let mut out = vec![];
+        cat(Cursor::new(input), "-", config, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_cat() {
+        let text = "a\r\n\nb";
+        // no flags : byte for byte
+        assert_eq!(cat_str(text, &Config::default()), text);
+        let config = Config {
+            number_nonblank_lines: true,
+            ..Config::default()
+        };
+        assert_eq!(cat_str(text, &config), "     1\ta\n\n     2\tb\n");
+        let config = Config {
+            number_lines: true,
+            selection: Selection::parse_range("-2:"),
+            ..Config::default()
+        };
+        assert_eq!(cat_str(text, &config), "     2\t\n     3\tb\n");
+        // decorations that auto turned on : binary input is copied as is
+        let config = Config {
+            decorations: true,
+            sniff: true,
+            ..Config::default()
+        };
+        let binary = b"\x7fELF\x02\x01\0\0\xff\n";
+        let mut out = vec![];
+        cat(Cursor::new(binary), "-", &config, &mut out).unwrap();
+        assert_eq!(out, binary);
+        assert!(cat_str("caf\u{e9}\n", &config).contains("│ caf\u{e9}\n"));
+        let config = Config {
+            hex: Some(HexConfig::default()),
+            ..Config::default()
+        };
+        assert_eq!(cat_str("hi", &config), "00000000: 6869                                     hi\n");
+
+        // numbered lines have to be text
+        let config = Config {
+            number_lines: true,
+            ..Config::default()
+        };
+        let res = cat(&b"ok\n\xff"[..], "bin", &config, &mut vec![]);
+        assert!(matches!(res, Err(Error::InvalidUtf8 { offset: 3, .. })));
+    }
+
+    #[test]
+    fn test_grid_width() {
+        let config = Config {
+            decorations: true,
+            ..Config::default()
+        };
+        let text: String = (1..=10000).map(|n| format!("{}\n", n)).collect();
+        // a stream starts at 4 digits and widens for line 10000
+        let out = cat_str(&text, &config);
+        assert!(out.contains("\n9999   │ 9999\n────────┼─"));
+        assert!(out.contains("\n10000   │ 10000\n────────┴─"));
+        // a file is counted up front : 5 digits from line 1
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(text.as_bytes()).unwrap();
+        let config = Config {
+            files: vec![file.path().display().to_string()],
+            ..config
+        };
+        let (mut out, mut err) = (vec![], vec![]);
+        run_with(&config, &mut out, &mut err).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("\n    1   │ 1\n"));
+        assert_eq!(out.matches('┼').count(), 1);
+        assert_eq!(line_count(&config.files[0], false), 10000);
+        assert_eq!(line_count("-", false), 0);
+    }
+
+    #[test]
+    fn test_run_with() {
+        // a missing file is reported to the error sink, the rest printed
+        let config = Config {
+            files: vec![
+                "no-such-file".to_string(),
+                "tests/inputs/fox.txt".to_string(),
+            ],
+            number_lines: true,
+            ..Config::default()
+        };
+        let (mut out, mut err) = (vec![], vec![]);
+        run_with(&config, &mut out, &mut err).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "     1\tThe quick brown fox jumps over the lazy dog.\n"
+        );
+        assert!(String::from_utf8(err)
+            .unwrap()
+            .starts_with("Failed to open no-such-file"));
     }
 }
@@ -1,12 +1,20 @@
+use ior::Failure;
+
 fn main() {
     // try to execute lib.rs run function
     // @audit : explain modules ... is src/lib.rs an implicit constant?
     // ANSWER : lib/run() is accessed through `catr` the project crate
     if let Err(e) = catr::get_args().and_then(catr::run) {
+        // the reader went away (`catr huge.log | headr -n 1`) : a clean
+        // early exit, 0 or 141 per $SIGPIPE_STATUS
+        if e.is_broken_pipe() {
+            std::process::exit(ior::PipePolicy::from_env().exit_code());
+        }
         // use error print line to print error message to STDERR
         eprintln!("{}", e);
         // Exit program with a nonzero value to indicate an error
         // @audit : Explain why this is important for command line tools
-        std::process::exit(1);
+        // - each kind of error has its own value, see Error::exit_code()
+        std::process::exit(e.exit_code());
     }
 }
@@ -0,0 +1,337 @@
+use std::env;
+use std::io::{self, IsTerminal, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+
+// == bat like output for reading code on a terminal
+// - --color : syntax highlighting picked from the file extension
+// - --decorations : a header with the file name and a numbered grid
+// - --paging : everything goes through $PAGER
+// - each defaults to auto, on only when stdout is a terminal, so piped
+// output stays byte for byte what cat prints; auto also leaves input that
+// isn't text as it is (see is_text() in lib.rs)
+// - --decorations sizes the gutter from a file's line count before it
+// starts; on a stream it starts at 4 digits and widens, with a ┼ rule, when
+// a number outgrows it
+
+// -- auto | always | never, as taken by --color, --decorations, --paging
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum When {
+    Auto,
+    Always,
+    Never,
+}
+
+impl When {
+    pub fn parse(val: &str) -> Option<When> {
+        match val {
+            "auto" => Some(When::Auto),
+            "always" => Some(When::Always),
+            "never" => Some(When::Never),
+            _ => None,
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        match self {
+            When::Auto => io::stdout().is_terminal(),
+            When::Always => true,
+            When::Never => false,
+        }
+    }
+}
+
+// == --paging : output goes to the stdin of $PAGER instead of stdout
+pub struct Pager {
+    child: Child,
+}
+
+impl Pager {
+    // -- None when the pager fails to start, output stays on stdout
+    pub fn spawn() -> Option<Pager> {
+        spawn_pager().map(|child| Pager { child })
+    }
+
+    // -- a concrete ChildStdin, not a Box<dyn Write>, so io::copy() can
+    // still splice into it (see ior::Source)
+    pub fn stdin(&mut self) -> io::Result<&mut ChildStdin> {
+        self.child
+            .stdin
+            .as_mut()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::BrokenPipe, "pager closed"))
+    }
+
+    // -- close the pager's stdin and wait for it, otherwise the shell
+    // prompt comes back while it's still on screen
+    pub fn finish(mut self) -> io::Result<()> {
+        drop(self.child.stdin.take());
+        self.child.wait().map(drop)
+    }
+}
+
+// -- $PAGER, split on whitespace, `less -R -F -X` when unset
+// - a bare `less` gets the same flags : -R to show colors, -F to exit
+// when everything fits on one screen, -X to leave it there
+fn spawn_pager() -> Option<Child> {
+    let pager = env::var("PAGER")
+        .ok()
+        .filter(|pager| !pager.trim().is_empty())
+        .unwrap_or_else(|| "less".to_string());
+    let mut words = pager.split_whitespace();
+    let program = words.next()?;
+    let mut args: Vec<&str> = words.collect();
+    if program.ends_with("less") && args.is_empty() {
+        args = vec!["-R", "-F", "-X"];
+    }
+    Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .spawn()
+        .ok()
+}
+
+// == header and numbered grid around each file, drawn like bat
+// ───────┬──────────────────
+//        │ File: fox.txt
+// ───────┼──────────────────
+//    1   │ The quick brown fox
+// ───────┴──────────────────
+#[derive(Clone, Copy)]
+pub struct Grid {
+    width: usize,
+    // columns for the line number, right aligned : at least 4
+    digits: usize,
+}
+
+// columns left of the │ besides the number : the padding after it
+const PADDING: usize = 3;
+const DIGITS: usize = 4;
+
+impl Grid {
+    // -- as wide as the terminal, $COLUMNS or 80 when stdout isn't one
+    pub fn new() -> Grid {
+        let width = terminal_size::terminal_size()
+            .map(|(width, _)| width.0 as usize)
+            .or_else(|| env::var("COLUMNS").ok()?.parse().ok())
+            .unwrap_or(80);
+        Grid { width, digits: DIGITS }
+    }
+
+    // -- the same grid with a gutter wide enough for `last`, the highest
+    // line number it will hold, so the │ stays in one column past 9999
+    // - 0 : not known, 4 digits
+    pub fn numbered_to(&self, last: usize) -> Grid {
+        Grid {
+            digits: last.to_string().len().max(DIGITS),
+            ..*self
+        }
+    }
+
+    fn gutter(&self) -> usize {
+        self.digits + PADDING
+    }
+
+    pub fn header<W: Write>(&self, out: &mut W, filename: &str) -> io::Result<()> {
+        let name = match filename {
+            "-" => "STDIN",
+            _ => filename,
+        };
+        writeln!(out, "{}", self.rule('┬'))?;
+        writeln!(out, "{:gutter$}│ File: {}", "", name, gutter = self.gutter())?;
+        writeln!(out, "{}", self.rule('┼'))
+    }
+
+    // -- None leaves the gutter empty (blank lines with -b)
+    // - a number too wide for the gutter widens it, below a new rule
+    pub fn line<W: Write>(&mut self, out: &mut W, number: Option<usize>, text: &str) -> io::Result<()> {
+        if let Some(number) = number.filter(|n| n.to_string().len() > self.digits) {
+            *self = self.numbered_to(number);
+            writeln!(out, "{}", self.rule('┼'))?;
+        }
+        match number {
+            Some(number) => writeln!(
+                out,
+                "{:>digits$}{:PADDING$}│ {}",
+                number,
+                "",
+                text,
+                digits = self.digits
+            ),
+            None => writeln!(out, "{:gutter$}│ {}", "", text, gutter = self.gutter()),
+        }
+    }
+
+    pub fn footer<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        writeln!(out, "{}", self.rule('┴'))
+    }
+
+    fn rule(&self, joint: char) -> String {
+        let gutter = self.gutter();
+        format!(
+            "{}{}{}",
+            "─".repeat(gutter),
+            joint,
+            "─".repeat(self.width.max(gutter + 2) - gutter - 1)
+        )
+    }
+}
+
+// == syntax highlighting, 24 bit terminal colors
+// - the syntax comes from the file extension, a compression suffix
+// (.gz, .xz ...) is looked through, then from the first line (#!), and
+// falls back to plain text
+#[cfg(feature = "highlight")]
+pub use highlight::Highlighter;
+
+#[cfg(feature = "highlight")]
+mod highlight {
+    use std::path::Path;
+    use syntect::easy::HighlightLines;
+    use syntect::highlighting::{Theme, ThemeSet};
+    use syntect::parsing::{SyntaxReference, SyntaxSet};
+    use syntect::util::as_24_bit_terminal_escaped;
+
+    const THEME: &str = "base16-ocean.dark";
+    const CODEC_EXTENSIONS: [&str; 4] = ["gz", "bz2", "xz", "zst"];
+
+    pub struct Highlighter {
+        syntaxes: SyntaxSet,
+        theme: Theme,
+    }
+
+    pub struct FileHighlighter<'a> {
+        syntaxes: &'a SyntaxSet,
+        lines: HighlightLines<'a>,
+    }
+
+    impl Highlighter {
+        pub fn new() -> Highlighter {
+            let mut themes = ThemeSet::load_defaults();
+            Highlighter {
+                syntaxes: SyntaxSet::load_defaults_newlines(),
+                theme: themes.themes.remove(THEME).unwrap_or_default(),
+            }
+        }
+
+        pub fn for_file(&self, filename: &str, first_line: &str) -> FileHighlighter<'_> {
+            FileHighlighter {
+                syntaxes: &self.syntaxes,
+                lines: HighlightLines::new(self.syntax(filename, first_line), &self.theme),
+            }
+        }
+
+        fn syntax(&self, filename: &str, first_line: &str) -> &SyntaxReference {
+            let mut path = Path::new(filename);
+            if path
+                .extension()
+                .is_some_and(|ext| CODEC_EXTENSIONS.iter().any(|codec| ext == *codec))
+            {
+                path = Path::new(path.file_stem().unwrap_or_default());
+            }
+            path.extension()
+                .and_then(|ext| self.syntaxes.find_syntax_by_extension(ext.to_str()?))
+                .or_else(|| self.syntaxes.find_syntax_by_first_line(first_line))
+                .unwrap_or_else(|| self.syntaxes.find_syntax_plain_text())
+        }
+    }
+
+    impl FileHighlighter<'_> {
+        // -- one line without its newline, colored and reset at the end
+        // - a line the grammar chokes on is passed through uncolored
+        pub fn line(&mut self, line: &str) -> String {
+            let line = format!("{}\n", line);
+            match self.lines.highlight_line(&line, self.syntaxes) {
+                Ok(ranges) => {
+                    let colored = as_24_bit_terminal_escaped(&ranges, false);
+                    format!("{}\x1b[0m", colored.trim_end_matches('\n'))
+                }
+                Err(_) => line.trim_end_matches('\n').to_string(),
+            }
+        }
+    }
+}
+
+// -- without the `highlight` feature --color leaves lines as they are
+#[cfg(not(feature = "highlight"))]
+pub struct Highlighter;
+
+#[cfg(not(feature = "highlight"))]
+pub struct FileHighlighter;
+
+#[cfg(not(feature = "highlight"))]
+impl Highlighter {
+    pub fn new() -> Highlighter {
+        Highlighter
+    }
+
+    pub fn for_file(&self, _filename: &str, _first_line: &str) -> FileHighlighter {
+        FileHighlighter
+    }
+}
+
+#[cfg(not(feature = "highlight"))]
+impl FileHighlighter {
+    pub fn line(&mut self, line: &str) -> String {
+        line.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Grid, When};
+
+    #[test]
+    fn test_when() {
+        assert_eq!(When::parse("always"), Some(When::Always));
+        assert_eq!(When::parse("sometimes"), None);
+        assert!(When::Always.enabled());
+        assert!(!When::Never.enabled());
+    }
+
+    #[test]
+    fn test_grid() {
+        let mut grid = Grid { width: 12, digits: 4 };
+        let mut out = vec![];
+        grid.header(&mut out, "-").unwrap();
+        grid.line(&mut out, Some(12), "x").unwrap();
+        grid.line(&mut out, None, "").unwrap();
+        grid.footer(&mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "───────┬────\n       │ File: STDIN\n───────┼────\n  12   │ x\n       │ \n───────┴────\n"
+        );
+        // sized up front for 12345 lines, the │ lines up from the start
+        let mut sized = grid.numbered_to(12345);
+        let mut out = vec![];
+        sized.header(&mut out, "-").unwrap();
+        sized.line(&mut out, Some(9), "x").unwrap();
+        sized.line(&mut out, Some(12345), "y").unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "────────┬───\n        │ File: STDIN\n────────┼───\n    9   │ x\n12345   │ y\n"
+        );
+        // a stream : the gutter widens under a new rule once 9999 is passed
+        let mut stream = Grid { width: 12, digits: 4 };
+        let mut out = vec![];
+        stream.line(&mut out, Some(9999), "x").unwrap();
+        stream.line(&mut out, Some(10000), "y").unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "9999   │ x\n────────┼───\n10000   │ y\n"
+        );
+    }
+
+    #[cfg(feature = "highlight")]
+    #[test]
+    fn test_highlight() {
+        let highlighter = super::Highlighter::new();
+        // .rs through the .gz suffix : `fn` is colored, plain text is not
+        let mut rust = highlighter.for_file("src/lib.rs.gz", "");
+        assert!(rust.line("fn main() {}").starts_with("\x1b[38;2;"));
+        let mut text = highlighter.for_file("notes.txt", "");
+        let line = text.line("fn main() {}");
+        assert!(line.contains("fn main() {}"));
+        // a #! line picks the syntax when the name doesn't
+        let mut shell = highlighter.for_file("-", "#!/bin/sh");
+        assert_ne!(shell.line("echo hi"), text.line("echo hi"));
+    }
+}
@@ -0,0 +1,238 @@
+use regex::Regex;
+use std::collections::VecDeque;
+
+// == which lines of each file to print (--range, --from-regex / --to-regex)
+// - line numbers are the file's own, so -n / -b number the slice the way
+// they'd number the whole file
+#[derive(Debug, Clone)]
+pub enum Selection {
+    Range(Bound, Bound),
+    // like sed -n '/FROM/,/TO/p' : every block from a FROM match to the
+    // next TO match, both lines included
+    Regex {
+        from: Option<Regex>,
+        to: Option<Regex>,
+    },
+}
+
+// -- one end of a --range, Open when left out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bound {
+    Open,
+    // 1 based line number
+    Line(usize),
+    // counted from the last line, 1 is the last line
+    FromEnd(usize),
+}
+
+impl Selection {
+    // -- START:END, START: or :END, either end negative to count from the
+    // end, a single N is just line N
+    pub fn parse_range(val: &str) -> Option<Selection> {
+        let (start, end) = match val.split_once(':') {
+            Some((start, end)) => (parse_bound(start)?, parse_bound(end)?),
+            None => {
+                let line = parse_bound(val)?;
+                (line, line)
+            }
+        };
+        match (start, end) {
+            (Bound::Open, Bound::Open) if !val.contains(':') => None,
+            _ => Some(Selection::Range(start, end)),
+        }
+    }
+}
+
+fn parse_bound(val: &str) -> Option<Bound> {
+    if val.is_empty() {
+        return Some(Bound::Open);
+    }
+    let (from_end, digits) = match val.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, val),
+    };
+    match (digits.parse().ok()?, from_end) {
+        (0, _) => None,
+        (n, true) => Some(Bound::FromEnd(n)),
+        (n, false) => Some(Bound::Line(n)),
+    }
+}
+
+// == picks the selected lines of one file as they're read
+// - items are whatever the caller prints, as_ref() the raw line text the
+// regexes are matched against
+// - a FromEnd start keeps the last k lines until the end shows which they
+// are, a FromEnd end holds back the lines that may turn out to be past it :
+// memory stays bounded by the count, not the file
+pub struct Selector<'a, T> {
+    selection: &'a Selection,
+    pending: VecDeque<(usize, T)>,
+    // --from-regex / --to-regex : inside a block
+    active: bool,
+    // --to-regex alone : the one block has ended
+    done: bool,
+}
+
+impl<'a, T: AsRef<str>> Selector<'a, T> {
+    pub fn new(selection: &'a Selection) -> Selector<'a, T> {
+        Selector {
+            selection,
+            pending: VecDeque::new(),
+            active: false,
+            done: false,
+        }
+    }
+
+    // -- line `line_no` (1 based) was read : anything now known to be
+    // selected goes to `out`, false once no later line can be
+    pub fn push(&mut self, line_no: usize, item: T, out: &mut Vec<T>) -> bool {
+        match self.selection {
+            Selection::Range(start, end) => {
+                if matches!(*start, Bound::Line(s) if line_no < s) {
+                    return true;
+                }
+                if matches!(*end, Bound::Line(e) if line_no > e) {
+                    // a FromEnd start still needs to count to the end
+                    return matches!(*start, Bound::FromEnd(_));
+                }
+                match (*start, *end) {
+                    (Bound::FromEnd(k), _) => {
+                        self.pending.push_back((line_no, item));
+                        if self.pending.len() > k {
+                            self.pending.pop_front();
+                        }
+                    }
+                    (_, Bound::FromEnd(m)) => {
+                        self.pending.push_back((line_no, item));
+                        while self.pending.len() > m - 1 {
+                            out.extend(self.pending.pop_front().map(|(_, item)| item));
+                        }
+                    }
+                    _ => out.push(item),
+                }
+                true
+            }
+            Selection::Regex { from, to } => {
+                if self.done {
+                    return false;
+                }
+                let starts = match from {
+                    Some(from) => !self.active && from.is_match(item.as_ref()),
+                    // no --from-regex : the block starts on line 1
+                    None => line_no == 1,
+                };
+                if !self.active && !starts {
+                    return true;
+                }
+                // the TO match is looked for from the line after FROM
+                let ends = !starts && to.as_ref().is_some_and(|to| to.is_match(item.as_ref()));
+                out.push(item);
+                self.active = !ends;
+                self.done = ends && from.is_none();
+                true
+            }
+        }
+    }
+
+    // -- the file ended after `total` lines, all of them read unless
+    // push() returned false
+    pub fn finish(mut self, total: usize, out: &mut Vec<T>) {
+        if let Selection::Range(Bound::FromEnd(k), end) = self.selection {
+            let first = (total + 1).saturating_sub(*k);
+            let last = match *end {
+                Bound::Open => total,
+                Bound::Line(e) => e,
+                Bound::FromEnd(m) => (total + 1).saturating_sub(m),
+            };
+            out.extend(
+                self.pending
+                    .drain(..)
+                    .filter(|(line_no, _)| (first..=last).contains(line_no))
+                    .map(|(_, item)| item),
+            );
+        }
+        // a FromEnd end : whatever is still held back is past it
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Bound, Selection, Selector};
+    use regex::Regex;
+
+    const LINES: [&str; 6] = ["a", "start", "b", "stop", "start", "c"];
+
+    // -- a line and its number
+    struct Item(usize, &'static str);
+
+    impl AsRef<str> for Item {
+        fn as_ref(&self) -> &str {
+            self.1
+        }
+    }
+
+    // -- selected line numbers
+    fn select(selection: &Selection) -> Vec<usize> {
+        let mut selector = Selector::new(selection);
+        let mut out = vec![];
+        for (i, line) in LINES.iter().enumerate() {
+            if !selector.push(i + 1, Item(i + 1, line), &mut out) {
+                break;
+            }
+        }
+        selector.finish(LINES.len(), &mut out);
+        out.iter().map(|item| item.0).collect()
+    }
+
+    fn range(val: &str) -> Vec<usize> {
+        select(&Selection::parse_range(val).unwrap())
+    }
+
+    fn regex(from: Option<&str>, to: Option<&str>) -> Vec<usize> {
+        select(&Selection::Regex {
+            from: from.map(|pat| Regex::new(pat).unwrap()),
+            to: to.map(|pat| Regex::new(pat).unwrap()),
+        })
+    }
+
+    #[test]
+    fn test_parse_range() {
+        assert!(matches!(
+            Selection::parse_range("2:-3"),
+            Some(Selection::Range(Bound::Line(2), Bound::FromEnd(3)))
+        ));
+        assert!(matches!(
+            Selection::parse_range(":"),
+            Some(Selection::Range(Bound::Open, Bound::Open))
+        ));
+        for bad in ["", "0", "1:0", "x:2", "1:2:3", "--1"] {
+            assert!(Selection::parse_range(bad).is_none(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_range() {
+        assert_eq!(range("2:4"), vec![2, 3, 4]);
+        assert_eq!(range("5"), vec![5]);
+        assert_eq!(range("5:"), vec![5, 6]);
+        assert_eq!(range(":2"), vec![1, 2]);
+        assert_eq!(range("-2:"), vec![5, 6]);
+        assert_eq!(range(":-5"), vec![1, 2]);
+        assert_eq!(range("-4:-2"), vec![3, 4, 5]);
+        assert_eq!(range("-4:4"), vec![3, 4]);
+        assert_eq!(range("2:-1"), vec![2, 3, 4, 5, 6]);
+        assert_eq!(range("4:2"), Vec::<usize>::new());
+        assert_eq!(range("-10:"), vec![1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn test_regex() {
+        // every block, like sed
+        assert_eq!(regex(Some("start"), Some("stop")), vec![2, 3, 4, 5, 6]);
+        assert_eq!(regex(Some("^b"), None), vec![3, 4, 5, 6]);
+        assert_eq!(regex(None, Some("stop")), vec![1, 2, 3, 4]);
+        // TO is only looked for after FROM
+        assert_eq!(regex(Some("st"), Some("st")), vec![2, 3, 4, 5, 6]);
+        assert_eq!(regex(Some("nope"), Some("stop")), Vec::<usize>::new());
+    }
+}
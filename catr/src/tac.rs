@@ -0,0 +1,339 @@
+use crate::{Error, MyResult};
+use clap::{App, Arg};
+use ior::Source;
+use regex::bytes::Regex;
+use std::fs::File;
+use std::io::{self, BufReader, Cursor, Read, Seek, SeekFrom, Write};
+
+// == tacr : catr's lines, last one first
+// - records end with the separator (newline by default), or start with
+// it with -b, and are printed as they are : a last line without a
+// newline stays glued to the one printed after it, like GNU tac
+// - regular files are read backwards a block at a time, stdin, pipes and
+// compressed input are spooled first, to a temp file past MEM_LIMIT
+
+// bytes read per seek
+const BLOCK: u64 = 64 * 1024;
+// the longest match assumed of a -r separator that can match any length
+const UNBOUNDED: usize = 64 * 1024;
+// stdin held in memory up to this, a temp file beyond it
+const MEM_LIMIT: usize = 8 * 1024 * 1024;
+
+#[derive(Debug)]
+pub struct TacConfig {
+    files: Vec<String>,
+    // -s SEP, escaped unless -r
+    separator: Regex,
+    // -b : the separator goes before each record
+    before: bool,
+    decompress: bool,
+}
+
+pub fn run(config: TacConfig) -> MyResult<()> {
+    let mut out = ior::stdout();
+    run_with(&config, &mut out, &mut io::stderr())?;
+    out.flush().map_err(Error::WriteFailed)
+}
+
+// -- run() with the output and the per-file errors going where the caller
+// says, like catr::run_with()
+// - a file that fails to open is reported to `err` and skipped
+pub fn run_with<W: Write, E: Write>(config: &TacConfig, out: &mut W, err: &mut E) -> MyResult<()> {
+    let (sep, before) = (&config.separator, config.before);
+    for filename in &config.files {
+        match ior::open_source(filename, config.decompress) {
+            Err(source) => report(
+                err,
+                &Error::OpenFailed {
+                    path: filename.to_string(),
+                    source,
+                },
+            ),
+            Ok(Source::File(file)) if is_file(&file) => tac(file, out, sep, before, filename)?,
+            Ok(source) => match spool(source).map_err(|source| Error::ReadFailed {
+                path: filename.to_string(),
+                source,
+            })? {
+                Spool::Memory(bytes) => tac(bytes, out, sep, before, filename)?,
+                Spool::File(file) => tac(file, out, sep, before, filename)?,
+            },
+        }
+    }
+    Ok(())
+}
+
+pub fn get_args() -> MyResult<TacConfig> {
+    let matches = App::new("tacr")
+        .version("0.1.0")
+        .author("MTON <mton@aol.com>")
+        .about("Rust tac")
+        .arg(
+            Arg::with_name("files")
+                .value_name("FILE")
+                .help("Input file(s)")
+                .multiple(true)
+                .default_value("-")
+        )
+        .arg(
+            Arg::with_name("before")
+                .short("b")
+                .long("before")
+                .help("Attach the separator before instead of after")
+                .takes_value(false)
+        )
+        .arg(
+            Arg::with_name("regex")
+                .short("r")
+                .long("regex")
+                .help("Interpret the separator as a regular expression")
+                .takes_value(false)
+        )
+        .arg(
+            Arg::with_name("separator")
+                .short("s")
+                .long("separator")
+                .value_name("STRING")
+                .help("Use STRING as the separator instead of newline")
+                .allow_hyphen_values(true)
+        )
+        .arg(
+            Arg::with_name("no_decompress")
+                .long("no-decompress")
+                .help("Pass compressed input through undecoded")
+                .takes_value(false)
+        )
+        .get_matches();
+
+    let separator = matches.value_of("separator").unwrap_or("\n");
+    if separator.is_empty() {
+        return Err(Error::EmptySeparator);
+    }
+    let pattern = match matches.is_present("regex") {
+        true => separator.to_string(),
+        false => regex::escape(separator),
+    };
+    let separator = Regex::new(&pattern).map_err(|source| Error::InvalidRegex {
+        flag: "separator",
+        pattern: separator.to_string(),
+        source,
+    })?;
+
+    Ok(TacConfig {
+        files: matches.values_of_lossy("files").unwrap(),
+        separator,
+        before: matches.is_present("before"),
+        decompress: !matches.is_present("no_decompress"),
+    })
+}
+
+fn report<E: Write>(err: &mut E, e: &Error) {
+    let _ = writeln!(err, "{}", e);
+}
+
+// -- a FIFO or device opens like a file, but can't seek
+fn is_file(file: &BufReader<File>) -> bool {
+    file.get_ref().metadata().is_ok_and(|meta| meta.is_file())
+}
+
+// -- an input that can't seek, read to the end so it can
+enum Spool {
+    Memory(Cursor<Vec<u8>>),
+    File(File),
+}
+
+fn spool(source: Source) -> io::Result<Spool> {
+    let mut bytes = vec![];
+    let mut input: Box<dyn Read> = match source {
+        Source::Stdin(stdin) => Box::new(stdin),
+        Source::File(file) => Box::new(file),
+        Source::Decoded(reader) => Box::new(reader),
+    };
+    (&mut input).take(MEM_LIMIT as u64 + 1).read_to_end(&mut bytes)?;
+    if bytes.len() <= MEM_LIMIT {
+        return Ok(Spool::Memory(Cursor::new(bytes)));
+    }
+    // unnamed, gone once closed : nothing to clean up on exit or ctrl-C
+    let mut file = tempfile::tempfile()?;
+    file.write_all(&bytes)?;
+    io::copy(&mut input, &mut file)?;
+    Ok(Spool::File(file))
+}
+
+pub fn tac<R: Read + Seek, W: Write>(
+    input: R,
+    out: &mut W,
+    sep: &Regex,
+    before: bool,
+    path: &str,
+) -> MyResult<()> {
+    tac_blocks(input, out, sep, before, BLOCK, path)
+}
+
+// -- walk back from the end, `pending` holds what's been read but not
+// printed : the start of the last record seen so far
+// - a separator found in the first reach - 1 bytes read may still grow
+// into a longer match starting in the block before (a regex, or a
+// separator split across blocks), it's held back until that block is read
+// - each block is searched once, with the reach of the one after it
+// still pending : a record longer than a block costs O(n), not O(n²)
+// - like any tac reading backwards, a separator that overlaps itself
+// ("aaa" in a run of a's) may split up differently than read forwards
+fn tac_blocks<R: Read + Seek, W: Write>(
+    mut input: R,
+    out: &mut W,
+    sep: &Regex,
+    before: bool,
+    block: u64,
+    path: &str,
+) -> MyResult<()> {
+    let read_failed = |source| Error::ReadFailed {
+        path: path.to_string(),
+        source,
+    };
+    let reach = reach(sep);
+    let mut pos = input.seek(SeekFrom::End(0)).map_err(read_failed)?;
+    let mut pending = Pending::default();
+    loop {
+        let start = pos.saturating_sub(block);
+        let len = (pos - start) as usize;
+        input.seek(SeekFrom::Start(start)).map_err(read_failed)?;
+        input.read_exact(pending.prepend(len)).map_err(read_failed)?;
+        pos = start;
+
+        // matches starting further in were found last time round, their
+        // records printed : only the new block and the held back start of
+        // the last one are searched, with a few bytes past them for \b or
+        // $ to look at
+        let data = pending.bytes();
+        let unsure = reach - 1;
+        let limit = (len + 2 * reach + 4).min(data.len());
+        // where each record starts : after its separator, or on it with -b
+        // - empty matches (-r 'x*') separate nothing
+        let bounds: Vec<usize> = sep
+            .find_iter(&data[..limit])
+            .take_while(|m| m.start() < len + unsure)
+            .filter(|m| !m.is_empty() && (pos == 0 || m.start() >= unsure))
+            .map(|m| if before { m.start() } else { m.end() })
+            .collect();
+        let mut end = data.len();
+        for &bound in bounds.iter().rev() {
+            if bound < end {
+                out.write_all(&data[bound..end]).map_err(Error::WriteFailed)?;
+                end = bound;
+            }
+        }
+        pending.truncate(end);
+
+        if pos == 0 {
+            return out.write_all(pending.bytes()).map_err(Error::WriteFailed);
+        }
+    }
+}
+
+// -- how far into what follows a block a match starting in that block can
+// reach : its longest match, and for \b, ^ or $ the char before it too
+// - a -r separator without a bound (x+) is taken to be no longer than
+// UNBOUNDED
+fn reach(sep: &Regex) -> usize {
+    let hir = regex_syntax::ParserBuilder::new()
+        .utf8(false)
+        .build()
+        .parse(sep.as_str());
+    let (longest, looks) = match &hir {
+        Ok(hir) => (hir.properties().maximum_len(), !hir.properties().look_set().is_empty()),
+        Err(_) => (None, true),
+    };
+    longest.unwrap_or(UNBOUNDED).max(1) + if looks { 4 } else { 0 }
+}
+
+// -- bytes kept at the end of `buf`, so a block read before them goes in
+// front without moving them : when there's no room left the buffer
+// doubles, and each byte is moved O(1) times on average
+#[derive(Default)]
+struct Pending {
+    buf: Vec<u8>,
+    start: usize,
+}
+
+impl Pending {
+    fn bytes(&self) -> &[u8] {
+        &self.buf[self.start..]
+    }
+
+    // -- room for `len` bytes in front of the ones held, to read into
+    fn prepend(&mut self, len: usize) -> &mut [u8] {
+        if self.start < len {
+            let held = self.buf.len() - self.start;
+            let mut buf = vec![0; 2 * (held + len)];
+            let start = buf.len() - held;
+            buf[start..].copy_from_slice(self.bytes());
+            self.buf = buf;
+            self.start = start;
+        }
+        self.start -= len;
+        &mut self.buf[self.start..self.start + len]
+    }
+
+    // -- keep the first `len` bytes held
+    fn truncate(&mut self, len: usize) {
+        self.buf.truncate(self.start + len);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run_with, tac_blocks, TacConfig};
+    use regex::bytes::Regex;
+    use std::io::Cursor;
+
+    fn tac(input: &str, sep: &str, before: bool, block: u64) -> String {
+        let mut out = vec![];
+        let sep = Regex::new(sep).unwrap();
+        tac_blocks(Cursor::new(input), &mut out, &sep, before, block, "-").unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_tac() {
+        // every block size gives the same answer, down to a byte a block
+        for block in [1, 2, 3, 64] {
+            assert_eq!(tac("a\nb\nc\n", "\n", false, block), "c\nb\na\n");
+            assert_eq!(tac("a\nb", "\n", false, block), "ba\n");
+            assert_eq!(tac("a\nb\n", "\n", true, block), "\n\nba");
+            assert_eq!(tac("a--b--c", "--", false, block), "cb--a--");
+            assert_eq!(tac("a12b345c", "[0-9]+", false, block), "cb345a12");
+            assert_eq!(tac("", "\n", false, block), "");
+            assert_eq!(tac("\n\n", "\n", false, block), "\n\n");
+        }
+        // empty matches don't separate
+        assert_eq!(tac("ab\n", "x*", false, 1), "ab\n");
+        // a record spanning many blocks, and separators found through the
+        // few bytes past the search that \b and $ look at
+        let long = "x".repeat(1000);
+        assert_eq!(tac(&format!("a\n{}\nb\n", long), "\n", false, 7), format!("b\n{}\na\n", long));
+        for block in [1, 2, 3] {
+            assert_eq!(tac("ab cd ef", r"\b ", false, block), "efcd ab ");
+            assert_eq!(tac("a1\nb2\nc", r"(?m)\d$", false, block), "\nc\nb2a1");
+        }
+    }
+
+    #[test]
+    fn test_run_with() {
+        // a missing file is reported to the error sink, the rest printed
+        let config = TacConfig {
+            files: vec!["no-such-file".to_string(), "tests/inputs/fox.txt".to_string()],
+            separator: Regex::new("\n").unwrap(),
+            before: false,
+            decompress: true,
+        };
+        let (mut out, mut err) = (vec![], vec![]);
+        run_with(&config, &mut out, &mut err).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "The quick brown fox jumps over the lazy dog.\n"
+        );
+        assert!(String::from_utf8(err)
+            .unwrap()
+            .starts_with("Failed to open no-such-file"));
+    }
+}
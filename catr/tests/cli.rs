@@ -20,6 +20,13 @@ const EMPTY: &str = "tests/inputs/empty.txt";
 const FOX: &str = "tests/inputs/fox.txt";
 const SPIDERS: &str = "tests/inputs/spiders.txt";
 const BUSTLE: &str = "tests/inputs/the-bustle.txt";
+// compressed copies : gzip -n, xz
+const FOX_GZ: &str = "tests/inputs/fox.txt.gz";
+const BUSTLE_XZ: &str = "tests/inputs/the-bustle.txt.xz";
+const CRLF: &str = "tests/inputs/crlf.txt";
+// every byte value 0..=255
+const BYTES: &str = "tests/inputs/bytes.bin";
+const HELLO_RS: &str = "tests/inputs/hello.rs";
 
 // ----------------------------------------------------------------------------
 // validate how cat runs
@@ -142,6 +149,17 @@ fn skips_bad_file() -> TestResult {
     Ok(())
 }
 
+#[test]
+fn quiet_stderr() -> TestResult {
+    // stderr is for errors only, a run without any says nothing there
+    Command::cargo_bin(PRG)?
+        .arg(FOX)
+        .assert()
+        .success()
+        .stderr("");
+    Ok(())
+}
+
 // ----------------------------------------------------------------------------
 #[test]
 fn empty() -> TestResult {
@@ -228,6 +246,45 @@ fn all_b() -> TestResult {
     run(&["-b", FOX, SPIDERS, BUSTLE], "tests/expected/all.b.out")
 }
 
+#[test]
+fn all_files0_from() -> TestResult {
+    // the same 3 files, named NUL-separated on stdin, e.g. by `findr -0`
+    let list = [FOX, SPIDERS, BUSTLE].join("\0");
+    let expected = fs::read_to_string("tests/expected/all.out")?;
+    Command::cargo_bin(PRG)?
+        .args(["--files0-from", "-"])
+        .write_stdin(list)
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+#[test]
+fn files0_from_skips_non_utf8_name() -> TestResult {
+    // `findr -print0` passes any name on : one that isn't UTF-8 is
+    // reported and skipped, the rest of the list still printed
+    let list = [FOX.as_bytes(), b"caf\xe9.txt", FOX.as_bytes()].join(&b'\0');
+    let fox = fs::read_to_string(FOX)?;
+    Command::cargo_bin(PRG)?
+        .args(["--files0-from", "-"])
+        .write_stdin(list)
+        .assert()
+        .success()
+        .stderr("Failed to open caf\u{FFFD}.txt: file name is not valid UTF-8\n")
+        .stdout(format!("{}{}", fox, fox));
+    Ok(())
+}
+
+#[test]
+fn dies_files0_from_with_files() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["--files0-from", "-", FOX])
+        .assert()
+        .failure();
+    Ok(())
+}
+
 // ----------------------------------------------------------------------------
 #[test]
 fn bustle_stdin() -> TestResult {
@@ -262,3 +319,367 @@ fn bustle_stdin_b() -> TestResult {
         "tests/expected/the-bustle.txt.stdin.b.out"
     )
 }
+
+// ----------------------------------------------------------------------------
+// compressed input is decoded to the same output as the plain file
+#[test]
+fn fox_gz() -> TestResult {
+    run(&[FOX_GZ], "tests/expected/fox.txt.out")
+}
+
+#[test]
+fn bustle_xz_n() -> TestResult {
+    run(&["-n", BUSTLE_XZ], "tests/expected/the-bustle.txt.n.out")
+}
+
+#[test]
+fn fox_gz_stdin() -> TestResult {
+    // bytes, not read_to_string() : compressed input isn't UTF-8
+    let input = fs::read(FOX_GZ)?;
+    let expected = fs::read_to_string("tests/expected/fox.txt.out")?;
+    Command::cargo_bin(PRG)?
+        .write_stdin(input)
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+#[test]
+fn fox_gz_no_decompress() -> TestResult {
+    // without flags the raw gzip bytes go through untouched, like cat
+    Command::cargo_bin(PRG)?
+        .args(["--no-decompress", FOX_GZ])
+        .assert()
+        .success()
+        .stdout(fs::read(FOX_GZ)?);
+    Ok(())
+}
+
+#[test]
+fn fox_gz_no_decompress_n() -> TestResult {
+    // raw gzip bytes are not lines of text
+    Command::cargo_bin(PRG)?
+        .args(["--no-decompress", "-n", FOX_GZ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("valid UTF-8"));
+    Ok(())
+}
+
+// ----------------------------------------------------------------------------
+// no flags : a byte for byte copy, CRLF and the missing final newline kept
+#[test]
+fn crlf_passthrough() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .arg(CRLF)
+        .assert()
+        .success()
+        .stdout(fs::read("tests/expected/crlf.txt.out")?);
+    Ok(())
+}
+
+#[test]
+fn crlf_passthrough_stdin() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .write_stdin(fs::read(CRLF)?)
+        .assert()
+        .success()
+        .stdout(fs::read("tests/expected/crlf.txt.out")?);
+    Ok(())
+}
+
+// ----------------------------------------------------------------------------
+// --hex dumps match xxd
+#[test]
+fn fox_hex() -> TestResult {
+    run(&["--hex", FOX], "tests/expected/fox.txt.hex.out")
+}
+
+#[test]
+fn bustle_hex_group_cols() -> TestResult {
+    run(
+        &["--hex", "--group", "1", "--cols", "10", BUSTLE],
+        "tests/expected/the-bustle.txt.hex.g1.c10.out",
+    )
+}
+
+#[test]
+fn spiders_hex_offset_length() -> TestResult {
+    run(
+        &["--hex", "--offset", "5", "--length", "0x14", SPIDERS],
+        "tests/expected/spiders.txt.hex.s5.l20.out",
+    )
+}
+
+#[test]
+fn bytes_hex_no_group() -> TestResult {
+    run(
+        &["--hex", "--group", "0", BYTES],
+        "tests/expected/bytes.bin.hex.g0.out",
+    )
+}
+
+#[test]
+fn fox_gz_hex() -> TestResult {
+    // the decoded bytes are dumped
+    run(&["--hex", FOX_GZ], "tests/expected/fox.txt.hex.out")
+}
+
+#[test]
+fn bytes_hex_reverse() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["--hex", "--reverse", "tests/expected/bytes.bin.hex.g0.out"])
+        .assert()
+        .success()
+        .stdout(fs::read(BYTES)?);
+    Ok(())
+}
+
+#[test]
+fn fox_hex_reverse_stdin() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["--hex", "--reverse"])
+        .write_stdin(fs::read("tests/expected/fox.txt.hex.out")?)
+        .assert()
+        .success()
+        .stdout(fs::read(FOX)?);
+    Ok(())
+}
+
+#[test]
+fn dies_hex_reverse_bad_dump() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["--hex", "--reverse", FOX])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid hex dump, line 1"));
+    Ok(())
+}
+
+#[test]
+fn dies_bad_hex_options() -> TestResult {
+    for (args, error) in [
+        (&["--hex", "--cols", "0", FOX], "illegal column count -- 0"),
+        (&["--hex", "--cols", "257", FOX], "illegal column count -- 257"),
+        (&["--hex", "--offset", "0xzz", FOX], "illegal offset -- 0xzz"),
+    ] {
+        Command::cargo_bin(PRG)?
+            .args(args)
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(error));
+    }
+    // [hex] options need --hex, and --hex doesn't number lines
+    for args in [&["--cols", "8", FOX], &["--hex", "-n", FOX]] {
+        Command::cargo_bin(PRG)?.args(args).assert().failure();
+    }
+    Ok(())
+}
+
+// ----------------------------------------------------------------------------
+// bat like output : piped, every mode is off unless forced with `always`
+fn run_grid(args: &[&str], expected_file: &str) -> TestResult {
+    let expected = fs::read_to_string(expected_file)?;
+    Command::cargo_bin(PRG)?
+        .env("COLUMNS", "40")
+        .args(["--decorations", "always", "--color", "never"])
+        .args(args)
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+#[test]
+fn fox_grid() -> TestResult {
+    run_grid(&[FOX], "tests/expected/fox.txt.grid.out")
+}
+
+#[test]
+fn bustle_grid_b() -> TestResult {
+    run_grid(&["-b", BUSTLE], "tests/expected/the-bustle.txt.grid.b.out")
+}
+
+#[test]
+fn fox_grid_stdin() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .env("COLUMNS", "40")
+        .args(["--decorations", "always", "--color", "never"])
+        .write_stdin(fs::read(FOX)?)
+        .assert()
+        .success()
+        .stdout(fs::read_to_string("tests/expected/fox.txt.stdin.grid.out")?);
+    Ok(())
+}
+
+#[test]
+fn hello_color() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["--color", "always", HELLO_RS])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\x1b[38;2;"))
+        .stdout(predicate::str::contains("hello"));
+    Ok(())
+}
+
+#[test]
+fn hello_color_never() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["--color", "never", HELLO_RS])
+        .assert()
+        .success()
+        .stdout(fs::read(HELLO_RS)?);
+    Ok(())
+}
+
+#[test]
+fn fox_paging() -> TestResult {
+    // the pager's output is ours, a missing pager falls back to stdout
+    for pager in ["cat", "no-such-pager"] {
+        Command::cargo_bin(PRG)?
+            .env("PAGER", pager)
+            .args(["--paging", "always", FOX])
+            .assert()
+            .success()
+            .stdout(fs::read_to_string("tests/expected/fox.txt.out")?);
+    }
+    Ok(())
+}
+
+#[test]
+fn dies_bad_color() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["--color", "sometimes", FOX])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("isn't a valid value"));
+    Ok(())
+}
+
+// ----------------------------------------------------------------------------
+// --range / --from-regex / --to-regex : slices keep their line numbers
+#[test]
+fn bustle_range_n() -> TestResult {
+    run(
+        &["--range", "2:3", "-n", BUSTLE],
+        "tests/expected/the-bustle.txt.range.2-3.n.out",
+    )
+}
+
+#[test]
+fn bustle_range_from_end_b() -> TestResult {
+    run(
+        &["--range=-4:", "-b", BUSTLE],
+        "tests/expected/the-bustle.txt.range.-4.b.out",
+    )
+}
+
+#[test]
+fn bustle_stdin_range_to_end() -> TestResult {
+    run_stdin(
+        BUSTLE,
+        &["--lines", ":-3"],
+        "tests/expected/the-bustle.txt.stdin.range.-3.out",
+    )
+}
+
+#[test]
+fn bustle_regex_n() -> TestResult {
+    run(
+        &["--from-regex", "^Is", "--to-regex", "^The", "-n", BUSTLE],
+        "tests/expected/the-bustle.txt.regex.n.out",
+    )
+}
+
+#[test]
+fn all_range_per_file() -> TestResult {
+    run(
+        &["--range", "1", EMPTY, FOX, SPIDERS, BUSTLE],
+        "tests/expected/all.range.1.out",
+    )
+}
+
+#[test]
+fn dies_bad_range() -> TestResult {
+    for (args, error) in [
+        (&["--range", "0:2", FOX], "illegal range -- 0:2"),
+        (&["--range", "a:b", FOX], "illegal range -- a:b"),
+        (&["--from-regex", "(", FOX], "Invalid --from-regex \"(\""),
+        (&["--to-regex", "*", FOX], "Invalid --to-regex \"*\""),
+    ] {
+        Command::cargo_bin(PRG)?
+            .args(args)
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(error));
+    }
+    // one way of picking lines at a time
+    Command::cargo_bin(PRG)?
+        .args(["--range", "1:2", "--from-regex", "x", FOX])
+        .assert()
+        .failure();
+    Ok(())
+}
+
+// ----------------------------------------------------------------------------
+// each kind of error exits with its own status, see catr::Error
+#[test]
+fn exit_codes() -> TestResult {
+    for (args, code, error) in [
+        (&["--range", "0:2", FOX][..], 2, "illegal range -- 0:2\n"),
+        (&["--hex", "--cols", "0", FOX][..], 2, "illegal column count -- 0\n"),
+        // gzip's magic number : 0x1f is ASCII, 0x8b can't start a character
+        (
+            &["--no-decompress", "-n", FOX_GZ][..],
+            5,
+            "tests/inputs/fox.txt.gz: stream did not contain valid UTF-8 at byte 1\n",
+        ),
+    ] {
+        Command::cargo_bin(PRG)?
+            .args(args)
+            .assert()
+            .code(code)
+            .stderr(error);
+    }
+    Ok(())
+}
+
+// ----------------------------------------------------------------------------
+// -- stdout closed before catr writes a byte, like the left side of
+// `catr huge.log | headr -n 1` once headr has its line
+fn broken_pipe(args: &[&str], policy: Option<&str>) -> Result<std::process::Output, Box<dyn Error>> {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let mut cmd = std::process::Command::new(assert_cmd::cargo::cargo_bin(PRG));
+    cmd.args(args)
+        .env_remove("SIGPIPE_STATUS")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    if let Some(policy) = policy {
+        cmd.env("SIGPIPE_STATUS", policy);
+    }
+    let mut child = cmd.spawn()?;
+    drop(child.stdout.take());
+    // catr may be gone before all of it is written
+    let _ = child.stdin.take().unwrap().write_all(&fs::read(BUSTLE)?);
+    Ok(child.wait_with_output()?)
+}
+
+#[test]
+fn broken_pipe_exits_quietly() -> TestResult {
+    // the plain copy, the line path and --hex all stop the same way
+    for args in [&[][..], &["-n"][..], &["--hex"][..]] {
+        let output = broken_pipe(args, None)?;
+        assert_eq!(output.status.code(), Some(0), "{:?}", args);
+        assert_eq!(String::from_utf8(output.stderr)?, "", "{:?}", args);
+
+        let output = broken_pipe(args, Some("141"))?;
+        assert_eq!(output.status.code(), Some(141), "{:?}", args);
+        assert_eq!(String::from_utf8(output.stderr)?, "", "{:?}", args);
+    }
+    Ok(())
+}
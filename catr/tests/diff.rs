@@ -0,0 +1,52 @@
+use difftest::both;
+use proptest::prelude::*;
+
+// catr against GNU cat ($CAT overrides, gcat on macOS), see difftest for
+// how the two are run and compared
+const PRG: &str = "catr";
+const CAT: (&str, &str) = ("CAT", "cat");
+
+// -- text lines each ended by a lone "\n" : no CR, no bad UTF-8, no
+// unterminated last line, what catr -n / -b print as cat does
+fn lf_text() -> impl Strategy<Value = Vec<u8>> {
+    let line = prop_oneof!["[a-z \t]{0,10}", "[^\r\n]{0,4}"];
+    prop::collection::vec(line, 0..10).prop_map(|lines| {
+        lines
+            .into_iter()
+            .map(|line| line + "\n")
+            .collect::<String>()
+            .into_bytes()
+    })
+}
+
+proptest! {
+    // -- no flags : io::copy() of stdin or the file, whatever it holds
+    #[test]
+    fn copy_matches_cat(input in difftest::input(), from_file in any::<bool>()) {
+        let Some((ours, theirs)) = both(PRG, CAT, &[], &input, from_file) else { return Ok(()) };
+        prop_assert_eq!((ours.status.code(), ours.stdout), (theirs.status.code(), theirs.stdout));
+    }
+
+    #[test]
+    #[ignore = "known failure: catr -n / -b read lines as text, a CRLF loses its \\r, \
+                the last line gains a newline and bad UTF-8 stops catr with status 5"]
+    fn numbers_match_cat(
+        input in difftest::input(),
+        flag in prop_oneof![Just("-n"), Just("-b")],
+        from_file in any::<bool>(),
+    ) {
+        let Some((ours, theirs)) = both(PRG, CAT, &[flag], &input, from_file) else { return Ok(()) };
+        prop_assert_eq!((ours.status.code(), ours.stdout), (theirs.status.code(), theirs.stdout));
+    }
+
+    // -- numbers_match_cat on the input none of its known failures touch
+    #[test]
+    fn numbers_match_cat_on_lf_text(
+        input in lf_text(),
+        flag in prop_oneof![Just("-n"), Just("-b")],
+        from_file in any::<bool>(),
+    ) {
+        let Some((ours, theirs)) = both(PRG, CAT, &[flag], &input, from_file) else { return Ok(()) };
+        prop_assert_eq!((ours.status.code(), ours.stdout), (theirs.status.code(), theirs.stdout));
+    }
+}
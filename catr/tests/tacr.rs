@@ -0,0 +1,141 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+use std::fs;
+
+type TestResult = Result<(), Box<dyn Error>>;
+
+// tacr ships in catr's crate, expected output is GNU tac's (see mk-outs.sh)
+const PRG: &str = "tacr";
+const EMPTY: &str = "tests/inputs/empty.txt";
+const FOX: &str = "tests/inputs/fox.txt";
+const SPIDERS: &str = "tests/inputs/spiders.txt";
+const BUSTLE: &str = "tests/inputs/the-bustle.txt";
+const BUSTLE_XZ: &str = "tests/inputs/the-bustle.txt.xz";
+const CRLF: &str = "tests/inputs/crlf.txt";
+
+// ----------------------------------------------------------------------------
+fn run(args: &[&str], expected_file: &str) -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(args)
+        .assert()
+        .success()
+        .stdout(fs::read(expected_file)?);
+    Ok(())
+}
+
+fn run_stdin(input_file: &str, args: &[&str], expected_file: &str) -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(args)
+        .write_stdin(fs::read(input_file)?)
+        .assert()
+        .success()
+        .stdout(fs::read(expected_file)?);
+    Ok(())
+}
+
+// ----------------------------------------------------------------------------
+#[test]
+fn bustle() -> TestResult {
+    run(&[BUSTLE], "tests/expected/the-bustle.txt.tac.out")
+}
+
+#[test]
+fn bustle_b() -> TestResult {
+    run(&["-b", BUSTLE], "tests/expected/the-bustle.txt.tac.b.out")
+}
+
+#[test]
+fn bustle_r() -> TestResult {
+    run(
+        &["-r", "-s", "[aeiou]", BUSTLE],
+        "tests/expected/the-bustle.txt.tac.r.out",
+    )
+}
+
+#[test]
+fn bustle_s() -> TestResult {
+    run(&["-s", "e", BUSTLE], "tests/expected/the-bustle.txt.tac.s.out")
+}
+
+#[test]
+fn spiders() -> TestResult {
+    run(&[SPIDERS], "tests/expected/spiders.txt.tac.out")
+}
+
+#[test]
+fn spiders_b_r() -> TestResult {
+    run(&["-b", SPIDERS], "tests/expected/spiders.txt.tac.b.out")?;
+    run(
+        &["--regex", "--separator", "[aeiou]", SPIDERS],
+        "tests/expected/spiders.txt.tac.r.out",
+    )
+}
+
+#[test]
+fn all() -> TestResult {
+    // each file reversed on its own, in the order given
+    run(&[EMPTY, FOX, SPIDERS, BUSTLE], "tests/expected/all.tac.out")
+}
+
+#[test]
+fn crlf_no_final_newline() -> TestResult {
+    run(&[CRLF], "tests/expected/crlf.txt.tac.out")
+}
+
+#[test]
+fn bustle_stdin() -> TestResult {
+    run_stdin(BUSTLE, &[], "tests/expected/the-bustle.txt.tac.out")?;
+    run_stdin(BUSTLE, &["-b", "-"], "tests/expected/the-bustle.txt.tac.b.out")
+}
+
+#[test]
+fn bustle_xz() -> TestResult {
+    run(&[BUSTLE_XZ], "tests/expected/the-bustle.txt.tac.out")
+}
+
+#[test]
+fn skips_bad_file() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["no-such-file", FOX])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Failed to open no-such-file"))
+        .stdout(fs::read(FOX)?);
+    Ok(())
+}
+
+#[test]
+fn dies_bad_separator() -> TestResult {
+    for (args, error) in [
+        (&["-s", ""][..], "separator cannot be empty"),
+        (&["-r", "-s", "("][..], "Invalid separator \"(\""),
+    ] {
+        Command::cargo_bin(PRG)?
+            .args(args)
+            .assert()
+            .code(2)
+            .stderr(predicate::str::contains(error));
+    }
+    Ok(())
+}
+
+#[test]
+fn broken_pipe_exits_quietly() -> TestResult {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    // stdout gone before the first record is printed
+    let mut child = std::process::Command::new(assert_cmd::cargo::cargo_bin(PRG))
+        .env("SIGPIPE_STATUS", "141")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    drop(child.stdout.take());
+    let _ = child.stdin.take().unwrap().write_all(&fs::read(BUSTLE)?);
+    let output = child.wait_with_output()?;
+    assert_eq!(output.status.code(), Some(141));
+    assert_eq!(String::from_utf8(output.stderr)?, "");
+    Ok(())
+}
@@ -0,0 +1,34 @@
+#!/usr/bin/env bash
+
+# expected output comes from GNU comm (coreutils), in the C locale : commr
+# compares bytes
+# - on macOS : `brew install coreutils` and run with COMM=gcomm
+# - GNU comm has no -i, the mixed*.txt.i outputs are kept by hand
+COMM="${COMM:-comm}"
+export LC_ALL=C
+
+set -u
+
+ROOT="tests/inputs"
+OUT_DIR="tests/expected"
+
+[[ ! -d "$OUT_DIR" ]] && mkdir -p "$OUT_DIR"
+
+EMPTY="$ROOT/empty.txt"
+FILE1="$ROOT/file1.txt"
+FILE2="$ROOT/file2.txt"
+
+for FLAGS in "" -1 -2 -3 -12 -13 -23 -123; do
+  $COMM $FLAGS $FILE1 $FILE2 > ${OUT_DIR}/file1.file2${FLAGS}.out
+done
+
+$COMM --output-delimiter=' | ' $FILE1 $FILE2 > ${OUT_DIR}/file1.file2.delim.out
+$COMM --output-delimiter=' | ' -2 $FILE1 $FILE2 > ${OUT_DIR}/file1.file2-2.delim.out
+
+# one side empty, files swapped
+$COMM $FILE1 $EMPTY > ${OUT_DIR}/file1.empty.out
+$COMM $EMPTY $FILE2 > ${OUT_DIR}/empty.file2.out
+$COMM $FILE2 $FILE1 > ${OUT_DIR}/file2.file1.out
+
+# compressed copy : tests expect the same output as the plain file
+gzip -nc $FILE2 > $ROOT/file2.txt.gz
@@ -0,0 +1,45 @@
+use std::io;
+use thiserror::Error;
+
+// == everything commr can fail with
+// - both inputs are needed : one that fails to open is an error, not
+// skipped like in the other tools
+#[derive(Debug, Error)]
+pub enum Error {
+    // stdin can only be read once
+    #[error("both input files cannot be STDIN (\"-\")")]
+    BothStdin,
+
+    #[error("Failed to open {path}: {source}")]
+    OpenFailed { path: String, source: io::Error },
+
+    #[error("{path}: {source}")]
+    ReadFailed { path: String, source: io::Error },
+
+    // `line` sorts before the one above it, by the comparison in use (-i
+    // or bytes) : the columns printed up to here are still right
+    #[error("{path}: not in sorted order at line {line}")]
+    Unsorted { path: String, line: u64 },
+
+    #[error("write error: {0}")]
+    WriteFailed(#[source] io::Error),
+}
+
+impl ior::Failure for Error {
+    fn exit_code(&self) -> i32 {
+        match self {
+            Error::BothStdin => 2,
+            Error::OpenFailed { .. } => 3,
+            Error::ReadFailed { .. } => 4,
+            Error::Unsorted { .. } => 5,
+            Error::WriteFailed(_) => 6,
+        }
+    }
+
+    fn write_error(&self) -> Option<&io::Error> {
+        match self {
+            Error::WriteFailed(e) => Some(e),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,341 @@
+use clap::{App, Arg};
+use std::cmp::Ordering;
+use std::io::{self, BufRead, Write};
+
+mod error;
+
+pub use error::Error;
+
+type MyResult<T> = Result<T, Error>;
+
+// == comm : two sorted inputs side by side, in three columns
+// lines only in file 1
+// <delim>lines only in file 2
+// <delim><delim>lines in both
+// - a column left out (-1, -2, -3) takes its delimiter with it, the
+// others move left
+// - built by get_args(), or by hand when commr is used as a library
+#[derive(Debug)]
+pub struct Config {
+    // "-" for stdin, only one of them
+    pub file1: String,
+    pub file2: String,
+    // columns not left out by -1, -2, -3
+    pub show_col1: bool,
+    pub show_col2: bool,
+    pub show_col3: bool,
+    // -i : letters compare without case, a common line is printed the way
+    // file 1 has it
+    pub insensitive: bool,
+    // --output-delimiter, a tab by default
+    pub delimiter: String,
+    // off with --nocheck-order
+    pub check_order: bool,
+    // decode gzip, bzip2, xz and zstd input (off with --no-decompress)
+    pub decompress: bool,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            file1: "-".to_string(),
+            file2: "-".to_string(),
+            show_col1: true,
+            show_col2: true,
+            show_col3: true,
+            insensitive: false,
+            delimiter: "\t".to_string(),
+            check_order: true,
+            decompress: true,
+        }
+    }
+}
+
+pub fn run(config: Config) -> MyResult<()> {
+    // buffered unless stdout is a terminal, see ior::stdout()
+    let mut out = ior::stdout();
+    run_with(&config, &mut out)?;
+    out.flush().map_err(Error::WriteFailed)
+}
+
+// -- run() with the output going where the caller says, e.g. into a
+// Vec<u8>
+// - no error sink : either input failing to open ends the run
+pub fn run_with<W: Write>(config: &Config, out: &mut W) -> MyResult<()> {
+    if config.file1 == "-" && config.file2 == "-" {
+        return Err(Error::BothStdin);
+    }
+    let input1 = open(&config.file1, config.decompress)?;
+    let input2 = open(&config.file2, config.decompress)?;
+    comm(input1, input2, config, out)
+}
+
+// -- the two inputs already open, config.file1 and config.file2 only name
+// them in errors
+pub fn comm<R1: BufRead, R2: BufRead, W: Write>(
+    input1: R1,
+    input2: R2,
+    config: &Config,
+    out: &mut W,
+) -> MyResult<()> {
+    let mut lines1 = Lines::new(input1, &config.file1, config);
+    let mut lines2 = Lines::new(input2, &config.file2, config);
+    let mut line1 = lines1.next()?;
+    let mut line2 = lines2.next()?;
+    // one delimiter per column shown to the left
+    let indent2 = config.show_col1 as usize;
+    let indent3 = indent2 + config.show_col2 as usize;
+
+    loop {
+        let ordering = match (&line1, &line2) {
+            (None, None) => return Ok(()),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (Some(a), Some(b)) => compare(a, b, config.insensitive),
+        };
+        match ordering {
+            Ordering::Less => {
+                if config.show_col1 {
+                    print(out, 0, config, line1.as_deref())?;
+                }
+                line1 = lines1.next()?;
+            }
+            Ordering::Greater => {
+                if config.show_col2 {
+                    print(out, indent2, config, line2.as_deref())?;
+                }
+                line2 = lines2.next()?;
+            }
+            Ordering::Equal => {
+                if config.show_col3 {
+                    print(out, indent3, config, line1.as_deref())?;
+                }
+                line1 = lines1.next()?;
+                line2 = lines2.next()?;
+            }
+        }
+    }
+}
+
+fn print<W: Write>(out: &mut W, indent: usize, config: &Config, line: Option<&[u8]>) -> MyResult<()> {
+    write_line(out, indent, config.delimiter.as_bytes(), line.unwrap_or_default())
+        .map_err(Error::WriteFailed)
+}
+
+fn write_line<W: Write>(out: &mut W, indent: usize, delimiter: &[u8], line: &[u8]) -> io::Result<()> {
+    for _ in 0..indent {
+        out.write_all(delimiter)?;
+    }
+    out.write_all(line)?;
+    out.write_all(b"\n")
+}
+
+// -- bytes, or with -i letters folded to upper case, the way sort -f
+// folds them : `sort -f | commr -i` agrees on where `_` goes
+fn compare(a: &[u8], b: &[u8], insensitive: bool) -> Ordering {
+    match insensitive {
+        true => a
+            .iter()
+            .map(u8::to_ascii_uppercase)
+            .cmp(b.iter().map(u8::to_ascii_uppercase)),
+        false => a.cmp(b),
+    }
+}
+
+// -- the lines of one input without their newline, each checked against
+// the one before it
+struct Lines<'a, R> {
+    input: R,
+    path: &'a str,
+    check_order: bool,
+    insensitive: bool,
+    // lines read so far
+    count: u64,
+    last: Option<Vec<u8>>,
+}
+
+impl<'a, R: BufRead> Lines<'a, R> {
+    fn new(input: R, path: &'a str, config: &Config) -> Lines<'a, R> {
+        Lines {
+            input,
+            path,
+            check_order: config.check_order,
+            insensitive: config.insensitive,
+            count: 0,
+            last: None,
+        }
+    }
+
+    fn next(&mut self) -> MyResult<Option<Vec<u8>>> {
+        let mut line = vec![];
+        let bytes = self
+            .input
+            .read_until(b'\n', &mut line)
+            .map_err(|source| Error::ReadFailed {
+                path: self.path.to_string(),
+                source,
+            })?;
+        if bytes == 0 {
+            return Ok(None);
+        }
+        if line.last() == Some(&b'\n') {
+            line.pop();
+        }
+        self.count += 1;
+        if self.check_order {
+            if let Some(last) = &self.last {
+                if compare(last, &line, self.insensitive) == Ordering::Greater {
+                    return Err(Error::Unsorted {
+                        path: self.path.to_string(),
+                        line: self.count,
+                    });
+                }
+            }
+            let last = self.last.get_or_insert_with(Vec::new);
+            last.clear();
+            last.extend_from_slice(&line);
+        }
+        Ok(Some(line))
+    }
+}
+
+pub fn get_args() -> MyResult<Config> {
+    let matches = App::new("commr")
+        .version("0.1.0")
+        .author("MTON <mton@aol.com>")
+        .about("Rust comm")
+        .arg(
+            Arg::with_name("file1")
+                .value_name("FILE1")
+                .help("Input file 1")
+                .required(true)
+        )
+        .arg(
+            Arg::with_name("file2")
+                .value_name("FILE2")
+                .help("Input file 2")
+                .required(true)
+        )
+        .arg(
+            Arg::with_name("suppress_col1")
+                .short("1")
+                .help("Suppress printing of column 1")
+                .takes_value(false)
+        )
+        .arg(
+            Arg::with_name("suppress_col2")
+                .short("2")
+                .help("Suppress printing of column 2")
+                .takes_value(false)
+        )
+        .arg(
+            Arg::with_name("suppress_col3")
+                .short("3")
+                .help("Suppress printing of column 3")
+                .takes_value(false)
+        )
+        .arg(
+            Arg::with_name("insensitive")
+                .short("i")
+                .help("Case-insensitive comparison of lines")
+                .takes_value(false)
+        )
+        .arg(
+            Arg::with_name("delimiter")
+                .short("d")
+                .long("output-delimiter")
+                .value_name("DELIM")
+                .help("Output delimiter")
+                .default_value("\t")
+                .hide_default_value(true)
+        )
+        .arg(
+            Arg::with_name("nocheck_order")
+                .long("nocheck-order")
+                .help("Don't check that the input is sorted")
+                .takes_value(false)
+        )
+        .arg(
+            Arg::with_name("no_decompress")
+                .long("no-decompress")
+                .help("Pass compressed input through undecoded")
+                .takes_value(false)
+        )
+        .get_matches();
+
+    Ok(Config {
+        file1: matches.value_of("file1").unwrap().to_string(),
+        file2: matches.value_of("file2").unwrap().to_string(),
+        show_col1: !matches.is_present("suppress_col1"),
+        show_col2: !matches.is_present("suppress_col2"),
+        show_col3: !matches.is_present("suppress_col3"),
+        insensitive: matches.is_present("insensitive"),
+        delimiter: matches.value_of("delimiter").unwrap().to_string(),
+        check_order: !matches.is_present("nocheck_order"),
+        decompress: !matches.is_present("no_decompress"),
+    })
+}
+
+fn open(filename: &str, decompress: bool) -> MyResult<Box<dyn BufRead>> {
+    ior::open(filename, decompress).map_err(|source| Error::OpenFailed {
+        path: filename.to_string(),
+        source,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{comm, run_with, Config, Error};
+    use std::io::Cursor;
+
+    fn run_comm(a: &str, b: &str, config: &Config) -> Result<String, Error> {
+        let mut out = vec![];
+        comm(Cursor::new(a), Cursor::new(b), config, &mut out)?;
+        Ok(String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn test_comm() {
+        let config = Config::default();
+        let out = run_comm("a\nb\nd\n", "b\nc\nd\ne", &config).unwrap();
+        assert_eq!(out, "a\n\t\tb\n\tc\n\t\td\n\te\n");
+
+        let config = Config {
+            show_col1: false,
+            delimiter: "::".to_string(),
+            ..Config::default()
+        };
+        let out = run_comm("a\nb\nd\n", "b\nc\nd\ne\n", &config).unwrap();
+        assert_eq!(out, "::b\nc\n::d\ne\n");
+
+        let config = Config {
+            insensitive: true,
+            ..Config::default()
+        };
+        let out = run_comm("Apple\nb_\n", "apple\nBA\n", &config).unwrap();
+        assert_eq!(out, "\t\tApple\n\tBA\nb_\n");
+    }
+
+    #[test]
+    fn test_unsorted() {
+        let config = Config::default();
+        let e = run_comm("a\nb\n", "c\nb\n", &config).unwrap_err();
+        assert!(matches!(e, Error::Unsorted { line: 2, .. }));
+        let config = Config {
+            check_order: false,
+            ..Config::default()
+        };
+        assert!(run_comm("a\nb\n", "c\nb\n", &config).is_ok());
+    }
+
+    #[test]
+    fn test_run_with() {
+        let config = Config::default();
+        assert!(matches!(run_with(&config, &mut vec![]), Err(Error::BothStdin)));
+        let config = Config {
+            file1: "no-such-file".to_string(),
+            ..Config::default()
+        };
+        assert!(matches!(run_with(&config, &mut vec![]), Err(Error::OpenFailed { .. })));
+    }
+}
@@ -0,0 +1,14 @@
+use ior::Failure;
+
+fn main() {
+    if let Err(e) = commr::get_args().and_then(commr::run) {
+        // stdout closed early (`commr a.txt b.txt | headr`), see
+        // ior::PipePolicy
+        if e.is_broken_pipe() {
+            std::process::exit(ior::PipePolicy::from_env().exit_code());
+        }
+        eprintln!("{}", e);
+        // each kind of failure has its own status, see commr::Error
+        std::process::exit(e.exit_code());
+    }
+}
@@ -0,0 +1,142 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+use std::fs;
+
+type TestResult = Result<(), Box<dyn Error>>;
+
+// expected output is GNU comm's in the C locale (see mk-outs.sh), except
+// for -i
+const PRG: &str = "commr";
+const EMPTY: &str = "tests/inputs/empty.txt";
+const FILE1: &str = "tests/inputs/file1.txt";
+const FILE2: &str = "tests/inputs/file2.txt";
+const FILE2_GZ: &str = "tests/inputs/file2.txt.gz";
+const MIXED1: &str = "tests/inputs/mixed1.txt";
+const MIXED2: &str = "tests/inputs/mixed2.txt";
+const UNSORTED: &str = "tests/inputs/unsorted.txt";
+
+// ----------------------------------------------------------------------------
+fn run(args: &[&str], expected_file: &str) -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(args)
+        .assert()
+        .success()
+        .stdout(fs::read(expected_file)?);
+    Ok(())
+}
+
+// ----------------------------------------------------------------------------
+#[test]
+fn columns() -> TestResult {
+    run(&[FILE1, FILE2], "tests/expected/file1.file2.out")?;
+    run(&[FILE2, FILE1], "tests/expected/file2.file1.out")
+}
+
+#[test]
+fn suppress() -> TestResult {
+    for flags in ["-1", "-2", "-3", "-12", "-13", "-23", "-123"] {
+        run(
+            &[flags, FILE1, FILE2],
+            &format!("tests/expected/file1.file2{}.out", flags),
+        )?;
+    }
+    Ok(())
+}
+
+#[test]
+fn output_delimiter() -> TestResult {
+    run(
+        &["--output-delimiter", " | ", FILE1, FILE2],
+        "tests/expected/file1.file2.delim.out",
+    )?;
+    run(
+        &["-2", "-d", " | ", FILE1, FILE2],
+        "tests/expected/file1.file2-2.delim.out",
+    )
+}
+
+#[test]
+fn empty() -> TestResult {
+    run(&[FILE1, EMPTY], "tests/expected/file1.empty.out")?;
+    run(&[EMPTY, FILE2], "tests/expected/empty.file2.out")
+}
+
+#[test]
+fn insensitive() -> TestResult {
+    // a common line the way file 1 has it
+    run(&["-i", MIXED1, MIXED2], "tests/expected/mixed1.mixed2.i.out")?;
+    run(&["-i", "-3", MIXED2, MIXED1], "tests/expected/mixed2.mixed1.i-3.out")
+}
+
+#[test]
+fn stdin_gz() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args([FILE1, "-"])
+        .write_stdin(fs::read(FILE2_GZ)?)
+        .assert()
+        .success()
+        .stdout(fs::read("tests/expected/file1.file2.out")?);
+    run(&[FILE1, FILE2_GZ], "tests/expected/file1.file2.out")
+}
+
+// ----------------------------------------------------------------------------
+#[test]
+fn dies_unsorted() -> TestResult {
+    // what was printed before it's found stays
+    Command::cargo_bin(PRG)?
+        .args([UNSORTED, EMPTY])
+        .assert()
+        .code(5)
+        .stdout("apple\ncherry\n")
+        .stderr(format!("{}: not in sorted order at line 3\n", UNSORTED));
+    // without -i, mixed case isn't in byte order
+    Command::cargo_bin(PRG)?
+        .args([MIXED1, MIXED2])
+        .assert()
+        .code(5)
+        .stderr(predicate::str::contains("not in sorted order at line"));
+    Command::cargo_bin(PRG)?
+        .args(["--nocheck-order", UNSORTED, EMPTY])
+        .assert()
+        .success();
+    Ok(())
+}
+
+#[test]
+fn dies_bad_inputs() -> TestResult {
+    for (args, code, error) in [
+        (&["-", "-"][..], 2, "both input files cannot be STDIN"),
+        (&[FILE1, "no-such-file"][..], 3, "Failed to open no-such-file"),
+    ] {
+        Command::cargo_bin(PRG)?
+            .args(args)
+            .assert()
+            .code(code)
+            .stdout("")
+            .stderr(predicate::str::starts_with(error));
+    }
+    Command::cargo_bin(PRG)?.arg(FILE1).assert().failure();
+    Ok(())
+}
+
+#[test]
+fn broken_pipe_exits_quietly() -> TestResult {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let lines: String = (0..100_000).map(|i| format!("{:06}\n", i)).collect();
+    let mut child = std::process::Command::new(assert_cmd::cargo::cargo_bin(PRG))
+        .args(["-", EMPTY])
+        .env("SIGPIPE_STATUS", "141")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    drop(child.stdout.take());
+    let _ = child.stdin.take().unwrap().write_all(lines.as_bytes());
+    let output = child.wait_with_output()?;
+    assert_eq!(output.status.code(), Some(141));
+    assert_eq!(String::from_utf8(output.stderr)?, "");
+    Ok(())
+}
@@ -0,0 +1,34 @@
+#!/usr/bin/env bash
+
+# expected output comes from GNU cut (coreutils)
+# - on macOS : `brew install coreutils` and run with CUT=gcut
+# - GNU cut -c counts bytes, cutr counts characters : utf8.txt.c*.out are
+# kept by hand, and so are the --csv outputs, cut has no CSV mode
+CUT="${CUT:-cut}"
+
+INPUTS="./tests/inputs"
+OUT_DIR="./tests/expected"
+
+[[ ! -d "$OUT_DIR" ]] && mkdir -p "$OUT_DIR"
+
+for FILE in $INPUTS/books.tsv $INPUTS/empty.txt; do
+  BASENAME=$(basename "$FILE")
+  $CUT -f 1         $FILE > ${OUT_DIR}/${BASENAME}.f1.out
+  $CUT -f 2         $FILE > ${OUT_DIR}/${BASENAME}.f2.out
+  $CUT -f 3,1       $FILE > ${OUT_DIR}/${BASENAME}.f3,1.out
+  $CUT -f 2-        $FILE > ${OUT_DIR}/${BASENAME}.f2-.out
+  $CUT -f -2        $FILE > ${OUT_DIR}/${BASENAME}.f-2.out
+  $CUT -f 2 --complement $FILE > ${OUT_DIR}/${BASENAME}.f2.complement.out
+  $CUT -f 1,3 --output-delimiter=' | ' $FILE > ${OUT_DIR}/${BASENAME}.f1,3.od.out
+  $CUT -b 1-4       $FILE > ${OUT_DIR}/${BASENAME}.b1-4.out
+  $CUT -b 1-2,3-4,8- --output-delimiter=: $FILE > ${OUT_DIR}/${BASENAME}.b.od.out
+  $CUT -b 3-5 --complement $FILE > ${OUT_DIR}/${BASENAME}.b3-5.complement.out
+done
+
+# CSV split naively on every comma
+$CUT -d , -f 1,3 $INPUTS/books.csv > ${OUT_DIR}/books.csv.d,f1,3.out
+
+$CUT -f 1 $INPUTS/books.tsv $INPUTS/empty.txt $INPUTS/books.tsv > ${OUT_DIR}/all.f1.out
+
+# compressed copy : tests expect the same output as the plain file
+gzip -nc $INPUTS/books.tsv > $INPUTS/books.tsv.gz
@@ -0,0 +1,52 @@
+use std::io;
+use thiserror::Error;
+
+// == everything cutr can fail with
+// - a file that fails to open is reported and skipped, not returned
+#[derive(Debug, Error)]
+pub enum Error {
+    // -f / -b / -c LIST that doesn't parse, `reason` says why
+    #[error("illegal list -- {list} ({reason})")]
+    InvalidList { list: String, reason: &'static str },
+
+    // -d / --output-delimiter (in --csv mode) that isn't one byte
+    #[error("illegal delimiter -- {0} (must be a single byte)")]
+    InvalidDelimiter(String),
+
+    #[error("Failed to open {path}: {source}")]
+    OpenFailed { path: String, source: io::Error },
+
+    #[error("{path}: {source}")]
+    ReadFailed { path: String, source: io::Error },
+
+    // -c counts characters, so lines have to be UTF-8, -b and -f take any
+    // bytes
+    #[error("{path}: stream did not contain valid UTF-8 at byte {offset}")]
+    InvalidUtf8 { path: String, offset: u64 },
+
+    // --csv : a record the parser can't make sense of (a stray quote ...)
+    #[error("{path}: {source}")]
+    InvalidCsv { path: String, source: csv::Error },
+
+    #[error("write error: {0}")]
+    WriteFailed(#[source] io::Error),
+}
+
+impl ior::Failure for Error {
+    fn exit_code(&self) -> i32 {
+        match self {
+            Error::InvalidList { .. } | Error::InvalidDelimiter(_) => 2,
+            Error::OpenFailed { .. } => 3,
+            Error::ReadFailed { .. } => 4,
+            Error::InvalidUtf8 { .. } | Error::InvalidCsv { .. } => 5,
+            Error::WriteFailed(_) => 6,
+        }
+    }
+
+    fn write_error(&self) -> Option<&io::Error> {
+        match self {
+            Error::WriteFailed(e) => Some(e),
+            _ => None,
+        }
+    }
+}
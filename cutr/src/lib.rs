@@ -0,0 +1,367 @@
+use clap::{App, Arg, ArgGroup};
+use std::io::{self, BufRead, Write};
+
+mod error;
+mod list;
+
+pub use error::Error;
+pub use list::PositionList;
+
+type MyResult<T> = Result<T, Error>;
+
+// == cut : keep some fields, bytes or characters of every line
+// - built by get_args(), or by hand when cutr is used as a library
+#[derive(Debug)]
+pub struct Config {
+    pub files: Vec<String>,
+    // what to keep, --complement already applied
+    pub extract: Extract,
+    // -d : splits fields, tab by default (comma with --csv)
+    pub delimiter: u8,
+    // --output-delimiter : between fields, the input delimiter if None,
+    // and between byte / character ranges, nothing if None
+    pub output_delimiter: Option<Vec<u8>>,
+    // --csv : fields are parsed as CSV, a quoted delimiter or newline
+    // doesn't split, and re-quoted on output
+    pub csv: bool,
+    // decode gzip, bzip2, xz and zstd input (off with --no-decompress)
+    pub decompress: bool,
+}
+
+#[derive(Debug, Clone)]
+pub enum Extract {
+    Fields(PositionList),
+    Bytes(PositionList),
+    Chars(PositionList),
+}
+
+pub fn run(config: Config) -> MyResult<()> {
+    // buffered unless stdout is a terminal, see ior::stdout()
+    let mut out = ior::stdout();
+    run_with(&config, &mut out, &mut io::stderr().lock())?;
+    out.flush().map_err(Error::WriteFailed)
+}
+
+// -- run() with the output and the per-file errors going where the caller
+// says, e.g. into a Vec<u8>
+// - a file that fails to open is reported to `err` and skipped
+pub fn run_with<W: Write, E: Write>(config: &Config, out: &mut W, err: &mut E) -> MyResult<()> {
+    for filename in &config.files {
+        match open(filename, config.decompress) {
+            // the error sink is best effort, like eprintln! without the panic
+            Err(e) => {
+                let _ = writeln!(err, "{}", e);
+            }
+            Ok(input) => cut(input, filename, config, out)?,
+        }
+    }
+    Ok(())
+}
+
+// -- one input, `filename` only names it in errors
+// - every line comes out with a newline, the last one included, like cut
+pub fn cut<R: BufRead, W: Write>(
+    mut input: R,
+    filename: &str,
+    config: &Config,
+    out: &mut W,
+) -> MyResult<()> {
+    if let (true, Extract::Fields(list)) = (config.csv, &config.extract) {
+        return cut_csv(input, filename, config, list, out);
+    }
+    let mut line = vec![];
+    // bytes before this line, to say where bad UTF-8 is
+    let mut offset = 0;
+    loop {
+        line.clear();
+        let bytes = input
+            .read_until(b'\n', &mut line)
+            .map_err(|source| Error::ReadFailed {
+                path: filename.to_string(),
+                source,
+            })?;
+        if bytes == 0 {
+            break;
+        }
+        // a \r before the newline is part of the last item, as with cut
+        let text = line.strip_suffix(b"\n").unwrap_or(&line);
+        let between = config.output_delimiter.as_deref().unwrap_or_default();
+        match &config.extract {
+            Extract::Bytes(list) => {
+                write_joined(out, list.spans(text.len()).map(|span| &text[span]), between)
+            }
+            Extract::Chars(list) => {
+                let text = std::str::from_utf8(text).map_err(|e| Error::InvalidUtf8 {
+                    path: filename.to_string(),
+                    offset: offset + e.valid_up_to() as u64,
+                })?;
+                // where each char starts, and where the last one ends
+                let bounds: Vec<usize> = text
+                    .char_indices()
+                    .map(|(i, _)| i)
+                    .chain([text.len()])
+                    .collect();
+                let chars = list
+                    .spans(bounds.len() - 1)
+                    .map(|span| &text.as_bytes()[bounds[span.start]..bounds[span.end]]);
+                write_joined(out, chars, between)
+            }
+            // a line without the delimiter is printed whole
+            Extract::Fields(_) if !text.contains(&config.delimiter) => out.write_all(text),
+            Extract::Fields(list) => {
+                let fields: Vec<&[u8]> = text.split(|b| *b == config.delimiter).collect();
+                let between = config
+                    .output_delimiter
+                    .as_deref()
+                    .unwrap_or(std::slice::from_ref(&config.delimiter));
+                let kept = list.spans(fields.len()).flat_map(|span| &fields[span]);
+                write_joined(out, kept.copied(), between)
+            }
+        }
+        .and_then(|_| out.write_all(b"\n"))
+        .map_err(Error::WriteFailed)?;
+        offset += bytes as u64;
+    }
+    Ok(())
+}
+
+// -- --csv : records rather than lines, fields rather than bytes
+// - a record with a single field is printed whole, like a line without
+// the delimiter
+fn cut_csv<R: BufRead, W: Write>(
+    input: R,
+    filename: &str,
+    config: &Config,
+    list: &PositionList,
+    out: &mut W,
+) -> MyResult<()> {
+    let between = match config.output_delimiter.as_deref() {
+        None => config.delimiter,
+        Some([byte]) => *byte,
+        Some(other) => {
+            return Err(Error::InvalidDelimiter(
+                String::from_utf8_lossy(other).into_owned(),
+            ))
+        }
+    };
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        // records don't all need the same number of fields
+        .flexible(true)
+        .delimiter(config.delimiter)
+        .from_reader(input);
+    let mut writer = csv::WriterBuilder::new()
+        .flexible(true)
+        .delimiter(between)
+        .from_writer(out);
+
+    let mut record = csv::ByteRecord::new();
+    loop {
+        match reader.read_byte_record(&mut record) {
+            Ok(false) => break,
+            Ok(true) => {}
+            Err(e) => return Err(csv_read_failed(filename, e)),
+        }
+        let written = match record.len() {
+            1 => writer.write_byte_record(&record),
+            len => writer.write_record(list.spans(len).flat_map(|span| span.map(|i| &record[i]))),
+        };
+        written.map_err(|e| Error::WriteFailed(e.into()))?;
+    }
+    writer.flush().map_err(Error::WriteFailed)
+}
+
+// -- the reader's own io::Error is a ReadFailed like any other, anything
+// else is the input's fault
+fn csv_read_failed(filename: &str, e: csv::Error) -> Error {
+    let path = filename.to_string();
+    if !e.is_io_error() {
+        return Error::InvalidCsv { path, source: e };
+    }
+    match e.into_kind() {
+        csv::ErrorKind::Io(source) => Error::ReadFailed { path, source },
+        _ => unreachable!("is_io_error() said Io"),
+    }
+}
+
+// -- items with `between` in between
+fn write_joined<'a, W: Write>(
+    out: &mut W,
+    items: impl Iterator<Item = &'a [u8]>,
+    between: &[u8],
+) -> io::Result<()> {
+    for (i, item) in items.enumerate() {
+        if i > 0 {
+            out.write_all(between)?;
+        }
+        out.write_all(item)?;
+    }
+    Ok(())
+}
+
+pub fn get_args() -> MyResult<Config> {
+    let matches = App::new("cutr")
+        .version("0.1.0")
+        .author("MTON <mton@aol.com>")
+        .about("Rust cut")
+        .arg(
+            Arg::with_name("files")
+                .value_name("FILE")
+                .help("Input file(s)")
+                .multiple(true)
+                .default_value("-")
+        )
+        .arg(
+            Arg::with_name("fields")
+                .short("f")
+                .long("fields")
+                .value_name("LIST")
+                .help("Selected fields, e.g. 1-3,5,7-")
+                .allow_hyphen_values(true)
+        )
+        .arg(
+            Arg::with_name("bytes")
+                .short("b")
+                .long("bytes")
+                .value_name("LIST")
+                .help("Selected bytes")
+                .allow_hyphen_values(true)
+        )
+        .arg(
+            Arg::with_name("chars")
+                .short("c")
+                .long("characters")
+                .value_name("LIST")
+                .help("Selected characters")
+                .allow_hyphen_values(true)
+        )
+        // exactly one of -f, -b, -c
+        .group(
+            ArgGroup::with_name("extract")
+                .args(&["fields", "bytes", "chars"])
+                .required(true)
+        )
+        .arg(
+            Arg::with_name("delimiter")
+                .short("d")
+                .long("delimiter")
+                .value_name("DELIM")
+                .help("Field delimiter [default: tab, comma with --csv]")
+                .requires("fields")
+        )
+        .arg(
+            Arg::with_name("output_delimiter")
+                .long("output-delimiter")
+                .value_name("STRING")
+                .help("Put STRING between fields, or between byte / character ranges")
+        )
+        .arg(
+            Arg::with_name("complement")
+                .long("complement")
+                .help("Keep everything but the selected fields, bytes or characters")
+                .takes_value(false)
+        )
+        .arg(
+            Arg::with_name("csv")
+                .long("csv")
+                .help("Parse fields as CSV, quoted delimiters and newlines don't split")
+                .takes_value(false)
+                .requires("fields")
+        )
+        .arg(
+            Arg::with_name("no_decompress")
+                .long("no-decompress")
+                .help("Pass compressed input through undecoded")
+                .takes_value(false)
+        )
+        .get_matches();
+
+    let csv = matches.is_present("csv");
+    let delimiter = match matches.value_of("delimiter") {
+        Some(val) => match val.as_bytes() {
+            [byte] => *byte,
+            _ => return Err(Error::InvalidDelimiter(val.to_string())),
+        },
+        None if csv => b',',
+        None => b'\t',
+    };
+
+    let list = |name| {
+        matches
+            .value_of(name)
+            .map(|val| {
+                let list = PositionList::parse(val).map_err(|reason| Error::InvalidList {
+                    list: val.to_string(),
+                    reason,
+                })?;
+                Ok(match matches.is_present("complement") {
+                    true => list.complement(),
+                    false => list,
+                })
+            })
+            .transpose()
+    };
+    // the group above makes sure one of them is there
+    let extract = match (list("fields")?, list("bytes")?, list("chars")?) {
+        (Some(fields), _, _) => Extract::Fields(fields),
+        (_, Some(bytes), _) => Extract::Bytes(bytes),
+        (_, _, Some(chars)) => Extract::Chars(chars),
+        _ => unreachable!("clap requires -f, -b or -c"),
+    };
+
+    Ok(Config {
+        files: matches.values_of_lossy("files").unwrap(),
+        extract,
+        delimiter,
+        output_delimiter: matches
+            .value_of("output_delimiter")
+            .map(|val| val.as_bytes().to_vec()),
+        csv,
+        decompress: !matches.is_present("no_decompress"),
+    })
+}
+
+// -- "-" is stdin, compressed input is decoded on the fly, see ior::open()
+fn open(filename: &str, decompress: bool) -> MyResult<Box<dyn BufRead>> {
+    ior::open(filename, decompress).map_err(|source| Error::OpenFailed {
+        path: filename.to_string(),
+        source,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cut, Config, Extract, PositionList};
+    use std::io::Cursor;
+
+    fn config(extract: Extract, csv: bool) -> Config {
+        Config {
+            files: vec![],
+            extract,
+            delimiter: if csv { b',' } else { b'\t' },
+            output_delimiter: None,
+            csv,
+            decompress: false,
+        }
+    }
+
+    fn run_cut(input: &str, config: &Config) -> String {
+        let mut out = vec![];
+        cut(Cursor::new(input), "-", config, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_cut() {
+        let list = |val| PositionList::parse(val).unwrap();
+        let fields = config(Extract::Fields(list("1,3")), false);
+        // a line without the delimiter is kept whole
+        assert_eq!(run_cut("a\tb\tc\nnone\n", &fields), "a\tc\nnone\n");
+        let chars = config(Extract::Chars(list("2-3")), false);
+        assert_eq!(run_cut("héllo\r\n", &chars), "él\n");
+        let bytes = config(Extract::Bytes(list("4-")), false);
+        assert_eq!(run_cut("abc\r\n", &bytes), "\r\n");
+        let csv = config(Extract::Fields(list("2")), true);
+        assert_eq!(run_cut("a,\"b,c\"\n", &csv), "\"b,c\"\n");
+    }
+}
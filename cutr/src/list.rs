@@ -0,0 +1,145 @@
+use std::ops::Range;
+
+// == which fields, bytes or characters to keep : -f / -b / -c LIST
+// - LIST is comma separated N, N-M, N- (to the end) or -M (from the
+// first), positions counted from 1
+// - like GNU cut, items come out in input order whatever the order of
+// LIST, each once : `-f 3,1` is `-f 1,3`
+// - overlapping ranges merge, adjacent ones don't : `-b 1-2,3-4` is two
+// ranges, which --output-delimiter shows
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PositionList {
+    // sorted, 1 based, both ends included, usize::MAX for an open end
+    ranges: Vec<(usize, usize)>,
+}
+
+impl PositionList {
+    pub fn parse(list: &str) -> Result<PositionList, &'static str> {
+        let mut ranges = list
+            .split(',')
+            .map(parse_range)
+            .collect::<Result<Vec<_>, _>>()?;
+        ranges.sort_unstable();
+
+        let mut merged: Vec<(usize, usize)> = vec![];
+        for (lo, hi) in ranges {
+            match merged.last_mut() {
+                Some(last) if lo <= last.1 => last.1 = last.1.max(hi),
+                _ => merged.push((lo, hi)),
+            }
+        }
+        Ok(PositionList { ranges: merged })
+    }
+
+    // -- every position this list leaves out (--complement)
+    pub fn complement(&self) -> PositionList {
+        let mut ranges = vec![];
+        // first position after the last range, None past an open end
+        let mut next = Some(1);
+        for &(lo, hi) in &self.ranges {
+            match next {
+                Some(next) if lo > next => ranges.push((next, lo - 1)),
+                _ => {}
+            }
+            next = hi.checked_add(1);
+        }
+        if let Some(next) = next {
+            ranges.push((next, usize::MAX));
+        }
+        PositionList { ranges }
+    }
+
+    // -- the selected ranges of `len` items, 0 based, empty ones left out
+    pub fn spans(&self, len: usize) -> impl Iterator<Item = Range<usize>> + '_ {
+        self.ranges
+            .iter()
+            .map(move |&(lo, hi)| (lo - 1)..hi.min(len))
+            .filter(|span| !span.is_empty())
+    }
+}
+
+// -- N, N-M, N- or -M
+fn parse_range(val: &str) -> Result<(usize, usize), &'static str> {
+    let (lo, hi) = match val.split_once('-') {
+        None => {
+            let n = parse_position(val)?;
+            (n, n)
+        }
+        Some(("", "")) => return Err("range with no endpoint"),
+        Some(("", hi)) => (1, parse_position(hi)?),
+        Some((lo, "")) => (parse_position(lo)?, usize::MAX),
+        Some((lo, hi)) => (parse_position(lo)?, parse_position(hi)?),
+    };
+    match lo <= hi {
+        true => Ok((lo, hi)),
+        false => Err("decreasing range"),
+    }
+}
+
+fn parse_position(val: &str) -> Result<usize, &'static str> {
+    // parse() takes a leading +, a position doesn't
+    if !val.bytes().all(|b| b.is_ascii_digit()) {
+        return Err("not a position");
+    }
+    match val.parse() {
+        Ok(0) => Err("positions are numbered from 1"),
+        Ok(n) => Ok(n),
+        Err(_) => Err("not a position"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PositionList;
+
+    fn spans(list: &str, len: usize) -> Vec<(usize, usize)> {
+        PositionList::parse(list)
+            .unwrap()
+            .spans(len)
+            .map(|span| (span.start, span.end))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(spans("1", 5), vec![(0, 1)]);
+        assert_eq!(spans("2-3", 5), vec![(1, 3)]);
+        assert_eq!(spans("4-", 5), vec![(3, 5)]);
+        assert_eq!(spans("-2", 5), vec![(0, 2)]);
+        // input order, overlaps merged, adjacent ranges kept apart
+        assert_eq!(spans("3,1", 5), vec![(0, 1), (2, 3)]);
+        assert_eq!(spans("1-3,2-4", 5), vec![(0, 4)]);
+        assert_eq!(spans("1-2,3-4", 5), vec![(0, 2), (2, 4)]);
+        // past the end of the line : nothing
+        assert_eq!(spans("2,7-9", 5), vec![(1, 2)]);
+
+        for (bad, reason) in [
+            ("0", "positions are numbered from 1"),
+            ("0-2", "positions are numbered from 1"),
+            ("3-1", "decreasing range"),
+            ("-", "range with no endpoint"),
+            ("a", "not a position"),
+            ("+1", "not a position"),
+            ("1,,2", "not a position"),
+            ("", "not a position"),
+        ] {
+            assert_eq!(PositionList::parse(bad), Err(reason), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_complement() {
+        let complement = |list: &str, len| -> Vec<(usize, usize)> {
+            PositionList::parse(list)
+                .unwrap()
+                .complement()
+                .spans(len)
+                .map(|span| (span.start, span.end))
+                .collect()
+        };
+        assert_eq!(complement("2", 4), vec![(0, 1), (2, 4)]);
+        assert_eq!(complement("1-2,4-", 9), vec![(2, 3)]);
+        assert_eq!(complement("1-", 9), vec![]);
+        assert_eq!(complement("1-2,3-4", 5), vec![(4, 5)]);
+    }
+}
@@ -0,0 +1,14 @@
+use ior::Failure;
+
+fn main() {
+    if let Err(e) = cutr::get_args().and_then(cutr::run) {
+        // stdout closed early (`cutr -f 1 big.tsv | headr -n 1`), see
+        // ior::PipePolicy
+        if e.is_broken_pipe() {
+            std::process::exit(ior::PipePolicy::from_env().exit_code());
+        }
+        eprintln!("{}", e);
+        // each kind of failure has its own status, see cutr::Error
+        std::process::exit(e.exit_code());
+    }
+}
@@ -0,0 +1,181 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+use std::fs;
+
+type TestResult = Result<(), Box<dyn Error>>;
+
+// expected output is GNU cut's, see mk-outs.sh for the hand kept ones
+const PRG: &str = "cutr";
+const EMPTY: &str = "tests/inputs/empty.txt";
+const BOOKS_TSV: &str = "tests/inputs/books.tsv";
+const BOOKS_TSV_GZ: &str = "tests/inputs/books.tsv.gz";
+const BOOKS_CSV: &str = "tests/inputs/books.csv";
+const UTF8: &str = "tests/inputs/utf8.txt";
+
+// ----------------------------------------------------------------------------
+fn run(args: &[&str], expected_file: &str) -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(args)
+        .assert()
+        .success()
+        .stdout(fs::read(expected_file)?);
+    Ok(())
+}
+
+// -- the same arguments on books.tsv and on empty.txt
+fn run_both(args: &[&str], suffix: &str) -> TestResult {
+    for (input, name) in [(BOOKS_TSV, "books.tsv"), (EMPTY, "empty.txt")] {
+        let mut all = args.to_vec();
+        all.push(input);
+        run(&all, &format!("tests/expected/{}.{}.out", name, suffix))?;
+    }
+    Ok(())
+}
+
+// ----------------------------------------------------------------------------
+#[test]
+fn fields() -> TestResult {
+    run_both(&["-f", "1"], "f1")?;
+    run_both(&["--fields", "2"], "f2")?;
+    run_both(&["-f", "2-"], "f2-")?;
+    run_both(&["-f", "-2"], "f-2")
+}
+
+#[test]
+fn fields_input_order() -> TestResult {
+    // like cut, not in the order asked for
+    run_both(&["-f", "3,1"], "f3,1")
+}
+
+#[test]
+fn fields_complement() -> TestResult {
+    run_both(&["-f", "2", "--complement"], "f2.complement")
+}
+
+#[test]
+fn fields_output_delimiter() -> TestResult {
+    run_both(&["-f", "1,3", "--output-delimiter", " | "], "f1,3.od")
+}
+
+#[test]
+fn fields_comma_delimiter() -> TestResult {
+    // quotes mean nothing without --csv
+    run(
+        &["-d", ",", "-f", "1,3", BOOKS_CSV],
+        "tests/expected/books.csv.d,f1,3.out",
+    )
+}
+
+#[test]
+fn bytes() -> TestResult {
+    run_both(&["-b", "1-4"], "b1-4")?;
+    run_both(&["--bytes", "3-5", "--complement"], "b3-5.complement")
+}
+
+#[test]
+fn bytes_output_delimiter() -> TestResult {
+    // between ranges, adjacent ones included
+    run_both(&["-b", "1-2,3-4,8-", "--output-delimiter", ":"], "b.od")
+}
+
+#[test]
+fn chars() -> TestResult {
+    run(&["-c", "1,3,5", UTF8], "tests/expected/utf8.txt.c1,3,5.out")?;
+    run(
+        &["--characters", "1,3-", "--output-delimiter", ":", UTF8],
+        "tests/expected/utf8.txt.c1,3-.od.out",
+    )
+}
+
+#[test]
+fn csv() -> TestResult {
+    run(
+        &["--csv", "-f", "1,3", BOOKS_CSV],
+        "tests/expected/books.csv.csv.f1,3.out",
+    )?;
+    // fields re-quoted for the new delimiter
+    run(
+        &["--csv", "-f", "3,1", "--output-delimiter", ";", BOOKS_CSV],
+        "tests/expected/books.csv.csv.f1,3.od.out",
+    )
+}
+
+#[test]
+fn all() -> TestResult {
+    run(&["-f", "1", BOOKS_TSV, EMPTY, BOOKS_TSV], "tests/expected/all.f1.out")
+}
+
+#[test]
+fn stdin_gz() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["-f", "1"])
+        .write_stdin(fs::read(BOOKS_TSV_GZ)?)
+        .assert()
+        .success()
+        .stdout(fs::read("tests/expected/books.tsv.f1.out")?);
+    run(&["-f", "1", BOOKS_TSV_GZ], "tests/expected/books.tsv.f1.out")
+}
+
+#[test]
+fn skips_bad_file() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["-f", "1", "no-such-file", BOOKS_TSV])
+        .assert()
+        .success()
+        .stderr(predicate::str::starts_with("Failed to open no-such-file"))
+        .stdout(fs::read("tests/expected/books.tsv.f1.out")?);
+    Ok(())
+}
+
+// ----------------------------------------------------------------------------
+#[test]
+fn dies_bad_args() -> TestResult {
+    for (args, code, error) in [
+        (&["-f", "0"][..], 2, "illegal list -- 0 (positions are numbered from 1)\n"),
+        (&["-b", "3-1"][..], 2, "illegal list -- 3-1 (decreasing range)\n"),
+        (&["-c", "a"][..], 2, "illegal list -- a (not a position)\n"),
+        (&["-f", "1", "-d", "::"][..], 2, "illegal delimiter -- :: (must be a single byte)\n"),
+        (
+            &["--csv", "-f", "1", "--output-delimiter", "::", BOOKS_CSV][..],
+            2,
+            "illegal delimiter -- :: (must be a single byte)\n",
+        ),
+    ] {
+        Command::cargo_bin(PRG)?
+            .args(args)
+            .assert()
+            .code(code)
+            .stderr(error);
+    }
+    Ok(())
+}
+
+#[test]
+fn dies_bad_usage() -> TestResult {
+    // one of -f, -b, -c, and -d / --csv only with -f
+    for args in [
+        &[][..],
+        &["-f", "1", "-b", "1"][..],
+        &["-b", "1", "-d", ","][..],
+        &["-c", "1", "--csv"][..],
+    ] {
+        Command::cargo_bin(PRG)?.args(args).assert().failure();
+    }
+    Ok(())
+}
+
+#[test]
+fn dies_invalid_utf8() -> TestResult {
+    // -c needs text, -b takes any bytes
+    Command::cargo_bin(PRG)?
+        .args(["-c", "1", "--no-decompress", BOOKS_TSV_GZ])
+        .assert()
+        .code(5)
+        .stderr(predicate::str::contains("stream did not contain valid UTF-8 at byte 1"));
+    Command::cargo_bin(PRG)?
+        .args(["-b", "1", "--no-decompress", BOOKS_TSV_GZ])
+        .assert()
+        .success();
+    Ok(())
+}
@@ -0,0 +1,102 @@
+use assert_cmd::Command;
+use proptest::prelude::*;
+use std::collections::HashMap;
+use std::io::Write;
+use std::process::Output;
+use std::sync::Mutex;
+
+// == differential tests : one of our tools and its GNU coreutils original
+// on the same generated input, for catr, headr and wcr's tests/diff.rs
+// - proptest shrinks a difference to the smallest input and flags that
+// still show it, kept in tests/diff.proptest-regressions and replayed
+// first from then on
+// - both sides get the input as it was generated and are compared as they
+// print it, byte for byte : where we part ways with GNU on purpose the
+// property is #[ignore]d as a known failure, named after what differs,
+// not bent until it passes
+// - no GNU tool : nothing to compare to, the properties fail unless
+// DIFFTEST_SKIP is set, then they pass with a notice on stderr
+// - PROPTEST_CASES=10000 for a longer run, `cargo test -- --ignored` to
+// see the known failures fail
+
+// -- the GNU tool named by $`var` (gcat, ghead ... on macOS), else
+// `default`, None when it doesn't say it's GNU's
+// - asked once per test binary
+pub fn reference(var: &str, default: &str) -> Option<String> {
+    static FOUND: Mutex<Option<HashMap<String, Option<String>>>> = Mutex::new(None);
+    let mut found = FOUND.lock().unwrap_or_else(|e| e.into_inner());
+    found
+        .get_or_insert_with(HashMap::new)
+        .entry(var.to_string())
+        .or_insert_with(|| {
+            let tool = std::env::var(var).unwrap_or_else(|_| default.to_string());
+            let version = std::process::Command::new(&tool).arg("--version").output().ok()?;
+            let gnu = String::from_utf8_lossy(&version.stdout).contains("GNU coreutils");
+            gnu.then_some(tool)
+        })
+        .clone()
+}
+
+// -- words, unicode, raw bytes (bad UTF-8, NUL, controls), newlines, CRLF
+// and blank lines in any order, the last line as often as not without its
+// newline
+pub fn input() -> impl Strategy<Value = Vec<u8>> {
+    let piece = prop_oneof![
+        "[a-z \t]{0,10}".prop_map(String::into_bytes),
+        "\\PC{0,4}".prop_map(String::into_bytes),
+        prop::collection::vec(any::<u8>(), 0..4),
+        Just(b"\n".to_vec()),
+        Just(b"\r\n".to_vec()),
+        Just(b"\n\n".to_vec()),
+    ];
+    prop::collection::vec(piece, 0..16).prop_map(|pieces| pieces.concat())
+}
+
+// -- (ours, GNU's) on `input`, from stdin or from one temp file both are
+// given, so a file name in the output is the same on both sides
+// - a regular file is memory mapped or spliced (see ior), stdin streamed
+// - None without a GNU `default` (or $`var`) and with DIFFTEST_SKIP set,
+// see reference() and missing()
+pub fn both(
+    prg: &str,
+    (var, default): (&str, &str),
+    args: &[&str],
+    input: &[u8],
+    from_file: bool,
+) -> Option<(Output, Output)> {
+    let Some(gnu) = reference(var, default) else {
+        missing(var, default);
+        return None;
+    };
+    let file = from_file.then(|| {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(input).unwrap();
+        file
+    });
+    let run = |mut cmd: Command| {
+        // wc -m needs a UTF-8 locale, the others don't mind one
+        cmd.args(args).env("LC_ALL", "C.UTF-8");
+        match &file {
+            Some(file) => cmd.arg(file.path()).output().unwrap(),
+            None => cmd.write_stdin(input).output().unwrap(),
+        }
+    };
+    Some((run(Command::cargo_bin(prg).unwrap()), run(Command::new(gnu))))
+}
+
+// -- no GNU tool to compare to : a failure, so a missing coreutils can't
+// pass for a match, or with DIFFTEST_SKIP set one notice per tool
+fn missing(var: &str, default: &str) {
+    if std::env::var_os("DIFFTEST_SKIP").is_none() {
+        panic!(
+            "no GNU coreutils {} to compare to : set ${} to one, or DIFFTEST_SKIP=1 to skip",
+            default, var
+        );
+    }
+    static NOTICED: Mutex<Vec<String>> = Mutex::new(vec![]);
+    let mut noticed = NOTICED.lock().unwrap_or_else(|e| e.into_inner());
+    if !noticed.iter().any(|v| v == var) {
+        eprintln!("difftest: skipped, no GNU coreutils {} (${} to set one)", default, var);
+        noticed.push(var.to_string());
+    }
+}
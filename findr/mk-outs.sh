@@ -0,0 +1,56 @@
+#!/usr/bin/env bash
+
+# expected output comes from GNU find (findutils), sorted : find lists a
+# directory in the order the filesystem hands it out, findr by name
+# - on macOS : `brew install findutils` and run with FIND=gfind
+# - findr --size counts bytes without a unit, find 512-byte blocks, so
+# sizes here all carry one
+# - --name is a regex, find's -name a glob : each is written with the
+# other's equivalent
+# - directories' sizes depend on the filesystem, --size is only tested on
+# files
+FIND="${FIND:-find}"
+export LC_ALL=C
+
+set -u
+
+ROOT="tests/inputs"
+OUT_DIR="tests/expected"
+
+[[ ! -d "$OUT_DIR" ]] && mkdir -p "$OUT_DIR"
+
+run() {
+  local OUT="$1"
+  shift
+  $FIND "$@" | sort > "$OUT_DIR/$OUT.out"
+}
+
+run all $ROOT
+for TYPE in f d l; do
+  run type-$TYPE $ROOT -type $TYPE
+done
+run type-f-l $ROOT \( -type f -o -type l \)
+
+# --name csv, --name '\.mp3$', --name csv --name mp3
+run name-csv $ROOT -name '*csv*'
+run name-mp3 $ROOT -name '*.mp3'
+run name-csv-mp3 $ROOT \( -name '*csv*' -o -name '*mp3*' \)
+# --glob '*.txt' --type f, --glob 'd*' --name '^a'
+run glob-txt-f $ROOT -name '*.txt' -type f
+run glob-d-name-a $ROOT \( -name 'd*' -o -name 'a*' \)
+
+# --type f with --size +1000c, -1k, 1k, +1k -3k, -1M
+run size-plus-1000c $ROOT -type f -size +1000c
+run size-minus-1k $ROOT -type f -size -1k
+run size-1k $ROOT -type f -size 1k
+run size-plus-1k-minus-3k $ROOT -type f -size +1k -size -3k
+run size-minus-1M $ROOT -type f -size -1M
+
+# --min-depth 1 --max-depth 1, --min-depth 3, --max-depth 0, two paths
+run depth-1 $ROOT -mindepth 1 -maxdepth 1
+run min-depth-3 $ROOT -mindepth 3
+run max-depth-0 $ROOT -maxdepth 0
+run a-f $ROOT/a $ROOT/f -type f
+
+# --print0 : sorted on NULs, with GNU sort -z
+$FIND $ROOT -name '*.txt' -print0 | sort -z > "$OUT_DIR/print0-txt.out"
@@ -0,0 +1,47 @@
+use std::io;
+use thiserror::Error;
+
+// == everything findr can fail with
+// - a path or directory that fails to open is reported and skipped, not
+// returned
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Invalid pattern \"{pattern}\"")]
+    InvalidRegex {
+        pattern: String,
+        source: regex::Error,
+    },
+
+    #[error("Invalid glob \"{pattern}\"")]
+    InvalidGlob {
+        pattern: String,
+        source: globset::Error,
+    },
+
+    // --size, --mtime, --min-depth, --max-depth
+    #[error("illegal {what} -- {value}")]
+    InvalidValue { what: &'static str, value: String },
+
+    #[error("Failed to open {path}: {source}")]
+    OpenFailed { path: String, source: io::Error },
+
+    #[error("write error: {0}")]
+    WriteFailed(#[source] io::Error),
+}
+
+impl ior::Failure for Error {
+    fn exit_code(&self) -> i32 {
+        match self {
+            Error::InvalidRegex { .. } | Error::InvalidGlob { .. } | Error::InvalidValue { .. } => 2,
+            Error::OpenFailed { .. } => 3,
+            Error::WriteFailed(_) => 6,
+        }
+    }
+
+    fn write_error(&self) -> Option<&io::Error> {
+        match self {
+            Error::WriteFailed(e) => Some(e),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use std::time::Duration;
+
+// == --size and --mtime : a number, compared the way find compares it
+// - `+N` more than N, `-N` less than N, `N` exactly N
+// - the value is counted in whole units, rounded up for sizes and down for
+// days, so `--size -1k` only matches empty files and `--mtime 0` anything
+// modified in the last 24 hours, like find
+
+const DAY: u64 = 24 * 60 * 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cmp {
+    Less(u64),
+    Exactly(u64),
+    More(u64),
+}
+
+impl Cmp {
+    // -- the comparison and what follows the digits, e.g. a unit
+    fn parse(val: &str) -> Option<(Cmp, &str)> {
+        let (sign, rest) = match val.as_bytes().first() {
+            Some(b'+') | Some(b'-') => val.split_at(1),
+            _ => ("", val),
+        };
+        let at = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+        let (digits, suffix) = rest.split_at(at);
+        let n = digits.parse().ok()?;
+        let cmp = match sign {
+            "+" => Cmp::More(n),
+            "-" => Cmp::Less(n),
+            _ => Cmp::Exactly(n),
+        };
+        Some((cmp, suffix))
+    }
+
+    pub fn matches(&self, value: u64) -> bool {
+        match *self {
+            Cmp::Less(n) => value < n,
+            Cmp::Exactly(n) => value == n,
+            Cmp::More(n) => value > n,
+        }
+    }
+}
+
+// -- --size [+-]N[c|k|M|G], bytes without a unit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Size {
+    pub cmp: Cmp,
+    // bytes per unit
+    pub unit: u64,
+}
+
+impl Size {
+    pub fn parse(val: &str) -> Option<Size> {
+        let (cmp, suffix) = Cmp::parse(val)?;
+        let unit = match suffix {
+            "" | "c" => 1,
+            "k" => 1 << 10,
+            "M" => 1 << 20,
+            "G" => 1 << 30,
+            _ => return None,
+        };
+        Some(Size { cmp, unit })
+    }
+
+    pub fn matches(&self, bytes: u64) -> bool {
+        self.cmp.matches(bytes.div_ceil(self.unit))
+    }
+}
+
+// -- --mtime [+-]N, in days
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Age {
+    pub cmp: Cmp,
+}
+
+impl Age {
+    pub fn parse(val: &str) -> Option<Age> {
+        match Cmp::parse(val)? {
+            (cmp, "") => Some(Age { cmp }),
+            _ => None,
+        }
+    }
+
+    // -- `age` : how long ago the file was modified
+    pub fn matches(&self, age: Duration) -> bool {
+        self.cmp.matches(age.as_secs() / DAY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Age, Cmp, Size, DAY};
+    use std::time::Duration;
+
+    #[test]
+    fn test_size() {
+        let size = Size::parse("+10k").unwrap();
+        assert_eq!(size, Size { cmp: Cmp::More(10), unit: 1024 });
+        assert!(size.matches(10 * 1024 + 1) && !size.matches(10 * 1024));
+        // rounded up : anything but an empty file is at least 1k
+        let size = Size::parse("-1k").unwrap();
+        assert!(size.matches(0) && !size.matches(1));
+        let size = Size::parse("100").unwrap();
+        assert!(size.matches(100) && !size.matches(101));
+        for bad in ["", "+", "k", "1x", "1kk", "--1", "1.5M"] {
+            assert_eq!(Size::parse(bad), None, "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_age() {
+        let days = |n: f64| Duration::from_secs((n * DAY as f64) as u64);
+        let age = Age::parse("0").unwrap();
+        assert!(age.matches(days(0.9)) && !age.matches(days(1.0)));
+        let age = Age::parse("+1").unwrap();
+        assert!(age.matches(days(2.0)) && !age.matches(days(1.9)));
+        let age = Age::parse("-2").unwrap();
+        assert!(age.matches(days(1.9)) && !age.matches(days(2.0)));
+        for bad in ["", "1d", "+-1"] {
+            assert_eq!(Age::parse(bad), None, "{}", bad);
+        }
+    }
+}
@@ -0,0 +1,400 @@
+use clap::{App, Arg};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use regex::Regex;
+use std::ffi::OsString;
+use std::io::{self, Write};
+use std::time::{Duration, SystemTime};
+use walkdir::{DirEntry, WalkDir};
+
+mod error;
+mod filter;
+
+pub use error::Error;
+pub use filter::{Age, Cmp, Size};
+
+type MyResult<T> = Result<T, Error>;
+
+// -- --type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryType {
+    Dir,
+    File,
+    Link,
+}
+
+// == find : every entry under each path that passes all the filters, one
+// path a line, or NUL-terminated with --print0 for headr, wcr and catr's
+// --files0-from
+// - built by get_args(), or by hand when findr is used as a library
+// - directories are walked in name order, symlinks are listed but not
+// followed
+#[derive(Debug)]
+pub struct Config {
+    // "." when none are given
+    pub paths: Vec<String>,
+    // --name and --glob, on the entry's own name : any of them matches,
+    // both empty lets everything through
+    pub names: Vec<Regex>,
+    pub globs: GlobSet,
+    // --type : any of them, empty for all
+    pub types: Vec<EntryType>,
+    // --size and --mtime : all of them
+    pub sizes: Vec<Size>,
+    pub mtimes: Vec<Age>,
+    // the paths given are depth 0
+    pub min_depth: usize,
+    pub max_depth: usize,
+    // --print0 : paths end with NUL instead of a newline
+    pub print0: bool,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            paths: vec![".".to_string()],
+            names: vec![],
+            globs: GlobSet::empty(),
+            types: vec![],
+            sizes: vec![],
+            mtimes: vec![],
+            min_depth: 0,
+            max_depth: usize::MAX,
+            print0: false,
+        }
+    }
+}
+
+pub fn run(config: Config) -> MyResult<()> {
+    // buffered unless stdout is a terminal, see ior::stdout()
+    let mut out = ior::stdout();
+    run_with(&config, &mut out, &mut io::stderr().lock())?;
+    out.flush().map_err(Error::WriteFailed)
+}
+
+// -- run() with the output and the per-path errors going where the caller
+// says, e.g. into a Vec<u8>
+// - a path or directory that fails to open is reported to `err` and
+// skipped, the walk goes on
+pub fn run_with<W: Write, E: Write>(config: &Config, out: &mut W, err: &mut E) -> MyResult<()> {
+    // one clock for the whole run : --mtime doesn't drift during a long walk
+    let now = SystemTime::now();
+    for path in &config.paths {
+        let walk = WalkDir::new(path)
+            .min_depth(config.min_depth)
+            .max_depth(config.max_depth)
+            .sort_by_file_name();
+        for entry in walk {
+            match entry {
+                Err(e) => {
+                    let failed = e.path().map(|failed| failed.display().to_string());
+                    let failed = failed.unwrap_or_else(|| path.to_string());
+                    report(err, open_failed(&failed, e.into()));
+                }
+                Ok(entry) => match is_match(&entry, config, now) {
+                    Err(e) => report(err, e),
+                    Ok(true) => print(out, &entry, config.print0)?,
+                    Ok(false) => {}
+                },
+            }
+        }
+    }
+    Ok(())
+}
+
+// -- the cheap filters first, the metadata is only read for --size and
+// --mtime
+pub fn is_match(entry: &DirEntry, config: &Config, now: SystemTime) -> MyResult<bool> {
+    let name = entry.file_name();
+    let named = (config.names.is_empty() && config.globs.is_empty())
+        || config.globs.is_match(name)
+        || config
+            .names
+            .iter()
+            .any(|re| re.is_match(&name.to_string_lossy()));
+    if !named || !is_type(entry, &config.types) {
+        return Ok(false);
+    }
+    if config.sizes.is_empty() && config.mtimes.is_empty() {
+        return Ok(true);
+    }
+    let path = entry.path().display().to_string();
+    let metadata = entry
+        .metadata()
+        .map_err(|e| open_failed(&path, e.into()))?;
+    if !config.sizes.iter().all(|size| size.matches(metadata.len())) {
+        return Ok(false);
+    }
+    if config.mtimes.is_empty() {
+        return Ok(true);
+    }
+    let modified = metadata.modified().map_err(|e| open_failed(&path, e))?;
+    // modified in the future : no age at all
+    let age = now.duration_since(modified).unwrap_or(Duration::ZERO);
+    Ok(config.mtimes.iter().all(|mtime| mtime.matches(age)))
+}
+
+fn is_type(entry: &DirEntry, types: &[EntryType]) -> bool {
+    let file_type = entry.file_type();
+    types.is_empty()
+        || types.iter().any(|t| match t {
+            EntryType::Dir => file_type.is_dir(),
+            EntryType::File => file_type.is_file(),
+            EntryType::Link => file_type.is_symlink(),
+        })
+}
+
+// -- the path as found, bytes and all : names that aren't UTF-8 come out
+// the way find prints them
+fn print<W: Write>(out: &mut W, entry: &DirEntry, print0: bool) -> MyResult<()> {
+    let end: &[u8] = match print0 {
+        true => b"\0",
+        false => b"\n",
+    };
+    out.write_all(entry.path().as_os_str().as_encoded_bytes())
+        .and_then(|_| out.write_all(end))
+        .map_err(Error::WriteFailed)
+}
+
+// -- find's -print0, one dash, as --print0 : clap 2 only takes a long
+// flag after two, and `find ... -print0 | xargs -0` is habit
+fn print0_alias(arg: OsString) -> OsString {
+    match arg == "-print0" {
+        true => OsString::from("--print0"),
+        false => arg,
+    }
+}
+
+pub fn get_args() -> MyResult<Config> {
+    let matches = App::new("findr")
+        .version("0.1.0")
+        .author("MTON <mton@aol.com>")
+        .about("Rust find")
+        .arg(
+            Arg::with_name("paths")
+                .value_name("PATH")
+                .help("Search paths")
+                .multiple(true)
+                .default_value(".")
+        )
+        .arg(
+            Arg::with_name("names")
+                .short("n")
+                .long("name")
+                .value_name("NAME")
+                .help("Name regex")
+                .multiple(true)
+                .number_of_values(1)
+        )
+        .arg(
+            Arg::with_name("globs")
+                .short("g")
+                .long("glob")
+                .value_name("GLOB")
+                .help("Name glob (*.txt)")
+                .multiple(true)
+                .number_of_values(1)
+        )
+        .arg(
+            Arg::with_name("types")
+                .short("t")
+                .long("type")
+                .value_name("TYPE")
+                .help("Entry type")
+                .possible_values(&["f", "d", "l"])
+                .multiple(true)
+                .number_of_values(1)
+        )
+        .arg(
+            Arg::with_name("sizes")
+                .short("s")
+                .long("size")
+                .value_name("SIZE")
+                .help("Size in bytes, or c k M G: +N more, -N less, N exactly")
+                .multiple(true)
+                .number_of_values(1)
+                .allow_hyphen_values(true)
+        )
+        .arg(
+            Arg::with_name("mtimes")
+                .short("m")
+                .long("mtime")
+                .value_name("DAYS")
+                .help("Days since modified: +N more, -N less, N exactly")
+                .multiple(true)
+                .number_of_values(1)
+                .allow_hyphen_values(true)
+        )
+        .arg(
+            Arg::with_name("min_depth")
+                .long("min-depth")
+                .value_name("DEPTH")
+                .help("Skip entries above DEPTH, the paths given are 0")
+        )
+        .arg(
+            Arg::with_name("max_depth")
+                .long("max-depth")
+                .value_name("DEPTH")
+                .help("Don't descend below DEPTH")
+        )
+        .arg(
+            Arg::with_name("print0")
+                .short("0")
+                .long("print0")
+                .help("End each path with NUL, for --files0-from (find's -print0 works too)")
+                .takes_value(false)
+        )
+        .get_matches_from(std::env::args_os().map(print0_alias));
+
+    let mut names = vec![];
+    for pattern in matches.values_of("names").into_iter().flatten() {
+        let re = Regex::new(pattern).map_err(|source| Error::InvalidRegex {
+            pattern: pattern.to_string(),
+            source,
+        })?;
+        names.push(re);
+    }
+
+    let patterns = matches.values_of_lossy("globs").unwrap_or_default();
+    let mut globs = GlobSetBuilder::new();
+    for pattern in &patterns {
+        let glob = Glob::new(pattern).map_err(|source| Error::InvalidGlob {
+            pattern: pattern.to_string(),
+            source,
+        })?;
+        globs.add(glob);
+    }
+    let globs = globs.build().map_err(|source| Error::InvalidGlob {
+        pattern: patterns.join(" "),
+        source,
+    })?;
+
+    let types = matches
+        .values_of("types")
+        .into_iter()
+        .flatten()
+        .map(|t| match t {
+            "d" => EntryType::Dir,
+            "f" => EntryType::File,
+            _ => EntryType::Link,
+        })
+        .collect();
+
+    Ok(Config {
+        paths: matches.values_of_lossy("paths").unwrap(),
+        names,
+        globs,
+        types,
+        sizes: parse_all(matches.values_of("sizes"), "size", Size::parse)?,
+        mtimes: parse_all(matches.values_of("mtimes"), "mtime", Age::parse)?,
+        min_depth: parse_depth(matches.value_of("min_depth"), 0)?,
+        max_depth: parse_depth(matches.value_of("max_depth"), usize::MAX)?,
+        print0: matches.is_present("print0"),
+    })
+}
+
+fn parse_all<'a, T>(
+    values: Option<clap::Values<'a>>,
+    what: &'static str,
+    parse: fn(&str) -> Option<T>,
+) -> MyResult<Vec<T>> {
+    values
+        .into_iter()
+        .flatten()
+        .map(|val| {
+            parse(val).ok_or_else(|| Error::InvalidValue {
+                what,
+                value: val.to_string(),
+            })
+        })
+        .collect()
+}
+
+fn parse_depth(val: Option<&str>, default: usize) -> MyResult<usize> {
+    match val {
+        None => Ok(default),
+        Some(val) => val.parse().map_err(|_| Error::InvalidValue {
+            what: "depth",
+            value: val.to_string(),
+        }),
+    }
+}
+
+fn open_failed(path: &str, source: io::Error) -> Error {
+    Error::OpenFailed {
+        path: path.to_string(),
+        source,
+    }
+}
+
+// -- the error sink is best effort, like eprintln! without the panic
+fn report<E: Write>(err: &mut E, e: Error) {
+    let _ = writeln!(err, "{}", e);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run_with, Age, Config, EntryType, Size};
+    use std::fs::{self, File};
+    use std::time::{Duration, SystemTime};
+
+    fn find(config: &Config) -> Vec<String> {
+        let mut out = vec![];
+        run_with(config, &mut out, &mut vec![]).unwrap();
+        String::from_utf8(out).unwrap().lines().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_mtime() {
+        // a fresh checkout gives every fixture the same mtime, these are
+        // set by hand
+        let dir = tempfile::tempdir().unwrap();
+        let now = SystemTime::now();
+        for (name, days) in [("new", 0), ("week", 7), ("year", 365)] {
+            let file = File::create(dir.path().join(name)).unwrap();
+            file.set_modified(now - Duration::from_secs(days * 86400 + 60)).unwrap();
+        }
+        let config = |mtimes: &[&str]| Config {
+            paths: vec![dir.path().display().to_string()],
+            types: vec![EntryType::File],
+            mtimes: mtimes.iter().map(|val| Age::parse(val).unwrap()).collect(),
+            ..Config::default()
+        };
+        let names = |mtimes: &[&str]| -> Vec<String> {
+            find(&config(mtimes))
+                .iter()
+                .map(|path| path.rsplit('/').next().unwrap().to_string())
+                .collect()
+        };
+        assert_eq!(names(&["0"]), ["new"]);
+        assert_eq!(names(&["+6"]), ["week", "year"]);
+        assert_eq!(names(&["+6", "-30"]), ["week"]);
+        assert_eq!(names(&["7"]), ["week"]);
+    }
+
+    #[test]
+    fn test_depth_and_size() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("a/b")).unwrap();
+        fs::write(dir.path().join("a/one"), "1").unwrap();
+        fs::write(dir.path().join("a/b/two"), "22").unwrap();
+        let root = dir.path().display().to_string();
+        let config = Config {
+            paths: vec![root.clone()],
+            min_depth: 1,
+            max_depth: 2,
+            ..Config::default()
+        };
+        let expected: Vec<String> = ["a", "a/b", "a/one"]
+            .iter()
+            .map(|path| format!("{}/{}", root, path))
+            .collect();
+        assert_eq!(find(&config), expected);
+        let config = Config {
+            paths: vec![root.clone()],
+            sizes: vec![Size::parse("+1").unwrap()],
+            types: vec![EntryType::File],
+            ..Config::default()
+        };
+        assert_eq!(find(&config), [format!("{}/a/b/two", root)]);
+    }
+}
@@ -0,0 +1,13 @@
+use ior::Failure;
+
+fn main() {
+    if let Err(e) = findr::get_args().and_then(findr::run) {
+        // stdout closed early (`findr . | headr`), see ior::PipePolicy
+        if e.is_broken_pipe() {
+            std::process::exit(ior::PipePolicy::from_env().exit_code());
+        }
+        eprintln!("{}", e);
+        // each kind of failure has its own status, see findr::Error
+        std::process::exit(e.exit_code());
+    }
+}
@@ -0,0 +1,137 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+use std::fs;
+
+type TestResult = Result<(), Box<dyn Error>>;
+
+// expected output is GNU find's, sorted (see mk-outs.sh) : findr walks each
+// directory in name order, which comes out the same for these names
+const PRG: &str = "findr";
+const ROOT: &str = "tests/inputs";
+
+// ----------------------------------------------------------------------------
+fn run(args: &[&str], expected_file: &str) -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(args)
+        .assert()
+        .success()
+        .stdout(fs::read(expected_file)?);
+    Ok(())
+}
+
+// ----------------------------------------------------------------------------
+#[test]
+fn all() -> TestResult {
+    run(&[ROOT], "tests/expected/all.out")
+}
+
+#[test]
+fn types() -> TestResult {
+    for t in ["f", "d", "l"] {
+        run(&[ROOT, "-t", t], &format!("tests/expected/type-{}.out", t))?;
+    }
+    run(&[ROOT, "--type", "f", "--type", "l"], "tests/expected/type-f-l.out")
+}
+
+#[test]
+fn names() -> TestResult {
+    run(&[ROOT, "-n", "csv"], "tests/expected/name-csv.out")?;
+    run(&[ROOT, "--name", r"\.mp3$"], "tests/expected/name-mp3.out")?;
+    run(&[ROOT, "-n", "csv", "-n", "mp3"], "tests/expected/name-csv-mp3.out")
+}
+
+#[test]
+fn globs() -> TestResult {
+    run(&[ROOT, "-g", "*.txt", "-t", "f"], "tests/expected/glob-txt-f.out")?;
+    // a glob or a regex
+    run(&[ROOT, "--glob", "d*", "--name", "^a"], "tests/expected/glob-d-name-a.out")
+}
+
+#[test]
+fn sizes() -> TestResult {
+    for (size, expected) in [
+        ("+1000c", "size-plus-1000c"),
+        ("+1000", "size-plus-1000c"),
+        ("-1k", "size-minus-1k"),
+        ("1k", "size-1k"),
+        ("-1M", "size-minus-1M"),
+    ] {
+        run(
+            &[ROOT, "-t", "f", "--size", size],
+            &format!("tests/expected/{}.out", expected),
+        )?;
+    }
+    run(
+        &[ROOT, "-t", "f", "-s", "+1k", "-s", "-3k"],
+        "tests/expected/size-plus-1k-minus-3k.out",
+    )
+}
+
+#[test]
+fn depths() -> TestResult {
+    run(&[ROOT, "--min-depth", "1", "--max-depth", "1"], "tests/expected/depth-1.out")?;
+    run(&[ROOT, "--min-depth", "3"], "tests/expected/min-depth-3.out")?;
+    run(&[ROOT, "--max-depth", "0"], "tests/expected/max-depth-0.out")
+}
+
+#[test]
+fn paths() -> TestResult {
+    run(&["tests/inputs/a", "tests/inputs/f", "-t", "f"], "tests/expected/a-f.out")
+}
+
+#[test]
+fn print0() -> TestResult {
+    run(&[ROOT, "-g", "*.txt", "-0"], "tests/expected/print0-txt.out")?;
+    run(&[ROOT, "-g", "*.txt", "--print0"], "tests/expected/print0-txt.out")?;
+    // find's spelling
+    run(&[ROOT, "-g", "*.txt", "-print0"], "tests/expected/print0-txt.out")
+}
+
+// ----------------------------------------------------------------------------
+#[test]
+fn skips_bad_path() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["no-such-dir", "tests/inputs/f"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Failed to open no-such-dir"))
+        .stdout("tests/inputs/f\ntests/inputs/f/f.txt\n");
+    Ok(())
+}
+
+#[test]
+fn dies_bad_values() -> TestResult {
+    for (args, message, code) in [
+        (&["-n", "*.csv"][..], "Invalid pattern \"*.csv\"", 2),
+        (&["-g", "a[b"], "Invalid glob \"a[b\"", 2),
+        (&["-s", "10x"], "illegal size -- 10x", 2),
+        (&["-m", "+1d"], "illegal mtime -- +1d", 2),
+        (&["--max-depth", "x"], "illegal depth -- x", 2),
+        (&["-t", "x"], "isn't a valid value", 1),
+    ] {
+        Command::cargo_bin(PRG)?
+            .args(args)
+            .assert()
+            .code(code)
+            .stderr(predicate::str::contains(message));
+    }
+    Ok(())
+}
+
+#[test]
+fn broken_pipe_exits_quietly() -> TestResult {
+    use std::process::Stdio;
+
+    let mut child = std::process::Command::new(assert_cmd::cargo::cargo_bin(PRG))
+        .arg(ROOT)
+        .env("SIGPIPE_STATUS", "141")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    drop(child.stdout.take());
+    let output = child.wait_with_output()?;
+    assert!(matches!(output.status.code(), Some(0) | Some(141)));
+    assert_eq!(String::from_utf8(output.stderr)?, "");
+    Ok(())
+}
@@ -0,0 +1,65 @@
+#!/usr/bin/env bash
+
+# expected output comes from GNU grep
+# - on macOS : `brew install grep` and run with GREP=ggrep
+# - grep -r walks directories in readdir order, grepr sorts them by name :
+# the -r outputs are sorted here, by file name then line number
+GREP="${GREP:-grep}"
+
+set -u
+
+ROOT="tests/inputs"
+OUT_DIR="tests/expected"
+
+[[ ! -d "$OUT_DIR" ]] && mkdir -p "$OUT_DIR"
+
+EMPTY="$ROOT/empty.txt"
+FOX="$ROOT/fox.txt"
+BUSTLE="$ROOT/the-bustle.txt"
+NOBODY="$ROOT/nobody.txt"
+POEMS="$ROOT/poems"
+
+for FILE in $EMPTY $FOX $BUSTLE $NOBODY; do
+  BASENAME=$(basename "$FILE")
+  $GREP The       $FILE > ${OUT_DIR}/${BASENAME}.the.out
+  $GREP -i the    $FILE > ${OUT_DIR}/${BASENAME}.the.i.out
+  $GREP -v The    $FILE > ${OUT_DIR}/${BASENAME}.the.v.out
+  $GREP -c The    $FILE > ${OUT_DIR}/${BASENAME}.the.c.out
+  $GREP -n -i the $FILE > ${OUT_DIR}/${BASENAME}.the.n.i.out
+  $GREP -o -n 'Nobody\|the' $FILE > ${OUT_DIR}/${BASENAME}.o.n.out
+done
+
+# context : groups joined where they meet, split by -- where they don't
+$GREP -n -A 1 Nobody    $NOBODY > ${OUT_DIR}/nobody.txt.A1.out
+$GREP -n -B 2 Frog      $NOBODY > ${OUT_DIR}/nobody.txt.B2.out
+$GREP -C 1 'How\|Who'   $NOBODY > ${OUT_DIR}/nobody.txt.C1.out
+$GREP -v -n -C 1 'e'    $NOBODY > ${OUT_DIR}/nobody.txt.C1.v.out
+$GREP -A 1 -C 3 eternity $BUSTLE > ${OUT_DIR}/the-bustle.txt.A1.C3.out
+
+# several files : names in front, -- between files too
+ALL="$EMPTY $FOX $BUSTLE $NOBODY"
+$GREP -i the        $ALL > ${OUT_DIR}/all.the.i.out
+$GREP -c -i the     $ALL > ${OUT_DIR}/all.the.c.i.out
+$GREP -l -i nobody  $ALL > ${OUT_DIR}/all.nobody.l.out
+$GREP -n -C 1 -i the $NOBODY $BUSTLE > ${OUT_DIR}/all.the.C1.out
+
+# colors, with grep's default GREP_COLORS
+$GREP --color=always -i the       $BUSTLE > ${OUT_DIR}/the-bustle.txt.color.out
+$GREP --color=always -n -C 1 Frog $NOBODY $BUSTLE > ${OUT_DIR}/all.color.C1.out
+$GREP --color=always -v -n -A 1 e $NOBODY > ${OUT_DIR}/nobody.txt.color.v.out
+$GREP --color=always -o -i nobody $NOBODY > ${OUT_DIR}/nobody.txt.color.o.out
+$GREP --color=always -c -i the    $ALL > ${OUT_DIR}/all.color.c.out
+$GREP --color=always -l -i the    $ALL > ${OUT_DIR}/all.color.l.out
+
+BY_NAME="sort -t: -s -k1,1 -k2,2n"
+$GREP -r -n the  $POEMS | $BY_NAME > ${OUT_DIR}/poems.the.r.n.out
+$GREP -r -c -i the $POEMS | $BY_NAME > ${OUT_DIR}/poems.the.r.c.out
+$GREP -r -l -i nobody $POEMS | $BY_NAME > ${OUT_DIR}/poems.nobody.r.l.out
+(cd $POEMS && $GREP -r on | $BY_NAME) > ${OUT_DIR}/poems.on.r.cwd.out
+
+# stdin
+$GREP -n -i the < $BUSTLE > ${OUT_DIR}/the-bustle.txt.stdin.out
+$GREP -c the - $FOX < $BUSTLE > ${OUT_DIR}/the-bustle.txt.stdin.c.out
+
+# compressed copy : tests expect the same output as the plain file
+gzip -nc $BUSTLE > $ROOT/the-bustle.txt.gz
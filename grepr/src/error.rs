@@ -0,0 +1,46 @@
+use std::io;
+use thiserror::Error;
+
+// == everything grepr can fail with
+// - every one exits 2, like grep's errors : 1 only means nothing was
+// selected (see Status)
+// - a file or directory that fails to open is reported and skipped, not
+// returned
+#[derive(Debug, Error)]
+pub enum Error {
+    // clap's message, usage included
+    #[error("{0}")]
+    Usage(clap::Error),
+
+    #[error("Invalid pattern \"{pattern}\"")]
+    InvalidRegex {
+        pattern: String,
+        source: regex::Error,
+    },
+
+    // -A, -B, -C
+    #[error("illegal context length -- {0}")]
+    InvalidContext(String),
+
+    #[error("Failed to open {path}: {source}")]
+    OpenFailed { path: String, source: io::Error },
+
+    #[error("{path}: {source}")]
+    ReadFailed { path: String, source: io::Error },
+
+    #[error("write error: {0}")]
+    WriteFailed(#[source] io::Error),
+}
+
+impl ior::Failure for Error {
+    fn exit_code(&self) -> i32 {
+        2
+    }
+
+    fn write_error(&self) -> Option<&io::Error> {
+        match self {
+            Error::WriteFailed(e) => Some(e),
+            _ => None,
+        }
+    }
+}
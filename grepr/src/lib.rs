@@ -0,0 +1,525 @@
+use clap::{App, Arg, ErrorKind};
+use regex::bytes::{Regex, RegexBuilder};
+use std::collections::VecDeque;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::Path;
+use walkdir::WalkDir;
+
+mod error;
+mod print;
+
+pub use error::Error;
+pub use print::Printer;
+
+type MyResult<T> = Result<T, Error>;
+
+// == grep : print the lines of each file that match a regex
+// - built by get_args(), or by hand when grepr is used as a library
+// - lines are bytes, matched without their newline, so any input can be
+// searched and `$` anchors at the end of the line
+#[derive(Debug)]
+pub struct Config {
+    // -i already applied
+    pub pattern: Regex,
+    // none : stdin, or the current directory with -r
+    pub files: Vec<String>,
+    // -r : directories are searched, everything under them
+    pub recursive: bool,
+    // lines are prefixed with the file they're from : more than one file,
+    // or a directory with -r
+    pub with_filename: bool,
+    // -v : select the lines that don't match
+    pub invert: bool,
+    // -c : how many lines were selected, per file
+    pub count: bool,
+    // -l : only the names of the files with a selected line
+    pub files_with_matches: bool,
+    // -n
+    pub line_number: bool,
+    // -o : each match on its own, not the whole line
+    pub only_matching: bool,
+    // -B / -A : context lines before and after each selected line
+    pub before: usize,
+    pub after: usize,
+    // --color, already resolved against the terminal
+    pub color: bool,
+    // decode gzip, bzip2, xz and zstd input (off with --no-decompress)
+    pub decompress: bool,
+}
+
+// -- how a search went, grep's exit status
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    // a line was selected, in some file
+    Selected,
+    NoneSelected,
+    // an input was reported and skipped : 2 even when others matched, so
+    // `if grepr ...` can't take a missing file for no match
+    Skipped,
+}
+
+impl Status {
+    pub fn exit_code(self) -> i32 {
+        match self {
+            Status::Selected => 0,
+            Status::NoneSelected => 1,
+            Status::Skipped => 2,
+        }
+    }
+}
+
+pub fn run(config: Config) -> MyResult<Status> {
+    // buffered unless stdout is a terminal, see ior::stdout()
+    let mut out = ior::stdout();
+    let status = run_with(&config, &mut out, &mut io::stderr().lock())?;
+    out.flush().map_err(Error::WriteFailed)?;
+    Ok(status)
+}
+
+// -- run() with the output and the per-file errors going where the caller
+// says, e.g. into a Vec<u8>
+// - a file or directory that fails to open is reported to `err` and
+// skipped
+pub fn run_with<W: Write, E: Write>(config: &Config, out: &mut W, err: &mut E) -> MyResult<Status> {
+    let context = (config.before > 0 || config.after > 0) && !config.only_matching;
+    let mut printer = Printer::new(out, config.color, config.line_number, context);
+    let mut skips = Skips { err, any: false };
+    let mut found = false;
+    if config.files.is_empty() {
+        found = match config.recursive {
+            true => search_dir(".", config, &mut printer, &mut skips)?,
+            false => search_file("-", config, &mut printer, &mut skips)?,
+        };
+    }
+    for filename in &config.files {
+        let found_here = match filename != "-" && Path::new(filename).is_dir() {
+            true if config.recursive => search_dir(filename, config, &mut printer, &mut skips)?,
+            true => {
+                skips.report(open_failed(filename, io::ErrorKind::IsADirectory.into()));
+                false
+            }
+            false => search_file(filename, config, &mut printer, &mut skips)?,
+        };
+        found |= found_here;
+    }
+    Ok(match (skips.any, found) {
+        (true, _) => Status::Skipped,
+        (false, true) => Status::Selected,
+        (false, false) => Status::NoneSelected,
+    })
+}
+
+// -- every file under `dir`, sorted by name in each directory
+// - symlinks found on the way are skipped, like grep -r
+// - the current directory searched by default is left out of the names :
+// `src/lib.rs`, not `./src/lib.rs`
+fn search_dir<W: Write, E: Write>(
+    dir: &str,
+    config: &Config,
+    printer: &mut Printer<W>,
+    skips: &mut Skips<E>,
+) -> MyResult<bool> {
+    let mut found = false;
+    for entry in WalkDir::new(dir).sort_by_file_name() {
+        match entry {
+            Err(e) => {
+                let path = e.path().map(|path| path.display().to_string());
+                let path = path.unwrap_or_else(|| dir.to_string());
+                skips.report(open_failed(&path, e.into()));
+            }
+            Ok(entry) if entry.file_type().is_file() => {
+                let path = entry.path().display().to_string();
+                let path = match config.files.is_empty() {
+                    true => path.strip_prefix("./").unwrap_or(&path),
+                    false => &path,
+                };
+                found |= search_file(path, config, printer, skips)?;
+            }
+            Ok(_) => {}
+        }
+    }
+    Ok(found)
+}
+
+fn search_file<W: Write, E: Write>(
+    filename: &str,
+    config: &Config,
+    printer: &mut Printer<W>,
+    skips: &mut Skips<E>,
+) -> MyResult<bool> {
+    match ior::open(filename, config.decompress) {
+        Err(e) => {
+            skips.report(open_failed(filename, e));
+            Ok(false)
+        }
+        Ok(input) => Ok(grep(input, filename, config, printer)? > 0),
+    }
+}
+
+// -- one input, `filename` names it in errors and in front of its lines,
+// "-" is shown as (standard input)
+// - returns how many lines were selected, -l stops at the first one
+pub fn grep<R: BufRead, W: Write>(
+    mut input: R,
+    filename: &str,
+    config: &Config,
+    printer: &mut Printer<W>,
+) -> MyResult<u64> {
+    let shown = match filename {
+        "-" => "(standard input)",
+        _ => filename,
+    };
+    let name = Some(shown).filter(|_| config.with_filename);
+    let print_lines = !config.count && !config.files_with_matches;
+    let context = print_lines && !config.only_matching;
+    // -B lines not printed yet, the oldest dropped past config.before
+    let mut before: VecDeque<(u64, Vec<u8>)> = VecDeque::with_capacity(config.before);
+    // -A lines still to print after the last selected one
+    let mut after = 0;
+    let mut selected = 0;
+    let mut number = 0;
+    let mut line = vec![];
+    printer.start_file();
+
+    loop {
+        line.clear();
+        let bytes = input
+            .read_until(b'\n', &mut line)
+            .map_err(|source| Error::ReadFailed {
+                path: filename.to_string(),
+                source,
+            })?;
+        if bytes == 0 {
+            break;
+        }
+        number += 1;
+        let text = line.strip_suffix(b"\n").unwrap_or(&line);
+
+        if config.pattern.is_match(text) != config.invert {
+            selected += 1;
+            if config.files_with_matches {
+                printer.name(shown).map_err(Error::WriteFailed)?;
+                return Ok(selected);
+            }
+            if !print_lines {
+                continue;
+            }
+            if config.only_matching {
+                // with -v the line has no match to show
+                for m in config.pattern.find_iter(text).filter(|m| !m.is_empty()) {
+                    printer
+                        .part(name, number, m.as_bytes())
+                        .map_err(Error::WriteFailed)?;
+                }
+                continue;
+            }
+            for (number, text) in before.drain(..) {
+                printer
+                    .line(name, number, b'-', &text, &config.pattern)
+                    .map_err(Error::WriteFailed)?;
+            }
+            printer
+                .line(name, number, b':', text, &config.pattern)
+                .map_err(Error::WriteFailed)?;
+            after = config.after;
+        } else if context && after > 0 {
+            after -= 1;
+            printer
+                .line(name, number, b'-', text, &config.pattern)
+                .map_err(Error::WriteFailed)?;
+        } else if context && config.before > 0 {
+            if before.len() == config.before {
+                before.pop_front();
+            }
+            before.push_back((number, text.to_vec()));
+        }
+    }
+
+    if config.count && !config.files_with_matches {
+        printer.count(name, selected).map_err(Error::WriteFailed)?;
+    }
+    Ok(selected)
+}
+
+pub fn get_args() -> MyResult<Config> {
+    let matches = App::new("grepr")
+        .version("0.1.0")
+        .author("MTON <mton@aol.com>")
+        .about("Rust grep")
+        .arg(
+            Arg::with_name("pattern")
+                .value_name("PATTERN")
+                .help("Search pattern")
+                .required(true)
+        )
+        .arg(
+            Arg::with_name("files")
+                .value_name("FILE")
+                .help("Input file(s), stdin by default (the current directory with -r)")
+                .multiple(true)
+        )
+        .arg(
+            Arg::with_name("insensitive")
+                .short("i")
+                .long("ignore-case")
+                .help("Case-insensitive match")
+                .takes_value(false)
+        )
+        .arg(
+            Arg::with_name("invert")
+                .short("v")
+                .long("invert-match")
+                .help("Select the lines that don't match")
+                .takes_value(false)
+        )
+        .arg(
+            Arg::with_name("count")
+                .short("c")
+                .long("count")
+                .help("Print a count of selected lines per file")
+                .takes_value(false)
+        )
+        .arg(
+            Arg::with_name("files_with_matches")
+                .short("l")
+                .long("files-with-matches")
+                .help("Print only the names of files with a selected line")
+                .takes_value(false)
+        )
+        .arg(
+            Arg::with_name("line_number")
+                .short("n")
+                .long("line-number")
+                .help("Prefix each line with its line number")
+                .takes_value(false)
+        )
+        .arg(
+            Arg::with_name("recursive")
+                .short("r")
+                .long("recursive")
+                .help("Search directories recursively")
+                .takes_value(false)
+        )
+        .arg(
+            Arg::with_name("only_matching")
+                .short("o")
+                .long("only-matching")
+                .help("Print only the matched parts of each line")
+                .takes_value(false)
+        )
+        .arg(
+            Arg::with_name("after")
+                .short("A")
+                .long("after-context")
+                .value_name("NUM")
+                .help("Print NUM lines after each selected line")
+        )
+        .arg(
+            Arg::with_name("before")
+                .short("B")
+                .long("before-context")
+                .value_name("NUM")
+                .help("Print NUM lines before each selected line")
+        )
+        .arg(
+            Arg::with_name("context")
+                .short("C")
+                .long("context")
+                .value_name("NUM")
+                .help("Print NUM lines before and after each selected line")
+        )
+        // --color alone is --color=auto, a bare word after it is the
+        // pattern
+        .arg(
+            Arg::with_name("color")
+                .long("color")
+                .value_name("WHEN")
+                .help("Highlight matches: auto, always or never")
+                .possible_values(&["auto", "always", "never"])
+                .min_values(0)
+                .require_equals(true)
+        )
+        .arg(
+            Arg::with_name("no_decompress")
+                .long("no-decompress")
+                .help("Pass compressed input through undecoded")
+                .takes_value(false)
+        )
+        // clap's own get_matches() exits 1 on a usage error, grep's status
+        // for no match
+        .get_matches_safe()
+        .map_err(|e| match e.kind {
+            ErrorKind::HelpDisplayed | ErrorKind::VersionDisplayed => e.exit(),
+            _ => Error::Usage(e),
+        })?;
+
+    let pattern = matches.value_of("pattern").unwrap();
+    let pattern = RegexBuilder::new(pattern)
+        .case_insensitive(matches.is_present("insensitive"))
+        .build()
+        .map_err(|source| Error::InvalidRegex {
+            pattern: pattern.to_string(),
+            source,
+        })?;
+
+    // -A and -B win over -C
+    let context = parse_context(matches.value_of("context"))?;
+    let before = parse_context(matches.value_of("before"))?.or(context);
+    let after = parse_context(matches.value_of("after"))?.or(context);
+
+    let files = matches.values_of_lossy("files").unwrap_or_default();
+    let recursive = matches.is_present("recursive");
+    let with_filename = files.len() > 1
+        || recursive && files.first().is_none_or(|file| Path::new(file).is_dir());
+
+    let color = match matches.value_of("color").unwrap_or("auto") {
+        "always" => true,
+        "never" => false,
+        _ => io::stdout().is_terminal(),
+    };
+
+    Ok(Config {
+        pattern,
+        files,
+        recursive,
+        with_filename,
+        invert: matches.is_present("invert"),
+        count: matches.is_present("count"),
+        files_with_matches: matches.is_present("files_with_matches"),
+        line_number: matches.is_present("line_number"),
+        only_matching: matches.is_present("only_matching"),
+        before: before.unwrap_or(0),
+        after: after.unwrap_or(0),
+        color,
+        decompress: !matches.is_present("no_decompress"),
+    })
+}
+
+fn parse_context(val: Option<&str>) -> MyResult<Option<usize>> {
+    val.map(|val| val.parse().map_err(|_| Error::InvalidContext(val.to_string())))
+        .transpose()
+}
+
+fn open_failed(path: &str, source: io::Error) -> Error {
+    Error::OpenFailed {
+        path: path.to_string(),
+        source,
+    }
+}
+
+// -- where skipped inputs are reported, remembering there were any
+struct Skips<'a, E: Write> {
+    err: &'a mut E,
+    any: bool,
+}
+
+impl<E: Write> Skips<'_, E> {
+    // -- the error sink is best effort, like eprintln! without the panic
+    fn report(&mut self, e: Error) {
+        self.any = true;
+        let _ = writeln!(self.err, "{}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{grep, run_with, Config, Printer, Status};
+    use regex::bytes::Regex;
+    use std::io::Cursor;
+
+    const TEXT: &str = "one fox\ntwo\nthree fox fox\nfour\nfive\nsix fox\n";
+
+    fn config(pattern: &str) -> Config {
+        Config {
+            pattern: Regex::new(pattern).unwrap(),
+            files: vec![],
+            recursive: false,
+            with_filename: false,
+            invert: false,
+            count: false,
+            files_with_matches: false,
+            line_number: false,
+            only_matching: false,
+            before: 0,
+            after: 0,
+            color: false,
+            decompress: false,
+        }
+    }
+
+    fn search(config: &Config) -> (u64, String) {
+        let context = config.before > 0 || config.after > 0;
+        let mut printer = Printer::new(vec![], config.color, config.line_number, context);
+        let selected = grep(Cursor::new(TEXT), "-", config, &mut printer).unwrap();
+        (selected, String::from_utf8(printer.into_inner()).unwrap())
+    }
+
+    #[test]
+    fn test_grep() {
+        let fox = config("fox");
+        assert_eq!(search(&fox), (3, "one fox\nthree fox fox\nsix fox\n".to_string()));
+
+        let invert = Config {
+            invert: true,
+            count: true,
+            ..config("fox")
+        };
+        assert_eq!(search(&invert), (3, "3\n".to_string()));
+
+        let only = Config {
+            only_matching: true,
+            line_number: true,
+            ..config("fox")
+        };
+        assert_eq!(search(&only).1, "1:fox\n3:fox\n3:fox\n6:fox\n");
+
+        let named = Config {
+            files_with_matches: true,
+            ..config("f")
+        };
+        assert_eq!(search(&named), (1, "(standard input)\n".to_string()));
+    }
+
+    #[test]
+    fn test_context() {
+        // groups that meet are joined, the others split by --
+        let after = Config {
+            after: 1,
+            line_number: true,
+            ..config("^(one|six)")
+        };
+        assert_eq!(search(&after).1, "1:one fox\n2-two\n--\n6:six fox\n");
+        let both = Config {
+            before: 1,
+            after: 1,
+            ..config("three")
+        };
+        assert_eq!(search(&both).1, "two\nthree fox fox\nfour\n");
+    }
+
+    #[test]
+    fn test_color() {
+        let color = Config {
+            color: true,
+            ..config("o")
+        };
+        let mut printer = Printer::new(vec![], true, false, false);
+        grep(Cursor::new("two\n"), "-", &color, &mut printer).unwrap();
+        assert_eq!(
+            String::from_utf8(printer.into_inner()).unwrap(),
+            "tw\x1b[01;31m\x1b[Ko\x1b[m\x1b[K\n"
+        );
+    }
+
+    #[test]
+    fn test_run_with() {
+        let config = Config {
+            files: vec!["no-such-file".to_string()],
+            ..config("fox")
+        };
+        let (mut out, mut err) = (vec![], vec![]);
+        assert_eq!(run_with(&config, &mut out, &mut err).unwrap(), Status::Skipped);
+        assert!(out.is_empty());
+        assert!(String::from_utf8(err).unwrap().starts_with("Failed to open no-such-file"));
+    }
+}
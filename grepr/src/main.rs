@@ -0,0 +1,14 @@
+use ior::Failure;
+
+fn main() {
+    match grepr::get_args().and_then(grepr::run) {
+        Ok(status) => std::process::exit(status.exit_code()),
+        Err(e) if e.is_broken_pipe() => {
+            std::process::exit(ior::PipePolicy::from_env().exit_code());
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(e.exit_code());
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use regex::bytes::Regex;
+use std::io::{self, Write};
+
+// == how selected and context lines come out, the way GNU grep prints them
+// name:12:line    a selected line, `-` instead of `:` for a context line
+// --              between groups of lines that aren't next to each other
+// - with color, the default GREP_COLORS : names magenta, line numbers
+// green, separators cyan, matches bold red
+const NAME: &str = "35";
+const LINE_NO: &str = "32";
+const SEP: &str = "36";
+const MATCH: &str = "01;31";
+
+pub struct Printer<W> {
+    out: W,
+    color: bool,
+    // -n
+    line_numbers: bool,
+    // -A / -B / -C : groups are split by `--`
+    context: bool,
+    // last line printed from the current file
+    last: Option<u64>,
+    // anything printed yet, from any file
+    printed: bool,
+}
+
+impl<W: Write> Printer<W> {
+    pub fn new(out: W, color: bool, line_numbers: bool, context: bool) -> Printer<W> {
+        Printer {
+            out,
+            color,
+            line_numbers,
+            context,
+            last: None,
+            printed: false,
+        }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+
+    // -- line numbers start over, the next line printed opens a new group
+    pub fn start_file(&mut self) {
+        self.last = None;
+    }
+
+    // -- a selected (`:`) or context (`-`) line, its matches highlighted
+    pub fn line(
+        &mut self,
+        name: Option<&str>,
+        number: u64,
+        sep: u8,
+        text: &[u8],
+        pattern: &Regex,
+    ) -> io::Result<()> {
+        if self.context && self.printed && self.last.is_none_or(|last| last + 1 != number) {
+            self.colored(SEP, b"--")?;
+            self.out.write_all(b"\n")?;
+        }
+        self.last = Some(number);
+        self.printed = true;
+        self.prefix(name, Some(number), sep)?;
+        if self.color {
+            let mut start = 0;
+            for m in pattern.find_iter(text).filter(|m| !m.is_empty()) {
+                self.out.write_all(&text[start..m.start()])?;
+                self.colored(MATCH, m.as_bytes())?;
+                start = m.end();
+            }
+            self.out.write_all(&text[start..])?;
+        } else {
+            self.out.write_all(text)?;
+        }
+        self.out.write_all(b"\n")
+    }
+
+    // -- -o : one match, on a line of its own
+    pub fn part(&mut self, name: Option<&str>, number: u64, text: &[u8]) -> io::Result<()> {
+        self.prefix(name, Some(number), b':')?;
+        self.colored(MATCH, text)?;
+        self.out.write_all(b"\n")
+    }
+
+    // -- -c
+    pub fn count(&mut self, name: Option<&str>, count: u64) -> io::Result<()> {
+        self.prefix(name, None, b':')?;
+        writeln!(self.out, "{}", count)
+    }
+
+    // -- -l
+    pub fn name(&mut self, name: &str) -> io::Result<()> {
+        self.colored(NAME, name.as_bytes())?;
+        self.out.write_all(b"\n")
+    }
+
+    // -- `number` is only shown with -n
+    fn prefix(&mut self, name: Option<&str>, number: Option<u64>, sep: u8) -> io::Result<()> {
+        if let Some(name) = name {
+            self.colored(NAME, name.as_bytes())?;
+            self.colored(SEP, &[sep])?;
+        }
+        if let Some(number) = number.filter(|_| self.line_numbers) {
+            self.colored(LINE_NO, number.to_string().as_bytes())?;
+            self.colored(SEP, &[sep])?;
+        }
+        Ok(())
+    }
+
+    // -- SGR on, erase to end of line (so a colored background doesn't
+    // run on), then both off again, like grep
+    fn colored(&mut self, sgr: &str, text: &[u8]) -> io::Result<()> {
+        if !self.color {
+            return self.out.write_all(text);
+        }
+        write!(self.out, "\x1b[{}m\x1b[K", sgr)?;
+        self.out.write_all(text)?;
+        self.out.write_all(b"\x1b[m\x1b[K")
+    }
+}
@@ -0,0 +1,241 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+use std::fs;
+
+type TestResult = Result<(), Box<dyn Error>>;
+
+// expected output is GNU grep's (see mk-outs.sh), patterns are Rust regex
+// syntax : `a|b` where grep has `a\|b`
+const PRG: &str = "grepr";
+const EMPTY: &str = "tests/inputs/empty.txt";
+const FOX: &str = "tests/inputs/fox.txt";
+const BUSTLE: &str = "tests/inputs/the-bustle.txt";
+const BUSTLE_GZ: &str = "tests/inputs/the-bustle.txt.gz";
+const NOBODY: &str = "tests/inputs/nobody.txt";
+const POEMS: &str = "tests/inputs/poems";
+const ALL: [&str; 4] = [EMPTY, FOX, BUSTLE, NOBODY];
+
+// ----------------------------------------------------------------------------
+fn run(args: &[&str], expected_file: &str) -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(args)
+        .assert()
+        .stdout(fs::read(expected_file)?);
+    Ok(())
+}
+
+// -- the same search on each file, one expected file each
+fn run_each(args: &[&str], suffix: &str) -> TestResult {
+    for file in ALL {
+        let name = file.rsplit('/').next().unwrap();
+        let mut all = args.to_vec();
+        all.push(file);
+        run(&all, &format!("tests/expected/{}.{}.out", name, suffix))?;
+    }
+    Ok(())
+}
+
+fn run_all(args: &[&str], expected_file: &str) -> TestResult {
+    run(&[args, &ALL[..]].concat(), expected_file)
+}
+
+// ----------------------------------------------------------------------------
+#[test]
+fn matches() -> TestResult {
+    run_each(&["The"], "the")
+}
+
+#[test]
+fn insensitive() -> TestResult {
+    run_each(&["-i", "the"], "the.i")?;
+    run_each(&["--line-number", "--ignore-case", "the"], "the.n.i")
+}
+
+#[test]
+fn invert() -> TestResult {
+    run_each(&["-v", "The"], "the.v")
+}
+
+#[test]
+fn count() -> TestResult {
+    run_each(&["-c", "The"], "the.c")
+}
+
+#[test]
+fn only_matching() -> TestResult {
+    run_each(&["-o", "-n", "Nobody|the"], "o.n")
+}
+
+#[test]
+fn context() -> TestResult {
+    run(&["-n", "-A", "1", "Nobody", NOBODY], "tests/expected/nobody.txt.A1.out")?;
+    run(&["-n", "-B", "2", "Frog", NOBODY], "tests/expected/nobody.txt.B2.out")?;
+    run(&["-C", "1", "How|Who", NOBODY], "tests/expected/nobody.txt.C1.out")?;
+    run(&["-v", "-n", "-C", "1", "e", NOBODY], "tests/expected/nobody.txt.C1.v.out")?;
+    // -A wins over -C
+    run(
+        &["-A", "1", "-C", "3", "eternity", BUSTLE],
+        "tests/expected/the-bustle.txt.A1.C3.out",
+    )
+}
+
+#[test]
+fn several_files() -> TestResult {
+    run_all(&["-i", "the"], "tests/expected/all.the.i.out")?;
+    run_all(&["-c", "-i", "the"], "tests/expected/all.the.c.i.out")?;
+    run_all(&["-l", "-i", "nobody"], "tests/expected/all.nobody.l.out")?;
+    run(
+        &["-n", "-C", "1", "-i", "the", NOBODY, BUSTLE],
+        "tests/expected/all.the.C1.out",
+    )
+}
+
+#[test]
+fn color() -> TestResult {
+    run(
+        &["--color=always", "-i", "the", BUSTLE],
+        "tests/expected/the-bustle.txt.color.out",
+    )?;
+    run(
+        &["--color=always", "-n", "-C", "1", "Frog", NOBODY, BUSTLE],
+        "tests/expected/all.color.C1.out",
+    )?;
+    // -v : the context lines are the ones with matches to color
+    run(
+        &["--color=always", "-v", "-n", "-A", "1", "e", NOBODY],
+        "tests/expected/nobody.txt.color.v.out",
+    )?;
+    run(
+        &["--color=always", "-o", "-i", "nobody", NOBODY],
+        "tests/expected/nobody.txt.color.o.out",
+    )?;
+    run_all(&["--color=always", "-c", "-i", "the"], "tests/expected/all.color.c.out")?;
+    run_all(&["--color=always", "-l", "-i", "the"], "tests/expected/all.color.l.out")
+}
+
+#[test]
+fn color_auto_when_piped() -> TestResult {
+    // not a terminal : no escapes, and a bare --color leaves the pattern be
+    run(&["--color", "-i", "the", BUSTLE], "tests/expected/the-bustle.txt.the.i.out")?;
+    run(&["--color=never", "-i", "the", BUSTLE], "tests/expected/the-bustle.txt.the.i.out")
+}
+
+#[test]
+fn recursive() -> TestResult {
+    run(&["-r", "-n", "the", POEMS], "tests/expected/poems.the.r.n.out")?;
+    run(&["-r", "-c", "-i", "the", POEMS], "tests/expected/poems.the.r.c.out")?;
+    run(&["-r", "-l", "-i", "nobody", POEMS], "tests/expected/poems.nobody.r.l.out")
+}
+
+#[test]
+fn recursive_cwd() -> TestResult {
+    // names relative to the current directory, without ./
+    Command::cargo_bin(PRG)?
+        .current_dir(POEMS)
+        .args(["-r", "on"])
+        .assert()
+        .success()
+        .stdout(fs::read("tests/expected/poems.on.r.cwd.out")?);
+    Ok(())
+}
+
+#[test]
+fn recursive_one_file() -> TestResult {
+    // a single file named, no name in front
+    run(&["-r", "-i", "the", BUSTLE], "tests/expected/the-bustle.txt.the.i.out")
+}
+
+#[test]
+fn stdin() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["-n", "-i", "the"])
+        .write_stdin(fs::read(BUSTLE)?)
+        .assert()
+        .success()
+        .stdout(fs::read("tests/expected/the-bustle.txt.stdin.out")?);
+    Command::cargo_bin(PRG)?
+        .args(["-c", "the", "-", FOX])
+        .write_stdin(fs::read(BUSTLE)?)
+        .assert()
+        .success()
+        .stdout(fs::read("tests/expected/the-bustle.txt.stdin.c.out")?);
+    Ok(())
+}
+
+#[test]
+fn gz() -> TestResult {
+    run(&["-i", "the", BUSTLE_GZ], "tests/expected/the-bustle.txt.the.i.out")
+}
+
+// ----------------------------------------------------------------------------
+#[test]
+fn exit_status() -> TestResult {
+    // 0 when a line is selected, 1 when none is
+    Command::cargo_bin(PRG)?.args(["fox", FOX]).assert().code(0);
+    Command::cargo_bin(PRG)?.args(["wolf", FOX]).assert().code(1).stdout("");
+    Command::cargo_bin(PRG)?.args(["fox", EMPTY]).assert().code(1);
+    // a file that can't be read is an error, not no match
+    Command::cargo_bin(PRG)?.args(["x", "no-such-file"]).assert().code(2);
+    Ok(())
+}
+
+#[test]
+fn skips_bad_file() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["fox", "no-such-file", FOX])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::starts_with("Failed to open no-such-file"))
+        .stdout(format!("{}:{}", FOX, fs::read_to_string(FOX)?));
+    Ok(())
+}
+
+#[test]
+fn skips_dir_without_r() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["fox", POEMS, FOX])
+        .assert()
+        .code(2)
+        .stderr(format!("Failed to open {}: is a directory\n", POEMS))
+        .stdout(format!("{}:{}", FOX, fs::read_to_string(FOX)?));
+    Ok(())
+}
+
+#[test]
+fn dies_bad_args() -> TestResult {
+    for (args, error) in [
+        (&["("][..], "Invalid pattern \"(\""),
+        (&["-A", "x", "fox"][..], "illegal context length -- x"),
+        (&["-C", "1x", "fox"][..], "illegal context length -- 1x"),
+        // usage errors too, not clap's 1
+        (&[][..], "error: The following required arguments"),
+        (&["--nope", "fox"][..], "error: Found argument '--nope'"),
+    ] {
+        Command::cargo_bin(PRG)?
+            .args(args)
+            .assert()
+            .code(2)
+            .stderr(predicate::str::starts_with(error));
+    }
+    Ok(())
+}
+
+#[test]
+fn broken_pipe_exits_quietly() -> TestResult {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let mut child = std::process::Command::new(assert_cmd::cargo::cargo_bin(PRG))
+        .arg("e")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    drop(child.stdout.take());
+    let _ = child.stdin.take().unwrap().write_all(&fs::read(BUSTLE)?.repeat(1000));
+    let output = child.wait_with_output()?;
+    assert!(matches!(output.status.code(), Some(0) | Some(141)));
+    assert_eq!(String::from_utf8(output.stderr)?, "");
+    Ok(())
+}
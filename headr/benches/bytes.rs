@@ -0,0 +1,65 @@
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use headr::Config;
+use ior::Input;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Write};
+use std::ops::Deref;
+use std::path::PathBuf;
+
+// -- `headr -c` over most of a large regular file, output thrown away
+// - buffered : head() on a BufReader, what stdin and pipes still get
+// - mapped : the same head() on the slice ior::open_input() maps
+// run with : cargo bench --bench bytes
+
+const SIZE_MIB: usize = 256;
+
+// -- ~SIZE_MIB of text lines, written once per run to the temp dir
+fn big_file() -> PathBuf {
+    let path = std::env::temp_dir().join(format!("headr-bench-{}MiB.txt", SIZE_MIB));
+    if fs::metadata(&path)
+        .map(|m| m.len() as usize >= SIZE_MIB << 20)
+        .unwrap_or(false)
+    {
+        return path;
+    }
+    let mut out = BufWriter::new(File::create(&path).unwrap());
+    let line = "The quick brown fox jumps over the lazy dog, again and again.\n";
+    for _ in 0..(SIZE_MIB << 20) / line.len() + 1 {
+        out.write_all(line.as_bytes()).unwrap();
+    }
+    out.flush().unwrap();
+    path
+}
+
+fn mapped(filename: &str) -> impl Deref<Target = [u8]> {
+    match ior::open_input(filename, false).unwrap() {
+        Input::Mapped(map) => map,
+        Input::Stream(_) => panic!("{} was not mapped", filename),
+    }
+}
+
+fn bench_bytes(c: &mut Criterion) {
+    let path = big_file();
+    let filename = path.to_str().unwrap();
+    let config = Config {
+        bytes: Some(SIZE_MIB << 20),
+        ..Config::default()
+    };
+
+    let mut group = c.benchmark_group("head_bytes");
+    group.throughput(Throughput::Bytes((SIZE_MIB << 20) as u64));
+    group.sample_size(10);
+    group.bench_function("buffered", |b| {
+        b.iter(|| {
+            let input = BufReader::new(File::open(filename).unwrap());
+            headr::head(input, filename, &config, &mut io::sink()).unwrap()
+        })
+    });
+    group.bench_function("mapped", |b| {
+        b.iter(|| headr::head(&mapped(filename)[..], filename, &config, &mut io::sink()).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_bytes);
+criterion_main!(benches);
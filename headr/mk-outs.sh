@@ -27,3 +27,7 @@ head -n 4 $ALL > $OUT_DIR/all.n4.out
 head -c 1 $ALL > $OUT_DIR/all.c1.out
 head -c 2 $ALL > $OUT_DIR/all.c2.out
 head -c 4 $ALL > $OUT_DIR/all.c4.out
+
+# compressed copies : tests expect the same output as the plain files
+zstd -qfc $INPUTS/ten.txt > $INPUTS/ten.txt.zst
+bzip2 -c $INPUTS/three.txt > $INPUTS/three.txt.bz2
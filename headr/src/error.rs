@@ -0,0 +1,44 @@
+use std::io;
+use thiserror::Error;
+
+// == everything headr can fail with
+// - a file that fails to open is reported and skipped, not returned
+#[derive(Debug, Error)]
+pub enum Error {
+    // -n / -c that isn't a positive integer, `what` is "line count" or
+    // "byte count"
+    #[error("illegal {what} -- {value}")]
+    InvalidCount { what: &'static str, value: String },
+
+    #[error("Failed to open {path}: {source}")]
+    OpenFailed { path: String, source: io::Error },
+
+    #[error("{path}: {source}")]
+    ReadFailed { path: String, source: io::Error },
+
+    // -n prints whole lines, which have to be UTF-8, -c prints any bytes
+    #[error("{path}: stream did not contain valid UTF-8 at byte {offset}")]
+    InvalidUtf8 { path: String, offset: u64 },
+
+    #[error("write error: {0}")]
+    WriteFailed(#[source] io::Error),
+}
+
+impl ior::Failure for Error {
+    fn exit_code(&self) -> i32 {
+        match self {
+            Error::InvalidCount { .. } => 2,
+            Error::OpenFailed { .. } => 3,
+            Error::ReadFailed { .. } => 4,
+            Error::InvalidUtf8 { .. } => 5,
+            Error::WriteFailed(_) => 6,
+        }
+    }
+
+    fn write_error(&self) -> Option<&io::Error> {
+        match self {
+            Error::WriteFailed(e) => Some(e),
+            _ => None,
+        }
+    }
+}
@@ -2,25 +2,52 @@ use clap::{
     App,
     Arg
 };
-use std::fs::File;
-use std::io;
-use std::io::Read;
+use ior::Input;
+use std::io::{self, Read, Write};
 use std::io::BufRead;
-use std::io::BufReader;
-use std::error::Error;
+
+mod error;
+
+pub use error::Error;
 
 //---------------------------------------------------------------------------80
 
-type MyResult<T> = Result<T, Box<dyn Error>>;
+type MyResult<T> = Result<T, Error>;
 
+// -- what get_args() parses, or built by hand when headr is used as a
+// library : Config { lines: 5, ..Config::default() }
 #[derive(Debug)]
 pub struct Config{
-    files: Vec<String>,
-    lines: usize,
-    bytes: Option<usize>,
+    pub files: Vec<String>,
+    pub lines: usize,
+    pub bytes: Option<usize>,
+    // decode gzip, bzip2, xz and zstd input (off with --no-decompress)
+    pub decompress: bool,
+}
+
+impl Default for Config {
+    // -- head's defaults : 10 lines of stdin
+    fn default() -> Config {
+        Config {
+            files: vec!["-".to_string()],
+            lines: 10,
+            bytes: None,
+            decompress: true,
+        }
+    }
 }
 
 pub fn run(config: Config) -> MyResult<()> {
+    // buffered unless stdout is a terminal, see ior::stdout()
+    let mut out = ior::stdout();
+    run_with(&config, &mut out, &mut io::stderr().lock())?;
+    out.flush().map_err(Error::WriteFailed)
+}
+
+// -- run() with the output and the per-file errors going where the caller
+// says, e.g. into a Vec<u8>
+// - a file that fails to open is reported to `err` and skipped
+pub fn run_with<W: Write, E: Write>(config: &Config, out: &mut W, err: &mut E) -> MyResult<()> {
     // get number of files passed as arguments
     // - if just one file, simply print the file's text block
     // - if > 1 file, append filename header before each text block
@@ -30,53 +57,27 @@ pub fn run(config: Config) -> MyResult<()> {
     // - idx   : file_num
     // - value : filename
     for (file_num, filename) in config.files.iter().enumerate() {
-        match open(&filename) {
-            Err(err) => eprintln!("Failed to open {}: {}", filename, err),
-            // (A) file needs to be mutable because :
-            // - take and read_line are ops that mut file's read cursor
-            Ok(mut file) => {
+        match open(filename, config.decompress) {
+            // the error sink is best effort, like eprintln! without the panic
+            Err(e) => {
+                let _ = writeln!(err, "{}", e);
+            }
+            Ok(input) => {
                 // if files > 1 add `{filename}<==` header @text block
                 if num_files > 1 {
-                    println!(
-                        "{}==> {} <==",
-                        if file_num > 0 {"\n"} else {""},
-                        &filename
-                    );
+                    write_header(out, filename, file_num == 0).map_err(Error::WriteFailed)?;
                 }
 
-                // if byte arg != None, print bytes
-                if let Some(num_bytes) = config.bytes {
-                    // take() :
-                    // - creates a new type (u64) segment of num_bytes
-                    // - (A) this mut op updates the file's read cursor
-                    let mut handle = file.take(num_bytes as u64);
-                    let mut buffer = vec![0; num_bytes];
-                    // - unwrap with ? so that if error occurs, EARLY return
-                    let bytes_read = handle.read(&mut buffer)?;
-                    print!(
+                match (input, config.bytes) {
+                    // regular file : the bytes are already mapped in,
+                    // slice them instead of copying into a buffer
+                    (Input::Mapped(map), Some(num_bytes)) => write!(
+                        out,
                         "{}",
-                        String::from_utf8_lossy(&buffer[..bytes_read])
-                    );
-                }
-                // else print lines
-                else {
-                    let mut line = String::new();
-                    // arg handling lib => config.lines will ALWAYS be valid
-                    for _ in 0..config.lines {
-                        // (A) read_line mutates the file's internal cursor
-                        // - unwrap with ? so that if error occurs, EARLY return
-                        let bytes = file.read_line(&mut line)?;
-                        // bytes == 0 is end of line or end of file
-                        if bytes == 0 {
-                            break;
-                        }
-                        print!("{}", line);
-                        // @udit-ok : Why do we need to clear line?
-                        // ANSWER : if we don't clear the line
-                        // - any test with more than one line will fail : 43
-                        // - only #[test] dies*() empyt*() and  one*() PASSES 
-                        line.clear();
-                    }
+                        String::from_utf8_lossy(&map[..num_bytes.min(map.len())])
+                    )
+                    .map_err(Error::WriteFailed)?,
+                    (input, _) => head(input.into_reader(), filename, config, out)?,
                 }
             }
         }
@@ -84,6 +85,77 @@ pub fn run(config: Config) -> MyResult<()> {
     Ok(())
 }
 
+// -- `==> filename <==` above each file's text when there's more than one,
+// a blank line between files
+// - pub : tailr prints the same headers
+pub fn write_header<W: Write>(out: &mut W, filename: &str, first: bool) -> io::Result<()> {
+    writeln!(
+        out,
+        "{}==> {} <==",
+        if first {""} else {"\n"},
+        filename
+    )
+}
+
+// -- the first -n lines or -c bytes of one input, `filename` only names it
+// in errors
+pub fn head<R: BufRead, W: Write>(
+    input: R,
+    filename: &str,
+    config: &Config,
+    out: &mut W,
+) -> MyResult<()> {
+    // a read error names the file it came from
+    let read_failed = |source| Error::ReadFailed {
+        path: filename.to_string(),
+        source,
+    };
+
+    // if byte arg != None, print bytes
+    if let Some(num_bytes) = config.bytes {
+        // take() :
+        // - creates a new type (u64) segment of num_bytes
+        // - (A) this mut op updates the file's read cursor
+        let mut handle = input.take(num_bytes as u64);
+        let mut buffer = Vec::with_capacity(num_bytes);
+        // - a pipe hands over what it has so far, read up to the limit
+        // - unwrap with ? so that if error occurs, EARLY return
+        handle.read_to_end(&mut buffer).map_err(read_failed)?;
+        return write!(out, "{}", String::from_utf8_lossy(&buffer)).map_err(Error::WriteFailed);
+    }
+
+    // else print lines
+    // (A) file needs to be mutable because :
+    // - read_line is an op that muts file's read cursor
+    let mut file = input;
+    let mut line = Vec::new();
+    // bytes before this line, to say where bad UTF-8 is
+    let mut offset = 0;
+    // arg handling lib => config.lines will ALWAYS be valid
+    for _ in 0..config.lines {
+        // (A) read_until mutates the file's internal cursor
+        // - unwrap with ? so that if error occurs, EARLY return
+        let bytes = file.read_until(b'\n', &mut line).map_err(read_failed)?;
+        // bytes == 0 is end of line or end of file
+        if bytes == 0 {
+            break;
+        }
+        // read_until() takes any bytes, lines have to be text
+        let text = std::str::from_utf8(&line).map_err(|e| Error::InvalidUtf8 {
+            path: filename.to_string(),
+            offset: offset + e.valid_up_to() as u64,
+        })?;
+        write!(out, "{}", text).map_err(Error::WriteFailed)?;
+        offset += bytes as u64;
+        // @udit-ok : Why do we need to clear line?
+        // ANSWER : if we don't clear the line
+        // - any test with more than one line will fail : 43
+        // - only #[test] dies*() empyt*() and  one*() PASSES 
+        line.clear();
+    }
+    Ok(())
+}
+
 pub fn get_args() -> MyResult<Config> {
 
     let matches = App::new("headr")
@@ -140,7 +212,22 @@ pub fn get_args() -> MyResult<Config> {
                 // - unless we specify value_name here
                 .value_name("BYTES")
         ) 
+        .arg(
+            Arg::with_name("files0_from")
+                // the names come from F instead : FILES can't be given too
+                .conflicts_with("files")
+                .takes_value(true)
+                .help("Read input file names from F, NUL separated (- for stdin)")
+                .long("files0-from")
+                .value_name("F")
+        )
         // -- flags 
+        .arg(
+            Arg::with_name("no_decompress")
+                .takes_value(false)
+                .help("Pass compressed input through undecoded")
+                .long("no-decompress")
+        )
         .get_matches();
 
 //---------------------------------------------------------------------------80
@@ -151,13 +238,24 @@ pub fn get_args() -> MyResult<Config> {
     // unwrap_or_default is EAGER would add an op 
     // - in this case the default is a static string value : "files"
     // - so neglible computation overhead
-    let files = matches.values_of_lossy("files").unwrap_or_default();
+    let files = match matches.value_of("files0_from") {
+        // e.g. `findr src -print0 | headr --files0-from -`
+        Some(list) => {
+            let reader = open(list, false)?.into_reader();
+            let names = ior::read_file_list(reader).map_err(|source| Error::ReadFailed {
+                path: list.to_string(),
+                source,
+            })?;
+            ior::utf8_file_names(names, &mut io::stderr())
+        }
+        None => matches.values_of_lossy("files").unwrap_or_default(),
+    };
    
     let lines = matches
         .value_of("lines")
         .map(parse_positive_int)
         .transpose()
-        .map_err(|e| format!("illegal line count -- {}", e))?;
+        .map_err(|value| Error::InvalidCount { what: "line count", value })?;
 
     let bytes = matches
         // returns Option<&str>
@@ -170,7 +268,7 @@ pub fn get_args() -> MyResult<Config> {
         .transpose()
         // error is deferred until after transpose
         // and transforms the error variant of the Result
-        .map_err(|e| format!("illegal byte count -- {}", e))
+        .map_err(|value| Error::InvalidCount { what: "byte count", value })
         // handles any error by immediately returning it
         ?;
        
@@ -184,19 +282,23 @@ pub fn get_args() -> MyResult<Config> {
         lines : lines.unwrap(), // has default arg and safe to unwrap
         // leave this as an Option, matches config define
         bytes,    
+        decompress: !matches.is_present("no_decompress"),
     })
 }
 
-fn open(filename: &str) -> MyResult<Box<dyn BufRead>> {
-    match filename {
-        // take input from stdin
-        "-" => Ok(Box::new(BufReader::new(io::stdin()))),
-        // else try to read from file
-        _ => Ok(Box::new(BufReader::new(File::open(filename)?))),
-    }
+// take input from stdin for "-", else try to read from file
+// - compressed input is decoded on the fly, see ior::open_input()
+// - regular files come back memory mapped, see Input
+fn open(filename: &str, decompress: bool) -> MyResult<Input> {
+    ior::open_input(filename, decompress).map_err(|source| Error::OpenFailed {
+        path: filename.to_string(),
+        source,
+    })
 }
 
-fn parse_positive_int(val: &str) -> MyResult<usize> {
+// -- the offending value comes back as is, get_args() says which count
+// - pub : tailr parses its counts with it
+pub fn parse_positive_int(val: &str) -> Result<usize, String> {
     // - 1 - parse value as a usize
     //  - parse returns Result<usize, ParseIntError> in this context
     // @udit-ok : How was this config to parse usize?
@@ -235,3 +337,44 @@ fn test_parse_positive_int() {
     assert!(res.is_err());
     assert_eq!(res.unwrap_err().to_string(), "0".to_string());
 }
+
+#[test]
+fn test_head() {
+    let text = "one\ntwo\nthree\n";
+    let head_of = |config: Config| {
+        let mut out = vec![];
+        head(std::io::Cursor::new(text), "-", &config, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    };
+    assert_eq!(head_of(Config { lines: 2, ..Config::default() }), "one\ntwo\n");
+    assert_eq!(head_of(Config::default()), text);
+    assert_eq!(head_of(Config { bytes: Some(5), ..Config::default() }), "one\nt");
+
+    // lines have to be text, the error says where they stop being
+    let mut out = vec![];
+    let res = head(&b"ok\n\xff\n"[..], "bin", &Config::default(), &mut out);
+    assert!(matches!(res, Err(Error::InvalidUtf8 { offset: 3, .. })));
+    assert_eq!(out, b"ok\n");
+}
+
+#[test]
+fn test_run_with() {
+    // headers between files, a missing file reported to the error sink
+    let config = Config {
+        files: vec![
+            "tests/inputs/one.txt".to_string(),
+            "no-such-file".to_string(),
+            "tests/inputs/two.txt".to_string(),
+        ],
+        lines: 1,
+        ..Config::default()
+    };
+    let (mut out, mut err) = (vec![], vec![]);
+    run_with(&config, &mut out, &mut err).unwrap();
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "==> tests/inputs/one.txt <==\nÖne line, four words.\n\n\
+         ==> tests/inputs/two.txt <==\nTwo lines.\n"
+    );
+    assert!(String::from_utf8(err).unwrap().starts_with("Failed to open no-such-file"));
+}
@@ -13,6 +13,9 @@ const ONE: &str = "./tests/inputs/one.txt";
 const TWO: &str = "./tests/inputs/two.txt";
 const THREE: &str = "./tests/inputs/three.txt";
 const TEN: &str = "./tests/inputs/ten.txt";
+// compressed copies : zstd, bzip2
+const TEN_ZST: &str = "./tests/inputs/ten.txt.zst";
+const THREE_BZ2: &str = "./tests/inputs/three.txt.bz2";
 
 //---------------------------------------------------------------------------80
 
@@ -38,7 +41,7 @@ fn run(args: &[&str], expected_file: &str) -> TestResult {
         .args(args)
         .assert()
         .success()
-        .stdout(predicate::eq(&expected.as_bytes() as &[u8]));
+        .stdout(predicate::eq(expected.as_bytes()));
 
     Ok(())
 }
@@ -91,7 +94,7 @@ fn run_stdin(
         // (B) PHOTOSHOP EQUIVALENT :
         // "Difference" blend between two images == byte-for-byte compare
         // - results in 100% black image patch if NO DIFFERENCE
-        .stdout(predicate::eq(&expected.as_bytes() as &[u8]));
+        .stdout(predicate::eq(expected.as_bytes()));
     
     // returns OK indicating that test PASSED
     Ok(())
@@ -104,7 +107,7 @@ fn dies_bad_bytes() -> TestResult {
     let bad = random_string();
     let expected = format!("illegal byte count -- {}", &bad);
     Command::cargo_bin(PRG)?
-        .args(&["-c", &bad, EMPTY])
+        .args(["-c", &bad, EMPTY])
         .assert()
         .failure()
         .stderr(predicate::str::contains(expected));
@@ -117,7 +120,7 @@ fn dies_bad_lines() -> TestResult {
     let bad = random_string();
     let expected = format!("illegal line count -- {}", &bad);
     Command::cargo_bin(PRG)?
-        .args(&["-n", &bad, EMPTY])
+        .args(["-n", &bad, EMPTY])
         .assert()
         .failure()
         .stderr(predicate::str::contains(expected));
@@ -128,9 +131,9 @@ fn dies_bad_lines() -> TestResult {
 #[test]
 fn dies_bytes_and_lines() -> TestResult {
     let bad = random_string();
-    let expected = format!("'--lines <LINES>' cannot be used with '--bytes <BYTES>'");
+    let expected = "'--lines <LINES>' cannot be used with '--bytes <BYTES>'";
     Command::cargo_bin(PRG)?
-        .args(&["-c", &bad, "-n", &bad, EMPTY])
+        .args(["-c", &bad, "-n", &bad, EMPTY])
         .assert()
         .failure()
         .stderr(predicate::str::contains(expected));
@@ -141,9 +144,9 @@ fn dies_bytes_and_lines() -> TestResult {
 #[test]
 fn dies_lines_and_bytes() -> TestResult {
     let bad = random_string();
-    let expected = format!("'--lines <LINES>' cannot be used with '--bytes <BYTES>'");
+    let expected = "'--lines <LINES>' cannot be used with '--bytes <BYTES>'";
     Command::cargo_bin(PRG)?
-        .args(&["-n", &bad, "-c", &bad, EMPTY])
+        .args(["-n", &bad, "-c", &bad, EMPTY])
         .assert()
         .failure()
         .stderr(predicate::str::contains(expected));
@@ -476,3 +479,122 @@ fn multipile_files_c2() -> TestResult {
 fn multiple_files_c4() -> TestResult {
     run(&[EMPTY, ONE, TWO, THREE, TEN, "-c", "4"], "tests/expected/all.c4.out")
 }
+
+// -- the same files named by a NUL separated list, as findr -print0 writes
+#[test]
+fn files0_from() -> TestResult {
+    let list = [EMPTY, ONE, TWO, THREE, TEN].join("\0");
+    Command::cargo_bin(PRG)?
+        .args(["--files0-from", "-", "-n", "2"])
+        .write_stdin(list)
+        .assert()
+        .success()
+        .stdout(fs::read("tests/expected/all.n2.out")?);
+    Ok(())
+}
+
+#[test]
+fn dies_files0_from() -> TestResult {
+    // not with FILES, not with an empty name
+    Command::cargo_bin(PRG)?
+        .args(["--files0-from", "-", ONE])
+        .assert()
+        .failure();
+    Command::cargo_bin(PRG)?
+        .args(["--files0-from", "-"])
+        .write_stdin(format!("{}\0\0{}", ONE, TWO))
+        .assert()
+        .code(4)
+        .stderr(predicate::str::contains("invalid zero-length file name"));
+    Ok(())
+}
+
+//---------------------------------------------------------------------------80
+// compressed input : counts apply to the decoded lines / bytes
+
+#[test]
+fn ten_zst_n4() -> TestResult {
+    run(&[TEN_ZST, "-n", "4"], "tests/expected/ten.txt.n4.out")
+}
+
+#[test]
+fn three_bz2_c2() -> TestResult {
+    run(&[THREE_BZ2, "-c", "2"], "tests/expected/three.txt.c2.out")
+}
+
+#[test]
+fn ten_zst_stdin() -> TestResult {
+    // bytes, not read_to_string() : compressed input isn't UTF-8
+    let input = fs::read(TEN_ZST)?;
+    let expected = fs::read("tests/expected/ten.txt.out")?;
+    Command::cargo_bin(PRG)?
+        .write_stdin(input)
+        .assert()
+        .success()
+        .stdout(predicate::eq(expected.as_slice()));
+    Ok(())
+}
+
+#[test]
+fn ten_zst_no_decompress() -> TestResult {
+    // -c 4 of the raw stream is the zstd frame magic 28 b5 2f fd
+    // - printed lossy, like any other bytes that aren't UTF-8
+    Command::cargo_bin(PRG)?
+        .args(["--no-decompress", "-c", "4", TEN_ZST])
+        .assert()
+        .success()
+        .stdout("(\u{FFFD}/\u{FFFD}");
+    Ok(())
+}
+
+//---------------------------------------------------------------------------80
+// each kind of failure exits with its own status, see headr::Error
+
+#[test]
+fn exit_code_bad_count() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["-n", "0", EMPTY])
+        .assert()
+        .code(2)
+        .stderr("illegal line count -- 0\n");
+    Ok(())
+}
+
+#[test]
+fn exit_code_invalid_utf8() -> TestResult {
+    // lines of the raw zstd stream : 28 is `(`, b5 can't start a char
+    Command::cargo_bin(PRG)?
+        .args(["--no-decompress", "-n", "1", TEN_ZST])
+        .assert()
+        .code(5)
+        .stderr(format!(
+            "{}: stream did not contain valid UTF-8 at byte 1\n",
+            TEN_ZST
+        ));
+    Ok(())
+}
+
+//---------------------------------------------------------------------------80
+
+// stdout already closed when headr writes, e.g. `headr big | headr -n 1`
+// - input comes on stdin after the close, so there's no race
+#[test]
+fn broken_pipe_exits_quietly() -> TestResult {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    for (policy, code) in [("", 0), ("141", 141)] {
+        let mut child = Command::new(assert_cmd::cargo::cargo_bin(PRG))
+            .env("SIGPIPE_STATUS", policy)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        drop(child.stdout.take());
+        let _ = child.stdin.take().unwrap().write_all(&fs::read(TEN)?);
+        let output = child.wait_with_output()?;
+        assert_eq!(output.status.code(), Some(code));
+        assert_eq!(String::from_utf8(output.stderr)?, "");
+    }
+    Ok(())
+}
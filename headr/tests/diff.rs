@@ -0,0 +1,50 @@
+use difftest::both;
+use proptest::prelude::*;
+
+// headr against GNU head ($HEAD overrides, ghead on macOS), see difftest
+// for how the two are run and compared
+// - the fixtures in tests/expected are GNU's output for a handful of
+// files, this covers the inputs nobody thought to write down
+const PRG: &str = "headr";
+const HEAD: (&str, &str) = ("HEAD", "head");
+
+// -- any UTF-8 : words, unicode, CR, blank lines, often no newline at the
+// end
+fn utf8() -> impl Strategy<Value = Vec<u8>> {
+    let piece = prop_oneof!["[a-z \t]{0,10}", "\\PC{0,4}", "\n", "\r\n"];
+    prop::collection::vec(piece, 0..16).prop_map(|pieces| pieces.concat().into_bytes())
+}
+
+proptest! {
+    #[test]
+    #[ignore = "known failure: headr -c prints the bytes through from_utf8_lossy, \
+                a bad or cut off sequence comes out as U+FFFD"]
+    fn bytes_match_head(input in difftest::input(), n in 1..40usize, from_file in any::<bool>()) {
+        let n = n.to_string();
+        let Some((ours, theirs)) = both(PRG, HEAD, &["-c", &n], &input, from_file) else {
+            return Ok(());
+        };
+        prop_assert_eq!((ours.status.code(), ours.stdout), (theirs.status.code(), theirs.stdout));
+    }
+
+    #[test]
+    #[ignore = "known failure: headr -n reads lines as text, a line that isn't UTF-8 \
+                stops it with status 5"]
+    fn lines_match_head(input in difftest::input(), n in 1..12usize, from_file in any::<bool>()) {
+        let n = n.to_string();
+        let Some((ours, theirs)) = both(PRG, HEAD, &["-n", &n], &input, from_file) else {
+            return Ok(());
+        };
+        prop_assert_eq!((ours.status.code(), ours.stdout), (theirs.status.code(), theirs.stdout));
+    }
+
+    // -- lines_match_head on the input its known failure doesn't touch
+    #[test]
+    fn lines_match_head_on_utf8(input in utf8(), n in 1..12usize, from_file in any::<bool>()) {
+        let n = n.to_string();
+        let Some((ours, theirs)) = both(PRG, HEAD, &["-n", &n], &input, from_file) else {
+            return Ok(());
+        };
+        prop_assert_eq!((ours.status.code(), ours.stdout), (theirs.status.code(), theirs.stdout));
+    }
+}
@@ -0,0 +1,55 @@
+use crate::is_broken_pipe;
+use std::io;
+
+// == how the tools fail, one convention for all of them
+// - each crate has an Error enum (thiserror) : library callers match on
+// the variant, the CLI exits with the status exit_code() gives it
+// - clap exits 1 on usage errors, so the tools' own statuses start at 2 :
+// 2 bad arguments, 3 an input that won't open, 4 one that fails to read,
+// 5 input that isn't what the tool takes (bad UTF-8, unsorted ...), 6 a
+// failed write to stdout or the terminal
+// - grepr is the exception : grep's 1 means nothing was selected, so
+// every one of its errors is 2
+// - an input that won't open is reported and skipped, not returned, unless
+// the crate's Error says otherwise
+// - a write that fails because nobody reads stdout anymore is not the
+// tool's failure : main() exits per PipePolicy, without a message
+pub trait Failure {
+    fn exit_code(&self) -> i32;
+
+    // -- the error of a failed write to stdout (or the pager), None for
+    // every other kind
+    fn write_error(&self) -> Option<&io::Error> {
+        None
+    }
+
+    fn is_broken_pipe(&self) -> bool {
+        self.write_error().is_some_and(is_broken_pipe)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Failure;
+    use std::io;
+
+    struct WriteFailed(io::Error);
+
+    impl Failure for WriteFailed {
+        fn exit_code(&self) -> i32 {
+            6
+        }
+
+        fn write_error(&self) -> Option<&io::Error> {
+            Some(&self.0)
+        }
+    }
+
+    #[test]
+    fn test_is_broken_pipe() {
+        let closed = WriteFailed(io::Error::from(io::ErrorKind::BrokenPipe));
+        assert!(closed.is_broken_pipe());
+        let full = WriteFailed(io::Error::from(io::ErrorKind::StorageFull));
+        assert!(!full.is_broken_pipe());
+    }
+}
@@ -0,0 +1,288 @@
+use memmap2::Mmap;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Cursor, StdinLock, Write};
+
+mod fail;
+mod list;
+mod out;
+mod pipe;
+
+pub use fail::Failure;
+pub use list::{read_file_list, utf8_file_names};
+pub use out::{stdout, Stdout};
+pub use pipe::{is_broken_pipe, PipePolicy};
+
+// == input layer shared by catr, headr and wcr
+// - "-" is stdin, anything else a path
+// - compressed input is recognized by its magic bytes, not its extension,
+// so `catr < app.log.gz` works as well as `catr app.log.gz`
+// - regular files are memory mapped, stdin, pipes and special files are
+// read through a BufReader
+
+// -- an opened input, either the whole file as one slice or a stream
+pub enum Input {
+    // regular, non empty, uncompressed file
+    Mapped(Mmap),
+    // stdin, pipes, special files and decoded compressed input
+    Stream(Box<dyn BufRead>),
+}
+
+impl Input {
+    // -- read line by line either way, a mapping through a Cursor
+    pub fn into_reader(self) -> Box<dyn BufRead> {
+        match self {
+            Input::Mapped(map) => Box::new(Cursor::new(map)),
+            Input::Stream(reader) => reader,
+        }
+    }
+}
+
+// -- codecs we can recognize, whether or not their feature is enabled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Codec {
+    Gzip,
+    Bzip2,
+    Xz,
+    Zstd,
+}
+
+impl Codec {
+    // -- leading bytes of a stream in each format
+    // - gzip : RFC 1952 ID1 ID2
+    // - bzip2 : "BZh" + block size digit
+    // - xz : FD "7zXZ" 00
+    // - zstd : frame magic 0xFD2FB528, little endian
+    pub fn detect(head: &[u8]) -> Option<Codec> {
+        match head {
+            [0x1f, 0x8b, ..] => Some(Codec::Gzip),
+            [b'B', b'Z', b'h', b'1'..=b'9', ..] => Some(Codec::Bzip2),
+            [0xfd, b'7', b'z', b'X', b'Z', 0x00, ..] => Some(Codec::Xz),
+            [0x28, 0xb5, 0x2f, 0xfd, ..] => Some(Codec::Zstd),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Codec::Gzip => "gzip",
+            Codec::Bzip2 => "bzip2",
+            Codec::Xz => "xz",
+            Codec::Zstd => "zstd",
+        }
+    }
+}
+
+// -- open a file, or stdin for "-", as buffered lines or bytes
+// - decompress : transparently decode gzip, bzip2, xz and zstd input,
+// false passes the raw bytes through (--no-decompress)
+pub fn open(filename: &str, decompress: bool) -> io::Result<Box<dyn BufRead>> {
+    open_input(filename, decompress).map(Input::into_reader)
+}
+
+// -- like open(), but hands out the mapping of a regular file so callers
+// can work on the bytes directly (headr -c, wcr -c / -l)
+pub fn open_input(filename: &str, decompress: bool) -> io::Result<Input> {
+    let reader: Box<dyn BufRead> = match filename {
+        "-" => Box::new(BufReader::new(io::stdin())),
+        _ => {
+            let file = File::open(filename)?;
+            match map(&file) {
+                // compressed : decode straight from the mapping
+                Some(map) if decompress && Codec::detect(&map).is_some() => {
+                    Box::new(Cursor::new(map))
+                }
+                Some(map) => return Ok(Input::Mapped(map)),
+                None => Box::new(BufReader::new(file)),
+            }
+        }
+    };
+    match decompress {
+        true => decode(reader).map(Input::Stream),
+        false => Ok(Input::Stream(reader)),
+    }
+}
+
+// -- an input opened for a byte for byte copy (catr without flags)
+// - io::copy() is specialized on Linux for a File / StdinLock into a
+// StdoutLock : copy_file_range, sendfile or splice move the data in the
+// kernel, so the concrete reader types stay out of a Box<dyn BufRead>
+// - a BufReader<File> hands out the bytes it peeked at first, the rest
+// still goes by fd
+pub enum Source {
+    Stdin(StdinLock<'static>),
+    File(BufReader<File>),
+    // compressed input, decoded in userspace
+    Decoded(Box<dyn BufRead>),
+}
+
+impl Source {
+    pub fn copy_to<W: Write>(self, out: &mut W) -> io::Result<u64> {
+        match self {
+            Source::Stdin(mut stdin) => io::copy(&mut stdin, out),
+            Source::File(mut file) => io::copy(&mut file, out),
+            Source::Decoded(mut reader) => io::copy(&mut reader, out),
+        }
+    }
+}
+
+// -- like open(), for copy_to() : nothing is mapped or boxed unless the
+// input needs decoding
+pub fn open_source(filename: &str, decompress: bool) -> io::Result<Source> {
+    match filename {
+        "-" => {
+            let mut stdin = io::stdin().lock();
+            match decompress && Codec::detect(stdin.fill_buf()?).is_some() {
+                true => decode(Box::new(stdin)).map(Source::Decoded),
+                false => Ok(Source::Stdin(stdin)),
+            }
+        }
+        _ => {
+            let mut file = BufReader::new(File::open(filename)?);
+            match decompress && Codec::detect(file.fill_buf()?).is_some() {
+                true => decode(Box::new(file)).map(Source::Decoded),
+                false => Ok(Source::File(file)),
+            }
+        }
+    }
+}
+
+// -- map regular, non empty files, None means read them the usual way
+// - empty files can't be mapped, and FIFOs, ttys, /proc files ... either
+// can't be or report a size that isn't what a read returns
+fn map(file: &File) -> Option<Mmap> {
+    let meta = file.metadata().ok()?;
+    if !meta.is_file() || meta.len() == 0 {
+        return None;
+    }
+    // SAFETY: the mapping is read only, but another process truncating the
+    // file while we read it can still fault (SIGBUS) : the same trade off
+    // ripgrep and friends make for the speed of not copying every byte
+    unsafe { Mmap::map(file) }.ok()
+}
+
+// -- wrap `reader` in a decoder when its first bytes match a codec
+// - fill_buf() only peeks, nothing is consumed when there's no match
+pub fn decode(mut reader: Box<dyn BufRead>) -> io::Result<Box<dyn BufRead>> {
+    let codec = Codec::detect(reader.fill_buf()?);
+    match codec {
+        None => Ok(reader),
+        Some(codec) => decoder(codec, reader),
+    }
+}
+
+// -- decoders read whole multi member / multi frame streams, like
+// `zcat a.gz b.gz > ab.gz` produces
+fn decoder(codec: Codec, reader: Box<dyn BufRead>) -> io::Result<Box<dyn BufRead>> {
+    match codec {
+        #[cfg(feature = "gzip")]
+        Codec::Gzip => Ok(Box::new(BufReader::new(
+            flate2::bufread::MultiGzDecoder::new(reader),
+        ))),
+        #[cfg(feature = "bzip2")]
+        Codec::Bzip2 => Ok(Box::new(BufReader::new(
+            bzip2::bufread::MultiBzDecoder::new(reader),
+        ))),
+        #[cfg(feature = "xz")]
+        Codec::Xz => Ok(Box::new(BufReader::new(
+            xz2::bufread::XzDecoder::new_multi_decoder(reader),
+        ))),
+        #[cfg(feature = "zstd")]
+        Codec::Zstd => Ok(Box::new(BufReader::new(
+            zstd::stream::read::Decoder::with_buffer(reader)?,
+        ))),
+        // recognized, but this build can't decode it
+        #[allow(unreachable_patterns)]
+        _ => {
+            drop(reader);
+            Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!(
+                    "{} compressed input, built without the `{}` feature (try --no-decompress)",
+                    codec.name(),
+                    codec.name()
+                ),
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode, open_input, open_source, Codec, Input, Source};
+    use std::io::{BufRead, Cursor, Read};
+
+    const TEXT: &str = "The quick brown fox\njumps over the lazy dog.\n";
+
+    fn read_all(bytes: Vec<u8>) -> String {
+        let reader: Box<dyn BufRead> = Box::new(Cursor::new(bytes));
+        let mut text = String::new();
+        decode(reader).unwrap().read_to_string(&mut text).unwrap();
+        text
+    }
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(Codec::detect(&[0x1f, 0x8b, 0x08]), Some(Codec::Gzip));
+        assert_eq!(Codec::detect(b"BZh91AY&SY"), Some(Codec::Bzip2));
+        assert_eq!(Codec::detect(b"\xfd7zXZ\x00\x00"), Some(Codec::Xz));
+        assert_eq!(Codec::detect(&[0x28, 0xb5, 0x2f, 0xfd]), Some(Codec::Zstd));
+        // plain text, too short to tell, and "BZh" without a block size
+        assert_eq!(Codec::detect(TEXT.as_bytes()), None);
+        assert_eq!(Codec::detect(&[0x1f]), None);
+        assert_eq!(Codec::detect(b"BZhx"), None);
+    }
+
+    #[test]
+    fn test_open_input_maps_regular_files() {
+        // this crate's own manifest : regular, non empty, uncompressed
+        let input = open_input("Cargo.toml", true).unwrap();
+        assert!(matches!(&input, Input::Mapped(map) if map.starts_with(b"[package]")));
+
+        let mut text = String::new();
+        input.into_reader().read_to_string(&mut text).unwrap();
+        assert!(text.contains("name = \"ior\""));
+
+        assert!(open_input("no-such-file", true).is_err());
+    }
+
+    #[test]
+    fn test_open_source_copies_bytes() {
+        let source = open_source("Cargo.toml", true).unwrap();
+        assert!(matches!(source, Source::File(_)));
+
+        let mut out = vec![];
+        let n = source.copy_to(&mut out).unwrap();
+        assert_eq!(n as usize, out.len());
+        assert_eq!(out, std::fs::read("Cargo.toml").unwrap());
+
+        assert!(open_source("no-such-file", true).is_err());
+    }
+
+    #[test]
+    fn test_plain_passthrough() {
+        assert_eq!(read_all(TEXT.as_bytes().to_vec()), TEXT);
+        assert_eq!(read_all(vec![]), "");
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn test_gzip_multi_member() {
+        use flate2::{write::GzEncoder, Compression};
+        use std::io::Write;
+
+        let mut bytes = vec![];
+        for _ in 0..2 {
+            let mut encoder = GzEncoder::new(vec![], Compression::default());
+            encoder.write_all(TEXT.as_bytes()).unwrap();
+            bytes.extend(encoder.finish().unwrap());
+        }
+        assert_eq!(read_all(bytes), TEXT.repeat(2));
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn test_zstd() {
+        let bytes = zstd::encode_all(TEXT.as_bytes(), 0).unwrap();
+        assert_eq!(read_all(bytes), TEXT);
+    }
+}
@@ -0,0 +1,88 @@
+use std::ffi::OsString;
+use std::io::{self, BufRead, Write};
+
+// == --files0-from : input file names, each ended by a NUL
+// - what `findr -print0` and `find -print0` write : any name goes
+// through, newlines, blanks and bytes that aren't UTF-8 included
+// - a last name without its NUL still counts, an empty one is an error
+// (two NULs in a row), not a file named ""
+
+pub fn read_file_list<R: BufRead>(mut reader: R) -> io::Result<Vec<OsString>> {
+    let mut files = vec![];
+    let mut name = vec![];
+    while reader.read_until(b'\0', &mut name)? > 0 {
+        if name.last() == Some(&b'\0') {
+            name.pop();
+        }
+        if name.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid zero-length file name after {} names", files.len()),
+            ));
+        }
+        files.push(os_string(std::mem::take(&mut name)));
+    }
+    Ok(files)
+}
+
+// -- the names for a tool that takes its files as &str : one that isn't
+// UTF-8 is reported to `err` and left out, like a file that fails to open,
+// the others still go through
+pub fn utf8_file_names<E: Write>(names: Vec<OsString>, err: &mut E) -> Vec<String> {
+    names
+        .into_iter()
+        .filter_map(|name| match name.into_string() {
+            Ok(name) => Some(name),
+            Err(name) => {
+                // best effort, like eprintln! without the panic
+                let _ = writeln!(
+                    err,
+                    "Failed to open {}: file name is not valid UTF-8",
+                    name.to_string_lossy()
+                );
+                None
+            }
+        })
+        .collect()
+}
+
+#[cfg(unix)]
+fn os_string(bytes: Vec<u8>) -> OsString {
+    use std::os::unix::ffi::OsStringExt;
+    OsString::from_vec(bytes)
+}
+
+// -- elsewhere names are UTF-16 underneath, bytes that aren't UTF-8 can't
+// name a file anyway
+#[cfg(not(unix))]
+fn os_string(bytes: Vec<u8>) -> OsString {
+    String::from_utf8_lossy(&bytes).into_owned().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_file_list, utf8_file_names};
+    use std::io::Cursor;
+
+    #[test]
+    fn test_read_file_list() {
+        let files = read_file_list(Cursor::new("a.txt\0dir/b c\n.txt\0last")).unwrap();
+        assert_eq!(files, ["a.txt", "dir/b c\n.txt", "last"]);
+        assert!(read_file_list(Cursor::new("")).unwrap().is_empty());
+        assert!(read_file_list(Cursor::new("a\0\0b\0")).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_utf8_file_names() {
+        // a name that isn't UTF-8 is read, then left out with a message
+        let files = read_file_list(Cursor::new(b"a\0caf\xe9\0b".to_vec())).unwrap();
+        assert_eq!(files.len(), 3);
+        let mut err = vec![];
+        assert_eq!(utf8_file_names(files, &mut err), ["a", "b"]);
+        assert_eq!(
+            String::from_utf8(err).unwrap(),
+            "Failed to open caf\u{FFFD}: file name is not valid UTF-8\n"
+        );
+    }
+}
@@ -0,0 +1,52 @@
+use std::io::{self, BufWriter, IsTerminal, StdoutLock, Write};
+
+// == output layer shared by the tools, see stdout()
+
+// big enough that a pipe or file gets few, large writes
+const CAPACITY: usize = 64 * 1024;
+
+// -- the locked stdout every tool prints through
+// - Terminal : std's own line buffering, each line shows up as soon as
+// it's printed (`catr` typed into interactively)
+// - Buffered : a pipe or a file, written a buffer at a time instead of a
+// line at a time
+// - variants are public so a caller can hand the concrete type to
+// io::copy(), which only splices into std types (see Source::copy_to())
+pub enum Stdout {
+    Terminal(StdoutLock<'static>),
+    Buffered(BufWriter<StdoutLock<'static>>),
+}
+
+// -- locks stdout for the rest of the run
+// - call flush() once done to see a failed write, dropping flushes too
+// (an error that cuts the run short) but ignores the result
+pub fn stdout() -> Stdout {
+    let lock = io::stdout().lock();
+    match lock.is_terminal() {
+        true => Stdout::Terminal(lock),
+        false => Stdout::Buffered(BufWriter::with_capacity(CAPACITY, lock)),
+    }
+}
+
+impl Write for Stdout {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Stdout::Terminal(out) => out.write(buf),
+            Stdout::Buffered(out) => out.write(buf),
+        }
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        match self {
+            Stdout::Terminal(out) => out.write_all(buf),
+            Stdout::Buffered(out) => out.write_all(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Stdout::Terminal(out) => out.flush(),
+            Stdout::Buffered(out) => out.flush(),
+        }
+    }
+}
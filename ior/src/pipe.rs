@@ -0,0 +1,54 @@
+use std::env;
+use std::io;
+
+// == a reader that goes away early : `catr huge.log | headr -n 1`
+// - Rust ignores SIGPIPE, so the next write fails with EPIPE instead of
+// killing the process : the tools stop there, quietly, whatever they
+// were doing
+// - the exit status is up to $SIGPIPE_STATUS, 0 unless it says 141
+
+// -- how a tool exits once its stdout is gone
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PipePolicy {
+    // status 0 : the reader got all it wanted, nothing went wrong
+    Success,
+    // status 141 (128 + SIGPIPE), what the shell reports for a process
+    // the signal killed, as seen by `set -o pipefail`
+    Signal,
+}
+
+impl PipePolicy {
+    // -- $SIGPIPE_STATUS=141 for Signal, anything else is Success
+    pub fn from_env() -> PipePolicy {
+        match env::var("SIGPIPE_STATUS").as_deref() {
+            Ok("141") => PipePolicy::Signal,
+            _ => PipePolicy::Success,
+        }
+    }
+
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            PipePolicy::Success => 0,
+            PipePolicy::Signal => 141,
+        }
+    }
+}
+
+// -- the write failed because nobody reads stdout (or the pager) anymore
+pub fn is_broken_pipe(err: &io::Error) -> bool {
+    err.kind() == io::ErrorKind::BrokenPipe
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_broken_pipe, PipePolicy};
+    use std::io;
+
+    #[test]
+    fn test_pipe_policy() {
+        assert_eq!(PipePolicy::Success.exit_code(), 0);
+        assert_eq!(PipePolicy::Signal.exit_code(), 141);
+        assert!(is_broken_pipe(&io::Error::from(io::ErrorKind::BrokenPipe)));
+        assert!(!is_broken_pipe(&io::Error::from(io::ErrorKind::WriteZero)));
+    }
+}
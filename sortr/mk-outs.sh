@@ -0,0 +1,61 @@
+#!/usr/bin/env bash
+
+# expected output comes from GNU sort (coreutils), in the C locale : sortr
+# compares bytes, not the locale's collation
+# - on macOS : `brew install coreutils` and run with SORT=gsort
+SORT="${SORT:-sort}"
+export LC_ALL=C
+
+set -u
+
+ROOT="tests/inputs"
+OUT_DIR="tests/expected"
+
+[[ ! -d "$OUT_DIR" ]] && mkdir -p "$OUT_DIR"
+
+EMPTY="$ROOT/empty.txt"
+WORDS="$ROOT/words.txt"
+NUMBERS="$ROOT/numbers.txt"
+SIZES="$ROOT/sizes.tsv"
+VERSIONS="$ROOT/versions.txt"
+PEOPLE="$ROOT/people.txt"
+SCORES="$ROOT/scores.txt"
+
+for FILE in $EMPTY $WORDS $NUMBERS; do
+  BASENAME=$(basename "$FILE")
+  $SORT       $FILE > ${OUT_DIR}/${BASENAME}.out
+  $SORT -r    $FILE > ${OUT_DIR}/${BASENAME}.r.out
+  $SORT -u    $FILE > ${OUT_DIR}/${BASENAME}.u.out
+  $SORT -b    $FILE > ${OUT_DIR}/${BASENAME}.b.out
+  $SORT -n    $FILE > ${OUT_DIR}/${BASENAME}.n.out
+  $SORT -n -r $FILE > ${OUT_DIR}/${BASENAME}.n.r.out
+  $SORT -n -u $FILE > ${OUT_DIR}/${BASENAME}.n.u.out
+  $SORT -n -s $FILE > ${OUT_DIR}/${BASENAME}.n.s.out
+done
+
+$SORT -h          $SIZES > ${OUT_DIR}/sizes.tsv.h.out
+$SORT -h -r       $SIZES > ${OUT_DIR}/sizes.tsv.h.r.out
+$SORT -V          $VERSIONS > ${OUT_DIR}/versions.txt.V.out
+$SORT -V -r       $VERSIONS > ${OUT_DIR}/versions.txt.V.r.out
+
+# keys, -t
+$SORT -t : -k 3        $PEOPLE > ${OUT_DIR}/people.txt.k3.out
+$SORT -t : -k 3,3n     $PEOPLE > ${OUT_DIR}/people.txt.k3n.out
+$SORT -t : -k 3,3n -s  $PEOPLE > ${OUT_DIR}/people.txt.k3n.s.out
+$SORT -t : -k 5,5 -k 3,3nr $PEOPLE > ${OUT_DIR}/people.txt.k5.k3nr.out
+$SORT -t : -k 4,4 -u   $PEOPLE > ${OUT_DIR}/people.txt.k4.u.out
+$SORT -t : -k 1.2,1.3  $PEOPLE > ${OUT_DIR}/people.txt.k1.2.out
+$SORT -t : -r -k 2     $PEOPLE > ${OUT_DIR}/people.txt.r.k2.out
+
+# blank separated fields, leading blanks part of the field without b
+$SORT -k 2         $SCORES > ${OUT_DIR}/scores.txt.k2.out
+$SORT -k 2,2n -k 1b,1 $SCORES > ${OUT_DIR}/scores.txt.k2n.k1b.out
+$SORT -b -k 3      $SCORES > ${OUT_DIR}/scores.txt.b.k3.out
+$SORT -k 1         $SCORES > ${OUT_DIR}/scores.txt.k1.out
+$SORT -k 1.3,1.3   $SCORES > ${OUT_DIR}/scores.txt.k1.3.out
+
+# all files together, a last line without a newline gets one
+$SORT $WORDS $ROOT/no-newline.txt $EMPTY > ${OUT_DIR}/all.out
+
+# compressed copy : tests expect the same output as the plain file
+gzip -nc $PEOPLE > $ROOT/people.txt.gz
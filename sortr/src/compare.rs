@@ -0,0 +1,268 @@
+use std::cmp::Ordering;
+
+// == the orderings a key can sort by, on the key's bytes
+// - everything is compared the way GNU sort does in the C locale : bytes,
+// no thousands separator, `.` as the decimal point
+
+// -- blanks, as in -b and between fields without -t
+pub fn is_blank(byte: u8) -> bool {
+    byte == b' ' || byte == b'\t'
+}
+
+pub fn skip_blanks(text: &[u8]) -> &[u8] {
+    let start = text.iter().position(|&b| !is_blank(b)).unwrap_or(text.len());
+    &text[start..]
+}
+
+// -- a number at the start of a key, after blanks : an optional `-`,
+// digits, an optional `.` and more digits
+// - anything else, an empty key included, is zero
+struct Number<'a> {
+    negative: bool,
+    // no leading zeros
+    int: &'a [u8],
+    // no trailing zeros
+    frac: &'a [u8],
+    // what follows, for -h's suffix
+    rest: &'a [u8],
+}
+
+impl Number<'_> {
+    fn parse(text: &[u8]) -> Number<'_> {
+        let text = skip_blanks(text);
+        let (negative, text) = match text.first() {
+            Some(b'-') => (true, &text[1..]),
+            _ => (false, text),
+        };
+        let digits = |text: &[u8]| text.iter().take_while(|b| b.is_ascii_digit()).count();
+        let int_len = digits(text);
+        let (int, mut rest) = text.split_at(int_len);
+        let mut frac: &[u8] = &[];
+        if let Some(after) = rest.strip_prefix(b".") {
+            let (digits, after) = after.split_at(digits(after));
+            frac = digits;
+            rest = after;
+        }
+        let int = &int[int.iter().position(|&b| b != b'0').unwrap_or(int.len())..];
+        let frac = &frac[..frac.iter().rposition(|&b| b != b'0').map_or(0, |i| i + 1)];
+        Number {
+            negative,
+            int,
+            frac,
+            rest,
+        }
+    }
+
+    fn is_zero(&self) -> bool {
+        self.int.is_empty() && self.frac.is_empty()
+    }
+
+    // -- -1, 0 or 1, a negative zero is zero
+    fn sign(&self) -> i32 {
+        match (self.is_zero(), self.negative) {
+            (true, _) => 0,
+            (false, true) => -1,
+            (false, false) => 1,
+        }
+    }
+
+    // -- digit strings are compared as they are, no float conversion : any
+    // length, no rounding
+    fn cmp_magnitude(&self, other: &Number) -> Ordering {
+        self.int
+            .len()
+            .cmp(&other.int.len())
+            .then_with(|| self.int.cmp(other.int))
+            .then_with(|| self.frac.cmp(other.frac))
+    }
+}
+
+// -- -n
+pub fn numeric(a: &[u8], b: &[u8]) -> Ordering {
+    cmp_numbers(&Number::parse(a), &Number::parse(b))
+}
+
+fn cmp_numbers(a: &Number, b: &Number) -> Ordering {
+    let (sign_a, sign_b) = (a.sign(), b.sign());
+    match sign_a.cmp(&sign_b) {
+        Ordering::Equal if sign_a < 0 => b.cmp_magnitude(a),
+        Ordering::Equal => a.cmp_magnitude(b),
+        unequal => unequal,
+    }
+}
+
+// -- -h : 2K, 1.5M, 10G ... a bigger suffix wins whatever the number, the
+// number only decides between equal suffixes
+// - no suffix comes between the negative and positive suffixed numbers
+pub fn human(a: &[u8], b: &[u8]) -> Ordering {
+    let (a, b) = (Number::parse(a), Number::parse(b));
+    unit_order(&a)
+        .cmp(&unit_order(&b))
+        .then_with(|| cmp_numbers(&a, &b))
+}
+
+// -- like GNU sort : k and K both kilo, the sign of a zero ignored
+fn unit_order(number: &Number) -> i32 {
+    const UNITS: &[u8] = b"KMGTPEZYRQ";
+    let order = match number.rest.first() {
+        Some(b'k') => 1,
+        Some(unit) => UNITS.iter().position(|u| u == unit).map_or(0, |i| i as i32 + 1),
+        None => 0,
+    };
+    match number.is_zero() {
+        true => 0,
+        false if number.negative => -order,
+        false => order,
+    }
+}
+
+// -- -V : GNU's filevercmp, runs of digits compare as numbers
+// - ".", "..", then other names starting with "." sort before the rest
+// - a trailing file suffix (.tar.gz ...) only counts when the names are
+// otherwise equal
+pub fn version(a: &[u8], b: &[u8]) -> Ordering {
+    match (a.is_empty(), b.is_empty()) {
+        (true, true) => return Ordering::Equal,
+        (true, false) => return Ordering::Less,
+        (false, true) => return Ordering::Greater,
+        _ => {}
+    }
+    match (a[0] == b'.', b[0] == b'.') {
+        (true, false) => return Ordering::Less,
+        (false, true) => return Ordering::Greater,
+        (true, true) => {
+            for special in [&b"."[..], b".."] {
+                match (a == special, b == special) {
+                    (true, true) => return Ordering::Equal,
+                    (true, false) => return Ordering::Less,
+                    (false, true) => return Ordering::Greater,
+                    _ => {}
+                }
+            }
+        }
+        _ => {}
+    }
+    let (a_prefix, b_prefix) = (prefix_len(a), prefix_len(b));
+    let result = verrevcmp(&a[..a_prefix], &b[..b_prefix]);
+    if result != 0 || (a_prefix == a.len() && b_prefix == b.len()) {
+        return result.cmp(&0);
+    }
+    verrevcmp(a, b).cmp(&0)
+}
+
+// -- `a` without its suffix : (\.[A-Za-z~][A-Za-z0-9~]*)* at the end,
+// never the first byte
+fn prefix_len(s: &[u8]) -> usize {
+    let mut prefix = 0;
+    let mut i = 0;
+    while i < s.len() {
+        i += 1;
+        prefix = i;
+        while i + 1 < s.len() && s[i] == b'.' && (s[i + 1].is_ascii_alphabetic() || s[i + 1] == b'~') {
+            i += 2;
+            while i < s.len() && (s[i].is_ascii_alphanumeric() || s[i] == b'~') {
+                i += 1;
+            }
+        }
+    }
+    prefix
+}
+
+// -- Debian's version compare : ~ before the end before letters before
+// anything else, digits compared as numbers
+fn verrevcmp(a: &[u8], b: &[u8]) -> i32 {
+    let order = |s: &[u8], pos: usize| -> i32 {
+        match s.get(pos) {
+            None => -1,
+            Some(c) if c.is_ascii_digit() => 0,
+            Some(c) if c.is_ascii_alphabetic() => *c as i32,
+            Some(b'~') => -2,
+            Some(c) => *c as i32 + 256,
+        }
+    };
+    let digit_at = |s: &[u8], pos: usize| s.get(pos).is_some_and(|c| c.is_ascii_digit());
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        while (i < a.len() && !a[i].is_ascii_digit()) || (j < b.len() && !b[j].is_ascii_digit()) {
+            let (oa, ob) = (order(a, i), order(b, j));
+            if oa != ob {
+                return oa - ob;
+            }
+            i += 1;
+            j += 1;
+        }
+        while a.get(i) == Some(&b'0') {
+            i += 1;
+        }
+        while b.get(j) == Some(&b'0') {
+            j += 1;
+        }
+        let mut first_diff = 0;
+        while digit_at(a, i) && digit_at(b, j) {
+            if first_diff == 0 {
+                first_diff = a[i] as i32 - b[j] as i32;
+            }
+            i += 1;
+            j += 1;
+        }
+        if digit_at(a, i) {
+            return 1;
+        }
+        if digit_at(b, j) {
+            return -1;
+        }
+        if first_diff != 0 {
+            return first_diff;
+        }
+    }
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{human, numeric, version};
+    use std::cmp::Ordering::{Equal, Greater, Less};
+
+    #[test]
+    fn test_numeric() {
+        assert_eq!(numeric(b"10", b"9"), Greater);
+        assert_eq!(numeric(b"  -3", b"-20"), Greater);
+        assert_eq!(numeric(b"007.50", b"7.5x"), Equal);
+        assert_eq!(numeric(b"-0", b"abc"), Equal);
+        assert_eq!(numeric(b"-.5", b""), Less);
+        assert_eq!(numeric(b"123456789012345678901234567890", b"9e9"), Greater);
+    }
+
+    #[test]
+    fn test_human() {
+        assert_eq!(human(b"2K", b"1000"), Greater);
+        assert_eq!(human(b"1k", b"1K"), Equal);
+        assert_eq!(human(b"1.5M", b"2M"), Less);
+        assert_eq!(human(b"-1G", b"-1K"), Less);
+        assert_eq!(human(b"0G", b"1"), Less);
+    }
+
+    #[test]
+    fn test_version() {
+        let sorted = [
+            &b""[..],
+            b".",
+            b"..",
+            b".hidden",
+            b"a~1",
+            b"a",
+            b"a1",
+            b"a2",
+            b"a10",
+            b"a10a",
+            b"b1.2.tar.gz",
+            b"b1.10.tar.gz",
+            b"b1.10.zip",
+        ];
+        for pair in sorted.windows(2) {
+            assert_eq!(version(pair[0], pair[1]), Less, "{:?}", pair);
+            assert_eq!(version(pair[1], pair[0]), Greater, "{:?}", pair);
+        }
+        assert_eq!(version(b"a01", b"a1"), Equal);
+    }
+}
@@ -0,0 +1,48 @@
+use std::io;
+use thiserror::Error;
+
+// == everything sortr can fail with
+// - a file that fails to open is reported and skipped, not returned
+#[derive(Debug, Error)]
+pub enum Error {
+    // -k KEYDEF that doesn't parse, `reason` says why
+    #[error("invalid key -- {spec} ({reason})")]
+    InvalidKey { spec: String, reason: &'static str },
+
+    #[error("illegal separator -- {0} (must be a single byte)")]
+    InvalidSeparator(String),
+
+    #[error("illegal buffer size -- {0}")]
+    InvalidBufferSize(String),
+
+    #[error("Failed to open {path}: {source}")]
+    OpenFailed { path: String, source: io::Error },
+
+    #[error("{path}: {source}")]
+    ReadFailed { path: String, source: io::Error },
+
+    // creating, writing or reading back a sorted run (-T, a full disk ...)
+    #[error("temp file: {0}")]
+    TempFailed(#[source] io::Error),
+
+    #[error("write error: {0}")]
+    WriteFailed(#[source] io::Error),
+}
+
+impl ior::Failure for Error {
+    fn exit_code(&self) -> i32 {
+        match self {
+            Error::InvalidKey { .. } | Error::InvalidSeparator(_) | Error::InvalidBufferSize(_) => 2,
+            Error::OpenFailed { .. } => 3,
+            Error::ReadFailed { .. } | Error::TempFailed(_) => 4,
+            Error::WriteFailed(_) => 6,
+        }
+    }
+
+    fn write_error(&self) -> Option<&io::Error> {
+        match self {
+            Error::WriteFailed(e) => Some(e),
+            _ => None,
+        }
+    }
+}
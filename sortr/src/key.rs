@@ -0,0 +1,264 @@
+use crate::compare::{self, is_blank};
+use std::cmp::Ordering;
+
+// == -k : the part of each line to sort by, and how
+// - F[.C][OPTS][,F[.C][OPTS]] : from character C of field F (1 based) to
+// character C of the second field, included, or to the end of that field
+// when C is left out (or 0), or to the end of the line without a second F
+// - fields are split on -t SEP, or else start where blanks do : a field
+// keeps the blanks in front of it, `b` skips them
+// - OPTS is any of b n h V r, a key with none of its own takes the global
+// -b -n -h -V -r
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Order {
+    // byte by byte
+    Text,
+    // -n
+    Numeric,
+    // -h : 2K, 1.5M ...
+    Human,
+    // -V : file names with version numbers
+    Version,
+}
+
+// -- the options a key can have of its own
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Options {
+    pub order: Order,
+    pub reverse: bool,
+    // b on the start / end position
+    pub blanks_start: bool,
+    pub blanks_end: bool,
+}
+
+impl Default for Options {
+    fn default() -> Options {
+        Options {
+            order: Order::Text,
+            reverse: false,
+            blanks_start: false,
+            blanks_end: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Key {
+    // field and character, 1 based
+    pub start: (usize, usize),
+    // character 0 is the end of the field, None the end of the line
+    pub end: Option<(usize, usize)>,
+    pub options: Options,
+}
+
+impl Default for Key {
+    // -- the whole line, as text
+    fn default() -> Key {
+        Key::whole_line(Options::default())
+    }
+}
+
+impl Key {
+    pub fn whole_line(options: Options) -> Key {
+        Key {
+            start: (1, 1),
+            end: None,
+            options,
+        }
+    }
+
+    // -- `global` are the -b -n -h -V -r given outside of any key
+    pub fn parse(spec: &str, global: Options) -> Result<Key, &'static str> {
+        let (start, end) = match spec.split_once(',') {
+            Some((start, end)) => (start, Some(end)),
+            None => (spec, None),
+        };
+        let (start, start_opts) = parse_position(start, false)?;
+        let (end, end_opts) = match end {
+            Some(end) => {
+                let (end, opts) = parse_position(end, true)?;
+                (Some(end), opts)
+            }
+            None => (None, ""),
+        };
+
+        if start_opts.is_empty() && end_opts.is_empty() {
+            return Ok(Key {
+                start,
+                end,
+                options: global,
+            });
+        }
+        let mut options = Options::default();
+        for (opts, at_end) in [(start_opts, false), (end_opts, true)] {
+            for opt in opts.chars() {
+                let order = match opt {
+                    'b' if at_end => {
+                        options.blanks_end = true;
+                        continue;
+                    }
+                    'b' => {
+                        options.blanks_start = true;
+                        continue;
+                    }
+                    'r' => {
+                        options.reverse = true;
+                        continue;
+                    }
+                    'n' => Order::Numeric,
+                    'h' => Order::Human,
+                    'V' => Order::Version,
+                    _ => return Err("unknown option"),
+                };
+                if options.order != Order::Text && options.order != order {
+                    return Err("more than one of n, h, V");
+                }
+                options.order = order;
+            }
+        }
+        Ok(Key {
+            start,
+            end,
+            options,
+        })
+    }
+
+    // -- the bytes of `line` this key covers, empty when the line is too
+    // short, or the end comes before the start
+    pub fn extract<'a>(&self, line: &'a [u8], separator: Option<u8>) -> &'a [u8] {
+        let (field, char) = self.start;
+        let mut start = field_start(line, field - 1, separator);
+        if self.options.blanks_start {
+            start += count_blanks(&line[start..]);
+        }
+        let start = line.len().min(start + char - 1);
+
+        let end = match self.end {
+            None => line.len(),
+            Some((field, 0)) => {
+                let from = field_start(line, field - 1, separator);
+                from + field_len(&line[from..], separator)
+            }
+            Some((field, char)) => {
+                let mut from = field_start(line, field - 1, separator);
+                if self.options.blanks_end {
+                    from += count_blanks(&line[from..]);
+                }
+                line.len().min(from + char)
+            }
+        };
+        &line[start..end.max(start)]
+    }
+
+    pub fn compare(&self, a: &[u8], b: &[u8], separator: Option<u8>) -> Ordering {
+        let (a, b) = (self.extract(a, separator), self.extract(b, separator));
+        let ordering = match self.options.order {
+            Order::Text => a.cmp(b),
+            Order::Numeric => compare::numeric(a, b),
+            Order::Human => compare::human(a, b),
+            Order::Version => compare::version(a, b),
+        };
+        match self.options.reverse {
+            true => ordering.reverse(),
+            false => ordering,
+        }
+    }
+}
+
+// -- F[.C] and the option letters after it, C may only be 0 at the end
+fn parse_position(spec: &str, end: bool) -> Result<((usize, usize), &str), &'static str> {
+    let opts_at = spec
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(spec.len());
+    let (position, opts) = spec.split_at(opts_at);
+    let (field, char) = match position.split_once('.') {
+        Some((field, char)) => (field, Some(char)),
+        None => (position, None),
+    };
+    let field: usize = field.parse().map_err(|_| "not a field number")?;
+    if field == 0 {
+        return Err("fields are numbered from 1");
+    }
+    let char = match char {
+        Some(char) => char.parse().map_err(|_| "not a character offset")?,
+        None if end => 0,
+        None => 1,
+    };
+    if char == 0 && !end {
+        return Err("characters are numbered from 1");
+    }
+    Ok(((field, char), opts))
+}
+
+// -- where field `skip` + 1 starts, after `skip` fields and their
+// separators, the line's length when it has fewer
+fn field_start(line: &[u8], skip: usize, separator: Option<u8>) -> usize {
+    let mut pos = 0;
+    for _ in 0..skip {
+        if pos == line.len() {
+            break;
+        }
+        pos += field_len(&line[pos..], separator);
+        if separator.is_some() && pos < line.len() {
+            pos += 1;
+        }
+    }
+    pos
+}
+
+// -- up to the next separator, or over blanks then non-blanks
+fn field_len(text: &[u8], separator: Option<u8>) -> usize {
+    match separator {
+        Some(sep) => text.iter().position(|&b| b == sep).unwrap_or(text.len()),
+        None => {
+            let blanks = count_blanks(text);
+            blanks + text[blanks..].iter().take_while(|&&b| !is_blank(b)).count()
+        }
+    }
+}
+
+fn count_blanks(text: &[u8]) -> usize {
+    text.len() - compare::skip_blanks(text).len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Key, Options, Order};
+
+    fn extract(spec: &str, line: &str, separator: Option<u8>) -> String {
+        let key = Key::parse(spec, Options::default()).unwrap();
+        String::from_utf8(key.extract(line.as_bytes(), separator).to_vec()).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let global = Options {
+            order: Order::Numeric,
+            ..Options::default()
+        };
+        let key = Key::parse("2,3", global).unwrap();
+        assert_eq!((key.start, key.end, key.options), ((2, 1), Some((3, 0)), global));
+        let key = Key::parse("1.2bV,1.4r", global).unwrap();
+        assert_eq!((key.start, key.end), ((1, 2), Some((1, 4))));
+        assert_eq!(key.options.order, Order::Version);
+        assert!(key.options.reverse && key.options.blanks_start && !key.options.blanks_end);
+        for bad in ["0", "1.0", "x", "1,", "1n,2h", "1q", ""] {
+            assert!(Key::parse(bad, global).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_extract() {
+        // without -t a field keeps the blanks in front of it
+        assert_eq!(extract("2", "a  b c", None), "  b c");
+        assert_eq!(extract("2,2", "a  b c", None), "  b");
+        assert_eq!(extract("2b,2", "a  b c", None), "b");
+        assert_eq!(extract("2.2,2.3", "a  b c", None), " b");
+        assert_eq!(extract("1.2,1.3", "abcd:e", Some(b':')), "bc");
+        assert_eq!(extract("2,2", "a::c", Some(b':')), "");
+        assert_eq!(extract("3", "a:b:c:d", Some(b':')), "c:d");
+        assert_eq!(extract("5", "a:b", Some(b':')), "");
+        assert_eq!(extract("2,1", "a:b", Some(b':')), "");
+    }
+}
@@ -0,0 +1,391 @@
+use clap::{App, Arg};
+use std::cmp::Ordering;
+use std::env;
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+
+mod compare;
+mod error;
+mod key;
+mod merge;
+
+pub use error::Error;
+pub use key::{Key, Options, Order};
+pub use merge::Sorter;
+
+type MyResult<T> = Result<T, Error>;
+
+// held in memory before spilling to temp files, -S
+const BUFFER_SIZE: usize = 64 * 1024 * 1024;
+
+// == sort : the lines of every file, together, in order
+// - built by get_args(), or by hand when sortr is used as a library
+// - lines compare by each key in turn, then, when the keys are all equal,
+// byte by byte as a last resort (left out with --stable or -u)
+#[derive(Debug)]
+pub struct Config {
+    pub files: Vec<String>,
+    // never empty : the whole line when there's no -k
+    pub keys: Vec<Key>,
+    // -t : splits fields, blanks do by default
+    pub separator: Option<u8>,
+    // -r : also reverses the last resort, a key's own r doesn't
+    pub reverse: bool,
+    // -u : only the first of each run of equal lines
+    pub unique: bool,
+    // --stable : equal lines keep their input order
+    pub stable: bool,
+    // -S : bytes of lines held before a sorted run is spilled to disk
+    pub buffer_size: usize,
+    // -T : where the runs go, $TMPDIR by default
+    pub tmp_dir: PathBuf,
+    // decode gzip, bzip2, xz and zstd input (off with --no-decompress)
+    pub decompress: bool,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            files: vec!["-".to_string()],
+            keys: vec![Key::default()],
+            separator: None,
+            reverse: false,
+            unique: false,
+            stable: false,
+            buffer_size: BUFFER_SIZE,
+            tmp_dir: env::temp_dir(),
+            decompress: true,
+        }
+    }
+}
+
+impl Config {
+    // -- the order lines come out in
+    pub fn compare(&self, a: &[u8], b: &[u8]) -> Ordering {
+        let ordering = self.compare_keys(a, b);
+        if ordering != Ordering::Equal || self.unique || self.stable {
+            return ordering;
+        }
+        match self.reverse {
+            true => b.cmp(a),
+            false => a.cmp(b),
+        }
+    }
+
+    // -- equal here is a duplicate for -u
+    pub fn compare_keys(&self, a: &[u8], b: &[u8]) -> Ordering {
+        self.keys
+            .iter()
+            .map(|key| key.compare(a, b, self.separator))
+            .find(|ordering| *ordering != Ordering::Equal)
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+pub fn run(config: Config) -> MyResult<()> {
+    // buffered unless stdout is a terminal, see ior::stdout()
+    let mut out = ior::stdout();
+    run_with(&config, &mut out, &mut io::stderr().lock())?;
+    out.flush().map_err(Error::WriteFailed)
+}
+
+// -- run() with the output and the per-file errors going where the caller
+// says, e.g. into a Vec<u8>
+// - a file that fails to open is reported to `err` and skipped, the rest
+// are still sorted together
+pub fn run_with<W: Write, E: Write>(config: &Config, out: &mut W, err: &mut E) -> MyResult<()> {
+    let mut sorter = Sorter::new(config);
+    for filename in &config.files {
+        match open(filename, config.decompress) {
+            // the error sink is best effort, like eprintln! without the panic
+            Err(e) => {
+                let _ = writeln!(err, "{}", e);
+            }
+            Ok(input) => read_lines(input, filename, &mut sorter)?,
+        }
+    }
+    sorter.finish(out)
+}
+
+// -- one input sorted on its own, `filename` only names it in errors
+pub fn sort<R: BufRead, W: Write>(
+    input: R,
+    filename: &str,
+    config: &Config,
+    out: &mut W,
+) -> MyResult<()> {
+    let mut sorter = Sorter::new(config);
+    read_lines(input, filename, &mut sorter)?;
+    sorter.finish(out)
+}
+
+// -- a last line without a newline is sorted like the others, and gets
+// one on the way out
+fn read_lines<R: BufRead>(mut input: R, filename: &str, sorter: &mut Sorter) -> MyResult<()> {
+    loop {
+        let mut line = vec![];
+        let bytes = input
+            .read_until(b'\n', &mut line)
+            .map_err(|source| Error::ReadFailed {
+                path: filename.to_string(),
+                source,
+            })?;
+        if bytes == 0 {
+            return Ok(());
+        }
+        if line.last() == Some(&b'\n') {
+            line.pop();
+        }
+        sorter.push(line)?;
+    }
+}
+
+pub fn get_args() -> MyResult<Config> {
+    let matches = App::new("sortr")
+        .version("0.1.0")
+        .author("MTON <mton@aol.com>")
+        .about("Rust sort")
+        // -h and -V are sort's, --help and --version stay
+        .help_short("?")
+        .version_short("v")
+        .arg(
+            Arg::with_name("files")
+                .value_name("FILE")
+                .help("Input file(s)")
+                .multiple(true)
+                .default_value("-")
+        )
+        .arg(
+            Arg::with_name("numeric")
+                .short("n")
+                .long("numeric-sort")
+                .help("Compare by numeric value")
+                .takes_value(false)
+                .conflicts_with_all(&["human", "version_sort"])
+        )
+        .arg(
+            Arg::with_name("human")
+                .short("h")
+                .long("human-numeric-sort")
+                .help("Compare human readable numbers (2K, 1G)")
+                .takes_value(false)
+                .conflicts_with("version_sort")
+        )
+        .arg(
+            Arg::with_name("version_sort")
+                .short("V")
+                .long("version-sort")
+                .help("Compare version numbers within text")
+                .takes_value(false)
+        )
+        .arg(
+            Arg::with_name("reverse")
+                .short("r")
+                .long("reverse")
+                .help("Reverse the result of comparisons")
+                .takes_value(false)
+        )
+        .arg(
+            Arg::with_name("unique")
+                .short("u")
+                .long("unique")
+                .help("Output only the first of equal lines")
+                .takes_value(false)
+        )
+        .arg(
+            Arg::with_name("stable")
+                .short("s")
+                .long("stable")
+                .help("Keep equal lines in input order, no last-resort comparison")
+                .takes_value(false)
+        )
+        .arg(
+            Arg::with_name("blanks")
+                .short("b")
+                .long("ignore-leading-blanks")
+                .help("Ignore leading blanks")
+                .takes_value(false)
+        )
+        .arg(
+            Arg::with_name("key")
+                .short("k")
+                .long("key")
+                .value_name("KEYDEF")
+                .help("Sort by a key: F[.C][OPTS][,F[.C][OPTS]]")
+                .multiple(true)
+                .number_of_values(1)
+        )
+        .arg(
+            Arg::with_name("separator")
+                .short("t")
+                .long("field-separator")
+                .value_name("SEP")
+                .help("Use SEP instead of the non-blank to blank transition")
+                .allow_hyphen_values(true)
+        )
+        .arg(
+            Arg::with_name("buffer_size")
+                .short("S")
+                .long("buffer-size")
+                .value_name("SIZE")
+                .help("Memory for lines before spilling to temp files [KiB, or b K M G]")
+        )
+        .arg(
+            Arg::with_name("tmp_dir")
+                .short("T")
+                .long("temporary-directory")
+                .value_name("DIR")
+                .help("Put temp files in DIR instead of $TMPDIR")
+        )
+        .arg(
+            Arg::with_name("no_decompress")
+                .long("no-decompress")
+                .help("Pass compressed input through undecoded")
+                .takes_value(false)
+        )
+        .get_matches();
+
+    let blanks = matches.is_present("blanks");
+    let global = Options {
+        order: match () {
+            _ if matches.is_present("numeric") => Order::Numeric,
+            _ if matches.is_present("human") => Order::Human,
+            _ if matches.is_present("version_sort") => Order::Version,
+            _ => Order::Text,
+        },
+        reverse: matches.is_present("reverse"),
+        blanks_start: blanks,
+        blanks_end: blanks,
+    };
+    let mut keys = vec![];
+    for spec in matches.values_of("key").into_iter().flatten() {
+        let key = Key::parse(spec, global).map_err(|reason| Error::InvalidKey {
+            spec: spec.to_string(),
+            reason,
+        })?;
+        keys.push(key);
+    }
+    if keys.is_empty() {
+        keys.push(Key::whole_line(global));
+    }
+
+    let separator = match matches.value_of("separator") {
+        None => None,
+        Some(sep) if sep.len() == 1 => Some(sep.as_bytes()[0]),
+        Some(sep) => return Err(Error::InvalidSeparator(sep.to_string())),
+    };
+
+    let buffer_size = match matches.value_of("buffer_size") {
+        None => BUFFER_SIZE,
+        Some(val) => parse_size(val).ok_or_else(|| Error::InvalidBufferSize(val.to_string()))?,
+    };
+
+    Ok(Config {
+        files: matches.values_of_lossy("files").unwrap(),
+        keys,
+        separator,
+        reverse: global.reverse,
+        unique: matches.is_present("unique"),
+        stable: matches.is_present("stable"),
+        buffer_size,
+        tmp_dir: matches
+            .value_of("tmp_dir")
+            .map_or_else(env::temp_dir, PathBuf::from),
+        decompress: !matches.is_present("no_decompress"),
+    })
+}
+
+// -- a number of KiB, like sort -S, or with a b K M G T suffix
+fn parse_size(val: &str) -> Option<usize> {
+    let (digits, unit) = match val.find(|c: char| !c.is_ascii_digit()) {
+        Some(at) => val.split_at(at),
+        None => (val, "K"),
+    };
+    let shift = match unit {
+        "b" => 0,
+        "K" | "k" => 10,
+        "M" | "m" => 20,
+        "G" | "g" => 30,
+        "T" | "t" => 40,
+        _ => return None,
+    };
+    let size: usize = digits.parse().ok()?;
+    size.checked_mul(1 << shift).filter(|&size| size > 0)
+}
+
+fn open(filename: &str, decompress: bool) -> MyResult<Box<dyn BufRead>> {
+    ior::open(filename, decompress).map_err(|source| Error::OpenFailed {
+        path: filename.to_string(),
+        source,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_size, sort, Config, Key, Options, Order};
+    use std::io::Cursor;
+
+    fn run_sort(input: &str, config: &Config) -> String {
+        let mut out = vec![];
+        sort(Cursor::new(input), "-", config, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    fn key(spec: &str) -> Key {
+        Key::parse(spec, Options::default()).unwrap()
+    }
+
+    #[test]
+    fn test_sort() {
+        let config = Config::default();
+        assert_eq!(run_sort("b\na\nc", &config), "a\nb\nc\n");
+        assert_eq!(run_sort("", &config), "");
+
+        // equal numbers fall back on the bytes, unless --stable
+        let numeric = Config {
+            keys: vec![Key::whole_line(Options {
+                order: Order::Numeric,
+                ..Options::default()
+            })],
+            ..Config::default()
+        };
+        assert_eq!(run_sort("10\n9\n09\n-1\n", &numeric), "-1\n09\n9\n10\n");
+        let stable = Config {
+            stable: true,
+            ..numeric
+        };
+        assert_eq!(run_sort("10\n9\n09\n-1\n", &stable), "-1\n9\n09\n10\n");
+
+        let keys = Config {
+            keys: vec![key("2,2"), key("1,1nr")],
+            separator: Some(b':'),
+            unique: true,
+            ..Config::default()
+        };
+        assert_eq!(run_sort("1:b\n2:a\n3:b\n1:b\n", &keys), "2:a\n3:b\n1:b\n");
+    }
+
+    #[test]
+    fn test_external() {
+        // a run every couple of lines, and more runs than are merged at once
+        let input: Vec<String> = (0..1000).map(|i| format!("{}", (i * 7919) % 1000)).collect();
+        let mut expected = input.clone();
+        expected.sort_by_key(|line| line.parse::<u32>().unwrap());
+        let config = Config {
+            keys: vec![key("1n")],
+            buffer_size: 64,
+            ..Config::default()
+        };
+        assert_eq!(run_sort(&input.join("\n"), &config), expected.join("\n") + "\n");
+    }
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("1"), Some(1024));
+        assert_eq!(parse_size("100b"), Some(100));
+        assert_eq!(parse_size("2M"), Some(2 << 20));
+        for bad in ["", "0", "1X", "M", "-1"] {
+            assert_eq!(parse_size(bad), None, "{}", bad);
+        }
+    }
+}
@@ -0,0 +1,14 @@
+use ior::Failure;
+
+fn main() {
+    if let Err(e) = sortr::get_args().and_then(sortr::run) {
+        // `sortr big.txt | headr` : everything is read and sorted before
+        // the first line goes out, only then can the pipe be found closed
+        if e.is_broken_pipe() {
+            std::process::exit(ior::PipePolicy::from_env().exit_code());
+        }
+        eprintln!("{}", e);
+        // each kind of failure has its own status, see sortr::Error
+        std::process::exit(e.exit_code());
+    }
+}
@@ -0,0 +1,235 @@
+use crate::{Config, Error, MyResult};
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Seek, SeekFrom, Write};
+use std::mem;
+
+// == external merge sort
+// - lines are held in memory up to config.buffer_size, then sorted and
+// spilled to a temp file (a run), and the next lines start a new one
+// - runs are merged MERGE_FAN at a time so the open files stay bounded :
+// as soon as there are MERGE_FAN of one size (like carrying in a counter),
+// and the rest at the end
+// - only neighbouring runs are merged, and earlier runs win ties, so the
+// sort stays stable across runs the way it is within one
+// - temp files are unnamed (tempfile::tempfile_in), gone once closed, ctrl-C
+// included
+
+// runs merged at once, GNU sort's default too
+const MERGE_FAN: usize = 16;
+// held per line besides its bytes : the Vec itself
+const LINE_OVERHEAD: usize = mem::size_of::<Vec<u8>>();
+
+pub struct Sorter<'a> {
+    config: &'a Config,
+    lines: Vec<Vec<u8>>,
+    // bytes held by `lines`, overhead included
+    held: usize,
+    runs: Vec<Run>,
+}
+
+// -- sorted lines in a temp file, `level` merges up from the spilled ones
+struct Run {
+    level: u32,
+    file: File,
+}
+
+impl<'a> Sorter<'a> {
+    pub fn new(config: &'a Config) -> Sorter<'a> {
+        Sorter {
+            config,
+            lines: vec![],
+            held: 0,
+            runs: vec![],
+        }
+    }
+
+    // -- one line, without its newline
+    pub fn push(&mut self, line: Vec<u8>) -> MyResult<()> {
+        self.held += line.len() + LINE_OVERHEAD;
+        self.lines.push(line);
+        if self.held > self.config.buffer_size {
+            self.spill()?;
+        }
+        Ok(())
+    }
+
+    // -- everything pushed, sorted, each line with a newline
+    pub fn finish<W: Write>(mut self, out: &mut W) -> MyResult<()> {
+        let mut emit = Emitter::new(self.config, out);
+        if self.runs.is_empty() {
+            self.sort_lines();
+            for line in &self.lines {
+                emit.line(line)?;
+            }
+            return Ok(());
+        }
+        if !self.lines.is_empty() {
+            self.spill()?;
+        }
+        let mut runs: Vec<File> = self.runs.drain(..).map(|run| run.file).collect();
+        while runs.len() > MERGE_FAN {
+            let rest = runs.split_off(MERGE_FAN);
+            runs = vec![merge_to_temp(self.config, runs)?];
+            runs.extend(rest);
+        }
+        merge(self.config, runs, |line| emit.line(line))
+    }
+
+    fn sort_lines(&mut self) {
+        let config = self.config;
+        // stable : with --stable or -u equal keys keep their input order
+        self.lines.sort_by(|a, b| config.compare(a, b));
+    }
+
+    // -- the lines held, sorted into a run of their own
+    fn spill(&mut self) -> MyResult<()> {
+        self.sort_lines();
+        let mut file = tempfile(self.config)?;
+        let mut writer = BufWriter::new(&mut file);
+        for line in self.lines.drain(..) {
+            write_line(&mut writer, &line)?;
+        }
+        writer.flush().map_err(Error::TempFailed)?;
+        drop(writer);
+        self.runs.push(Run { level: 0, file });
+        self.held = 0;
+
+        while self.runs.len() >= MERGE_FAN {
+            let tail = self.runs.len() - MERGE_FAN;
+            let level = self.runs[tail].level;
+            if self.runs[tail..].iter().any(|run| run.level != level) {
+                break;
+            }
+            let files = self.runs.drain(tail..).map(|run| run.file).collect();
+            let file = merge_to_temp(self.config, files)?;
+            self.runs.push(Run {
+                level: level + 1,
+                file,
+            });
+        }
+        Ok(())
+    }
+}
+
+fn tempfile(config: &Config) -> MyResult<File> {
+    tempfile::tempfile_in(&config.tmp_dir).map_err(Error::TempFailed)
+}
+
+fn merge_to_temp(config: &Config, runs: Vec<File>) -> MyResult<File> {
+    let mut merged = tempfile(config)?;
+    let mut writer = BufWriter::new(&mut merged);
+    merge(config, runs, |line| write_line(&mut writer, line))?;
+    writer.flush().map_err(Error::TempFailed)?;
+    drop(writer);
+    Ok(merged)
+}
+
+fn write_line<W: Write>(out: &mut W, line: &[u8]) -> MyResult<()> {
+    out.write_all(line)
+        .and_then(|_| out.write_all(b"\n"))
+        .map_err(Error::TempFailed)
+}
+
+// -- the next line of each run, smallest first out of the heap
+struct Head<'a> {
+    config: &'a Config,
+    line: Vec<u8>,
+    run: usize,
+}
+
+impl Ord for Head<'_> {
+    // -- reversed, BinaryHeap pops the greatest
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.config
+            .compare(&self.line, &other.line)
+            .then(self.run.cmp(&other.run))
+            .reverse()
+    }
+}
+
+impl PartialOrd for Head<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Head<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Head<'_> {}
+
+// -- sorted runs into one sorted stream, handed to `emit` a line at a time
+fn merge<F>(config: &Config, runs: Vec<File>, mut emit: F) -> MyResult<()>
+where
+    F: FnMut(&[u8]) -> MyResult<()>,
+{
+    let mut readers = vec![];
+    for mut run in runs {
+        run.seek(SeekFrom::Start(0)).map_err(Error::TempFailed)?;
+        readers.push(BufReader::new(run));
+    }
+    let mut heap = BinaryHeap::with_capacity(readers.len());
+    for (run, reader) in readers.iter_mut().enumerate() {
+        if let Some(line) = next_line(reader)? {
+            heap.push(Head { config, line, run });
+        }
+    }
+    while let Some(mut head) = heap.pop() {
+        emit(&head.line)?;
+        if let Some(line) = next_line(&mut readers[head.run])? {
+            head.line = line;
+            heap.push(head);
+        }
+    }
+    Ok(())
+}
+
+fn next_line<R: BufRead>(reader: &mut R) -> MyResult<Option<Vec<u8>>> {
+    let mut line = vec![];
+    match reader.read_until(b'\n', &mut line).map_err(Error::TempFailed)? {
+        0 => Ok(None),
+        _ => {
+            line.pop();
+            Ok(Some(line))
+        }
+    }
+}
+
+// -- the final output, -u drops a line equal to the one before it
+struct Emitter<'a, W> {
+    config: &'a Config,
+    out: &'a mut W,
+    last: Option<Vec<u8>>,
+}
+
+impl<'a, W: Write> Emitter<'a, W> {
+    fn new(config: &'a Config, out: &'a mut W) -> Emitter<'a, W> {
+        Emitter {
+            config,
+            out,
+            last: None,
+        }
+    }
+
+    fn line(&mut self, line: &[u8]) -> MyResult<()> {
+        if self.config.unique {
+            if let Some(last) = &self.last {
+                if self.config.compare_keys(last, line) == Ordering::Equal {
+                    return Ok(());
+                }
+            }
+            let last = self.last.get_or_insert_with(Vec::new);
+            last.clear();
+            last.extend_from_slice(line);
+        }
+        self.out
+            .write_all(line)
+            .and_then(|_| self.out.write_all(b"\n"))
+            .map_err(Error::WriteFailed)
+    }
+}
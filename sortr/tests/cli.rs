@@ -0,0 +1,241 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+use std::fs;
+
+type TestResult = Result<(), Box<dyn Error>>;
+
+// expected output is GNU sort's in the C locale (see mk-outs.sh)
+const PRG: &str = "sortr";
+const EMPTY: &str = "tests/inputs/empty.txt";
+const WORDS: &str = "tests/inputs/words.txt";
+const NUMBERS: &str = "tests/inputs/numbers.txt";
+const SIZES: &str = "tests/inputs/sizes.tsv";
+const VERSIONS: &str = "tests/inputs/versions.txt";
+const PEOPLE: &str = "tests/inputs/people.txt";
+const PEOPLE_GZ: &str = "tests/inputs/people.txt.gz";
+const SCORES: &str = "tests/inputs/scores.txt";
+const NO_NEWLINE: &str = "tests/inputs/no-newline.txt";
+
+// ----------------------------------------------------------------------------
+fn run(args: &[&str], expected_file: &str) -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(args)
+        .assert()
+        .success()
+        .stdout(fs::read(expected_file)?);
+    Ok(())
+}
+
+// -- the same flags on each of empty.txt, words.txt and numbers.txt
+fn run_each(args: &[&str], suffix: &str) -> TestResult {
+    for file in [EMPTY, WORDS, NUMBERS] {
+        let name = file.rsplit('/').next().unwrap();
+        let mut all = args.to_vec();
+        all.push(file);
+        run(&all, &format!("tests/expected/{}{}.out", name, suffix))?;
+    }
+    Ok(())
+}
+
+// ----------------------------------------------------------------------------
+#[test]
+fn lines() -> TestResult {
+    run_each(&[], "")?;
+    run_each(&["-r"], ".r")?;
+    run_each(&["--ignore-leading-blanks"], ".b")
+}
+
+#[test]
+fn unique() -> TestResult {
+    run_each(&["-u"], ".u")?;
+    run_each(&["-n", "--unique"], ".n.u")
+}
+
+#[test]
+fn numeric() -> TestResult {
+    run_each(&["-n"], ".n")?;
+    run_each(&["--numeric-sort", "--reverse"], ".n.r")
+}
+
+#[test]
+fn numeric_stable() -> TestResult {
+    // 007 and 7 ... in input order, not byte order
+    run_each(&["-n", "--stable"], ".n.s")
+}
+
+#[test]
+fn human() -> TestResult {
+    run(&["-h", SIZES], "tests/expected/sizes.tsv.h.out")?;
+    run(&["--human-numeric-sort", "-r", SIZES], "tests/expected/sizes.tsv.h.r.out")
+}
+
+#[test]
+fn version() -> TestResult {
+    run(&["-V", VERSIONS], "tests/expected/versions.txt.V.out")?;
+    run(&["--version-sort", "-r", VERSIONS], "tests/expected/versions.txt.V.r.out")
+}
+
+#[test]
+fn keys_separator() -> TestResult {
+    for (args, expected) in [
+        (&["-t", ":", "-k", "3"][..], "k3"),
+        (&["-t", ":", "-k", "3,3n"][..], "k3n"),
+        (&["-t", ":", "-k", "3,3n", "-s"][..], "k3n.s"),
+        (&["-t", ":", "-k", "5,5", "-k", "3,3nr"][..], "k5.k3nr"),
+        (&["-t", ":", "-k", "4,4", "-u"][..], "k4.u"),
+        (&["-t", ":", "-k", "1.2,1.3"][..], "k1.2"),
+        (&["--field-separator", ":", "-r", "--key", "2"][..], "r.k2"),
+    ] {
+        run(
+            &[args, &[PEOPLE]].concat(),
+            &format!("tests/expected/people.txt.{}.out", expected),
+        )?;
+    }
+    Ok(())
+}
+
+#[test]
+fn keys_blanks() -> TestResult {
+    for (args, expected) in [
+        (&["-k", "2"][..], "k2"),
+        (&["-k", "2,2n", "-k", "1b,1"][..], "k2n.k1b"),
+        (&["-b", "-k", "3"][..], "b.k3"),
+        (&["-k", "1"][..], "k1"),
+        (&["-k", "1.3,1.3"][..], "k1.3"),
+    ] {
+        run(
+            &[args, &[SCORES]].concat(),
+            &format!("tests/expected/scores.txt.{}.out", expected),
+        )?;
+    }
+    Ok(())
+}
+
+#[test]
+fn all() -> TestResult {
+    run(&[WORDS, NO_NEWLINE, EMPTY], "tests/expected/all.out")
+}
+
+#[test]
+fn stdin_gz() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["-t", ":", "-k", "3"])
+        .write_stdin(fs::read(PEOPLE_GZ)?)
+        .assert()
+        .success()
+        .stdout(fs::read("tests/expected/people.txt.k3.out")?);
+    Ok(())
+}
+
+// -- 2000 lines, 4 of each key from 0 to 499 in scrambled order : big
+// enough to spill with a small -S
+fn keyed_lines() -> Vec<(usize, String)> {
+    (1..=2000)
+        .map(|i| {
+            let key = i * 389 % 500;
+            (key, format!("{} line {}\n", key, i))
+        })
+        .collect()
+}
+
+#[test]
+fn external() -> TestResult {
+    let lines = keyed_lines();
+    let input: String = lines.iter().map(|(_, line)| line.as_str()).collect();
+    // -n -k 1,1 : equal keys fall back to the whole line
+    let mut sorted = lines.clone();
+    sorted.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.cmp(&b.1)));
+    let sorted: String = sorted.iter().map(|(_, line)| line.as_str()).collect();
+    // -u -k 1,1n : the first line of each key, in input order
+    let mut unique = lines.clone();
+    unique.sort_by_key(|(key, _)| *key);
+    unique.dedup_by_key(|(key, _)| *key);
+    let unique: String = unique.iter().map(|(_, line)| line.as_str()).collect();
+
+    // a run every few lines, merged a level or two up
+    let tmp = tempfile::tempdir()?;
+    let tmp = tmp.path().to_str().unwrap();
+    for size in ["1K", "100b"] {
+        Command::cargo_bin(PRG)?
+            .args(["-n", "-k", "1,1", "-S", size, "-T", tmp])
+            .write_stdin(input.as_str())
+            .assert()
+            .success()
+            .stdout(sorted.clone());
+    }
+    Command::cargo_bin(PRG)?
+        .args(["-u", "-k", "1,1n", "-S", "200b"])
+        .write_stdin(input.as_str())
+        .assert()
+        .success()
+        .stdout(unique);
+    // the runs are unnamed, nothing is left behind
+    assert_eq!(fs::read_dir(tmp)?.count(), 0);
+    Ok(())
+}
+
+#[test]
+fn skips_bad_file() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args([WORDS, "no-such-file", NO_NEWLINE])
+        .assert()
+        .success()
+        .stderr(predicate::str::starts_with("Failed to open no-such-file"))
+        .stdout(fs::read("tests/expected/all.out")?);
+    Ok(())
+}
+
+// ----------------------------------------------------------------------------
+#[test]
+fn dies_bad_args() -> TestResult {
+    for (args, code, error) in [
+        (&["-k", "0"][..], 2, "invalid key -- 0 (fields are numbered from 1)"),
+        (&["-k", "1.0"][..], 2, "invalid key -- 1.0 (characters are numbered from 1)"),
+        (&["-k", "2n,3h"][..], 2, "invalid key -- 2n,3h (more than one of n, h, V)"),
+        (&["-k", "1x"][..], 2, "invalid key -- 1x (unknown option)"),
+        (&["-t", "ab"][..], 2, "illegal separator -- ab (must be a single byte)"),
+        (&["-S", "10X"][..], 2, "illegal buffer size -- 10X"),
+        (&["-S", "1b", "-T", "no-such-dir", WORDS][..], 4, "temp file: "),
+    ] {
+        Command::cargo_bin(PRG)?
+            .args(args)
+            .assert()
+            .code(code)
+            .stderr(predicate::str::starts_with(error));
+    }
+    Ok(())
+}
+
+#[test]
+fn dies_conflicting_orders() -> TestResult {
+    for args in [["-n", "-h"], ["-h", "-V"], ["-V", "-n"]] {
+        Command::cargo_bin(PRG)?.args(args).assert().failure();
+    }
+    Ok(())
+}
+
+#[test]
+fn broken_pipe_exits_quietly() -> TestResult {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let mut child = std::process::Command::new(assert_cmd::cargo::cargo_bin(PRG))
+        .env("SIGPIPE_STATUS", "141")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    drop(child.stdout.take());
+    let _ = child.stdin.take().unwrap().write_all(
+        keyed_lines()
+            .into_iter()
+            .map(|(_, line)| line)
+            .collect::<String>()
+            .as_bytes(),
+    );
+    let output = child.wait_with_output()?;
+    assert_eq!(output.status.code(), Some(141));
+    assert_eq!(String::from_utf8(output.stderr)?, "");
+    Ok(())
+}
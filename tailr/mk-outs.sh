@@ -0,0 +1,37 @@
+#!/usr/bin/env bash
+
+# expected output comes from GNU tail (coreutils)
+# - on macOS : `brew install coreutils` and run with TAIL=gtail
+# - the inputs are headr's
+TAIL="${TAIL:-tail}"
+
+set -u
+
+INPUTS="./tests/inputs"
+OUT_DIR="./tests/expected"
+
+[[ ! -d "$OUT_DIR" ]] && mkdir -p "$OUT_DIR"
+
+ALL="$INPUTS/empty.txt $INPUTS/one.txt $INPUTS/two.txt $INPUTS/three.txt \
+    $INPUTS/ten.txt"
+
+for FILE in $ALL; do
+  BASENAME=$(basename "$FILE")
+  $TAIL      $FILE > ${OUT_DIR}/${BASENAME}.out
+  $TAIL -n 0 $FILE > ${OUT_DIR}/${BASENAME}.n0.out
+  $TAIL -n 1 $FILE > ${OUT_DIR}/${BASENAME}.n1.out
+  $TAIL -n 3 $FILE > ${OUT_DIR}/${BASENAME}.n3.out
+  $TAIL -n +2 $FILE > ${OUT_DIR}/${BASENAME}.n+2.out
+  $TAIL -c 1 $FILE > ${OUT_DIR}/${BASENAME}.c1.out
+  $TAIL -c 4 $FILE > ${OUT_DIR}/${BASENAME}.c4.out
+  $TAIL -c +8 $FILE > ${OUT_DIR}/${BASENAME}.c+8.out
+done
+
+$TAIL      $ALL > $OUT_DIR/all.out
+$TAIL -n 1 $ALL > $OUT_DIR/all.n1.out
+$TAIL -n +3 $ALL > $OUT_DIR/all.n+3.out
+$TAIL -c 3 $ALL > $OUT_DIR/all.c3.out
+
+# compressed copies : tests expect the same output as the plain files
+zstd -qfc $INPUTS/ten.txt > $INPUTS/ten.txt.zst
+gzip -nc $INPUTS/ten.txt > $INPUTS/ten.txt.gz
@@ -0,0 +1,339 @@
+use clap::{App, Arg};
+use ior::Codec;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom, Write};
+
+mod error;
+mod ring;
+mod seek;
+
+pub use error::Error;
+
+type MyResult<T> = Result<T, Error>;
+
+// -- -n / -c : from the end, or from the start with +N (numbered from 1,
+// +0 is the same as +1)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Count {
+    Last(usize),
+    From(usize),
+}
+
+// == tail : the end of each file, headr's sibling
+// - built by get_args(), or by hand when tailr is used as a library
+// - regular files are read backwards from their end (see seek.rs), stdin,
+// pipes and compressed input all the way through, holding only the end
+// (see ring.rs)
+// - output is the input's bytes as they are, lines don't have to be text
+#[derive(Debug)]
+pub struct Config {
+    pub files: Vec<String>,
+    pub lines: Count,
+    // -c, instead of lines
+    pub bytes: Option<Count>,
+    // decode gzip, bzip2, xz and zstd input (off with --no-decompress)
+    pub decompress: bool,
+}
+
+impl Default for Config {
+    // -- tail's defaults : the last 10 lines of stdin
+    fn default() -> Config {
+        Config {
+            files: vec!["-".to_string()],
+            lines: Count::Last(10),
+            bytes: None,
+            decompress: true,
+        }
+    }
+}
+
+pub fn run(config: Config) -> MyResult<()> {
+    // buffered unless stdout is a terminal, see ior::stdout()
+    let mut out = ior::stdout();
+    run_with(&config, &mut out, &mut io::stderr().lock())?;
+    out.flush().map_err(Error::WriteFailed)
+}
+
+// -- run() with the output and the per-file errors going where the caller
+// says, e.g. into a Vec<u8>
+// - a file that fails to open is reported to `err` and skipped, the next
+// header is still the first one when nothing was printed before it
+pub fn run_with<W: Write, E: Write>(config: &Config, out: &mut W, err: &mut E) -> MyResult<()> {
+    let mut first = true;
+    for filename in &config.files {
+        match open(filename, config.decompress) {
+            // the error sink is best effort, like eprintln! without the panic
+            Err(e) => {
+                let _ = writeln!(err, "{}", e);
+            }
+            Ok(input) => {
+                if config.files.len() > 1 {
+                    headr::write_header(out, filename, first).map_err(Error::WriteFailed)?;
+                }
+                first = false;
+                match input {
+                    Opened::File(file) => tail_seekable(file, filename, config, out)?,
+                    Opened::Stream(reader) => tail(reader, filename, config, out)?,
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+// -- the end of an input that can seek : only the blocks holding the last
+// lines are read, or none at all for bytes
+// - +N lines still have to be counted from the start
+pub fn tail_seekable<R: Read + Seek, W: Write>(
+    mut input: R,
+    filename: &str,
+    config: &Config,
+    out: &mut W,
+) -> MyResult<()> {
+    let read_failed = |source| Error::ReadFailed {
+        path: filename.to_string(),
+        source,
+    };
+    let start = match (config.bytes, config.lines) {
+        (Some(Count::Last(n)), _) => seek::last_bytes(&mut input, n),
+        (Some(Count::From(n)), _) => Ok(n.saturating_sub(1) as u64),
+        (None, Count::Last(n)) => seek::last_lines(&mut input, n, seek::BLOCK),
+        (None, Count::From(_)) => return tail(BufReader::new(input), filename, config, out),
+    };
+    input
+        .seek(SeekFrom::Start(start.map_err(read_failed)?))
+        .map_err(read_failed)?;
+    copy(BufReader::new(input), filename, out)
+}
+
+// -- the end of a stream, `filename` only names it in errors
+pub fn tail<R: BufRead, W: Write>(
+    mut input: R,
+    filename: &str,
+    config: &Config,
+    out: &mut W,
+) -> MyResult<()> {
+    let read_failed = |source| Error::ReadFailed {
+        path: filename.to_string(),
+        source,
+    };
+    match (config.bytes, config.lines) {
+        (Some(Count::Last(n)), _) => {
+            let bytes = ring::last_bytes(input, n).map_err(read_failed)?;
+            let (front, back) = bytes.as_slices();
+            out.write_all(front)
+                .and_then(|_| out.write_all(back))
+                .map_err(Error::WriteFailed)
+        }
+        (Some(Count::From(n)), _) => {
+            let skip = n.saturating_sub(1) as u64;
+            io::copy(&mut input.by_ref().take(skip), &mut io::sink()).map_err(read_failed)?;
+            copy(input, filename, out)
+        }
+        (None, Count::Last(n)) => {
+            for line in ring::last_lines(input, n).map_err(read_failed)? {
+                out.write_all(&line).map_err(Error::WriteFailed)?;
+            }
+            Ok(())
+        }
+        (None, Count::From(n)) => {
+            ring::skip_lines(&mut input, n.saturating_sub(1)).map_err(read_failed)?;
+            copy(input, filename, out)
+        }
+    }
+}
+
+// -- the rest of `input`, a buffer at a time : io::copy() can't tell a
+// read error from a write error
+fn copy<R: BufRead, W: Write>(mut input: R, filename: &str, out: &mut W) -> MyResult<()> {
+    loop {
+        let buf = input.fill_buf().map_err(|source| Error::ReadFailed {
+            path: filename.to_string(),
+            source,
+        })?;
+        if buf.is_empty() {
+            return Ok(());
+        }
+        out.write_all(buf).map_err(Error::WriteFailed)?;
+        let len = buf.len();
+        input.consume(len);
+    }
+}
+
+pub fn get_args() -> MyResult<Config> {
+    let matches = App::new("tailr")
+        .version("0.1.0")
+        .author("MTON <mton@aol.com>")
+        .about("Rust tail")
+        .arg(
+            Arg::with_name("files")
+                .value_name("FILE")
+                .help("Input file(s)")
+                .multiple(true)
+                .default_value("-")
+        )
+        .arg(
+            Arg::with_name("lines")
+                .short("n")
+                .long("lines")
+                .value_name("LINES")
+                .help("Number of lines, +N to start at line N")
+                .default_value("10")
+                .allow_hyphen_values(true)
+        )
+        .arg(
+            Arg::with_name("bytes")
+                .short("c")
+                .long("bytes")
+                .value_name("BYTES")
+                .help("Number of bytes, +N to start at byte N")
+                .conflicts_with("lines")
+                .allow_hyphen_values(true)
+        )
+        .arg(
+            Arg::with_name("no_decompress")
+                .long("no-decompress")
+                .help("Pass compressed input through undecoded")
+                .takes_value(false)
+        )
+        .get_matches();
+
+    let lines = parse_count(matches.value_of("lines").unwrap())
+        .map_err(|value| Error::InvalidCount { what: "line count", value })?;
+    let bytes = matches
+        .value_of("bytes")
+        .map(parse_count)
+        .transpose()
+        .map_err(|value| Error::InvalidCount { what: "byte count", value })?;
+
+    Ok(Config {
+        files: matches.values_of_lossy("files").unwrap(),
+        lines,
+        bytes,
+        decompress: !matches.is_present("no_decompress"),
+    })
+}
+
+// -- N, -N or +N, on top of headr's positive counts : tail also takes 0,
+// which prints nothing (or everything, with +0)
+// - the offending value comes back as is, get_args() says which count
+fn parse_count(val: &str) -> Result<Count, String> {
+    let (from, digits) = match val.strip_prefix('+') {
+        Some(digits) => (true, digits),
+        None => (false, val.strip_prefix('-').unwrap_or(val)),
+    };
+    let n = match !digits.is_empty() && digits.bytes().all(|b| b == b'0') {
+        true => 0,
+        false => headr::parse_positive_int(digits).map_err(|_| val.to_string())?,
+    };
+    Ok(match from {
+        true => Count::From(n),
+        false => Count::Last(n),
+    })
+}
+
+// -- an opened input : a regular file tailr can seek in, or a stream
+enum Opened {
+    File(File),
+    Stream(Box<dyn BufRead>),
+}
+
+// -- stdin for "-", else the file, decoded on the fly when compressed
+// - not ior::open_input() : that maps a regular file whole, tailr only
+// wants its last blocks
+fn open(filename: &str, decompress: bool) -> MyResult<Opened> {
+    let open_failed = |source| Error::OpenFailed {
+        path: filename.to_string(),
+        source,
+    };
+    if filename == "-" {
+        return ior::open(filename, decompress)
+            .map(Opened::Stream)
+            .map_err(open_failed);
+    }
+    let file = File::open(filename).map_err(open_failed)?;
+    let regular = file.metadata().map_err(open_failed)?.is_file();
+    if regular && !(decompress && is_compressed(&file).map_err(open_failed)?) {
+        return Ok(Opened::File(file));
+    }
+    let reader: Box<dyn BufRead> = Box::new(BufReader::new(file));
+    match decompress {
+        true => ior::decode(reader).map(Opened::Stream).map_err(open_failed),
+        false => Ok(Opened::Stream(reader)),
+    }
+}
+
+// -- a peek at the first bytes of a regular file, rewound after
+fn is_compressed(mut file: &File) -> io::Result<bool> {
+    let mut head = Vec::with_capacity(6);
+    file.take(6).read_to_end(&mut head)?;
+    file.rewind()?;
+    Ok(Codec::detect(&head).is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_count, run_with, tail, tail_seekable, Config, Count};
+    use std::io::Cursor;
+
+    const TEXT: &str = "one\ntwo\nthree\nfour";
+
+    // -- the same tail whether the input can seek or not
+    fn tail_of(config: Config) -> String {
+        let (mut seeked, mut streamed) = (vec![], vec![]);
+        tail_seekable(Cursor::new(TEXT), "-", &config, &mut seeked).unwrap();
+        tail(TEXT.as_bytes(), "-", &config, &mut streamed).unwrap();
+        assert_eq!(seeked, streamed);
+        String::from_utf8(seeked).unwrap()
+    }
+
+    #[test]
+    fn test_tail() {
+        let lines = |lines| Config { lines, ..Config::default() };
+        let bytes = |bytes| Config { bytes: Some(bytes), ..Config::default() };
+        assert_eq!(tail_of(Config::default()), TEXT);
+        assert_eq!(tail_of(lines(Count::Last(2))), "three\nfour");
+        assert_eq!(tail_of(lines(Count::Last(0))), "");
+        assert_eq!(tail_of(lines(Count::From(3))), "three\nfour");
+        assert_eq!(tail_of(lines(Count::From(0))), TEXT);
+        assert_eq!(tail_of(lines(Count::From(9))), "");
+        assert_eq!(tail_of(bytes(Count::Last(6))), "e\nfour");
+        assert_eq!(tail_of(bytes(Count::From(15))), "four");
+        assert_eq!(tail_of(bytes(Count::From(99))), "");
+    }
+
+    #[test]
+    fn test_parse_count() {
+        assert_eq!(parse_count("3"), Ok(Count::Last(3)));
+        assert_eq!(parse_count("-3"), Ok(Count::Last(3)));
+        assert_eq!(parse_count("+3"), Ok(Count::From(3)));
+        assert_eq!(parse_count("0"), Ok(Count::Last(0)));
+        assert_eq!(parse_count("+00"), Ok(Count::From(0)));
+        for bad in ["", "+", "-", "x", "+-3", "3k"] {
+            assert_eq!(parse_count(bad), Err(bad.to_string()));
+        }
+    }
+
+    #[test]
+    fn test_run_with() {
+        // the first header printed has no blank line above it, even when
+        // the file before it is missing
+        let config = Config {
+            files: vec![
+                "no-such-file".to_string(),
+                "tests/inputs/two.txt".to_string(),
+                "tests/inputs/one.txt".to_string(),
+            ],
+            lines: Count::Last(1),
+            ..Config::default()
+        };
+        let (mut out, mut err) = (vec![], vec![]);
+        run_with(&config, &mut out, &mut err).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "==> tests/inputs/two.txt <==\nFour words.\n\n\
+             ==> tests/inputs/one.txt <==\nÖne line, four words.\n"
+        );
+        assert!(String::from_utf8(err).unwrap().starts_with("Failed to open no-such-file"));
+    }
+}
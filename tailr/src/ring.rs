@@ -0,0 +1,98 @@
+use std::collections::VecDeque;
+use std::io::{self, BufRead};
+use std::mem;
+
+// == the end of a stream : stdin, a pipe, decoded compressed input
+// - there's no seeking back, so everything is read and only the last
+// lines or bytes are held on the way : memory is bounded by the count,
+// not by the input
+
+// -- at most `n` lines, each with its newline (the last one may have
+// none), oldest first
+pub fn last_lines<R: BufRead>(mut input: R, n: usize) -> io::Result<VecDeque<Vec<u8>>> {
+    let mut lines = VecDeque::new();
+    if n == 0 {
+        return Ok(lines);
+    }
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        if input.read_until(b'\n', &mut line)? == 0 {
+            return Ok(lines);
+        }
+        // once the ring is full the oldest line's buffer is reused
+        let spare = match lines.len() == n {
+            true => lines.pop_front().unwrap_or_default(),
+            false => Vec::new(),
+        };
+        lines.push_back(mem::replace(&mut line, spare));
+    }
+}
+
+// -- at most `n` bytes, a buffer at a time
+pub fn last_bytes<R: BufRead>(mut input: R, n: usize) -> io::Result<VecDeque<u8>> {
+    let mut bytes = VecDeque::new();
+    loop {
+        let buf = input.fill_buf()?;
+        if buf.is_empty() {
+            return Ok(bytes);
+        }
+        let len = buf.len();
+        let keep = &buf[len.saturating_sub(n)..];
+        let over = (bytes.len() + keep.len()).saturating_sub(n);
+        bytes.drain(..over);
+        bytes.extend(keep);
+        input.consume(len);
+    }
+}
+
+// -- past the first `n` lines, for +N
+pub fn skip_lines<R: BufRead>(mut input: R, n: usize) -> io::Result<()> {
+    let mut line = Vec::new();
+    for _ in 0..n {
+        line.clear();
+        if input.read_until(b'\n', &mut line)? == 0 {
+            break;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{last_bytes, last_lines, skip_lines};
+    use std::io::{BufRead, BufReader};
+
+    #[test]
+    fn test_last_lines() {
+        let lines = |text: &str, n| -> Vec<String> {
+            let ring = last_lines(text.as_bytes(), n).unwrap();
+            ring.into_iter().map(|line| String::from_utf8(line).unwrap()).collect()
+        };
+        assert_eq!(lines("a\nb\nc\n", 2), ["b\n", "c\n"]);
+        assert_eq!(lines("a\nb\nc", 2), ["b\n", "c"]);
+        assert_eq!(lines("a\n", 5), ["a\n"]);
+        assert!(lines("a\nb\n", 0).is_empty());
+    }
+
+    #[test]
+    fn test_last_bytes() {
+        // buffers smaller and bigger than the count
+        for capacity in [1, 3, 64] {
+            let bytes = |text: &str, n| -> Vec<u8> {
+                let input = BufReader::with_capacity(capacity, text.as_bytes());
+                last_bytes(input, n).unwrap().into()
+            };
+            assert_eq!(bytes("abcdefg", 4), b"defg");
+            assert_eq!(bytes("ab", 4), b"ab");
+            assert_eq!(bytes("abc", 0), b"");
+        }
+    }
+
+    #[test]
+    fn test_skip_lines() {
+        let mut input = "a\nb\nc\n".as_bytes();
+        skip_lines(&mut input, 2).unwrap();
+        assert_eq!(input.fill_buf().unwrap(), b"c\n");
+    }
+}
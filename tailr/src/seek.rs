@@ -0,0 +1,79 @@
+use std::io::{self, Read, Seek, SeekFrom};
+
+// == the end of a regular file, found by reading it backwards
+// - blocks are read from the end towards the start until enough newlines
+// have gone by : a 10 line tail of a 10 GB log reads one block, not 10 GB
+// - only the offset is worked out here, the caller seeks there and copies
+// the rest
+
+// bytes read per step back
+pub const BLOCK: usize = 64 * 1024;
+
+// -- where the last `n` lines start
+// - the file's last newline ends its last line, it doesn't start another :
+// "a\nb\n" has 2 lines, so does "a\nb"
+pub fn last_lines<R: Read + Seek>(input: &mut R, n: usize, block: usize) -> io::Result<u64> {
+    let len = input.seek(SeekFrom::End(0))?;
+    if n == 0 {
+        return Ok(len);
+    }
+    let mut buf = vec![0; block];
+    let mut end = len;
+    let mut found = 0;
+    while end > 0 {
+        let start = end.saturating_sub(block as u64);
+        let chunk = &mut buf[..(end - start) as usize];
+        input.seek(SeekFrom::Start(start))?;
+        input.read_exact(chunk)?;
+        let mut upto = chunk.len();
+        if end == len && chunk.last() == Some(&b'\n') {
+            upto -= 1;
+        }
+        while let Some(at) = chunk[..upto].iter().rposition(|&b| b == b'\n') {
+            found += 1;
+            if found == n {
+                return Ok(start + at as u64 + 1);
+            }
+            upto = at;
+        }
+        end = start;
+    }
+    Ok(0)
+}
+
+// -- where the last `n` bytes start, nothing to read for those
+pub fn last_bytes<R: Seek>(input: &mut R, n: usize) -> io::Result<u64> {
+    let len = input.seek(SeekFrom::End(0))?;
+    Ok(len.saturating_sub(n as u64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{last_bytes, last_lines};
+    use std::io::Cursor;
+
+    #[test]
+    fn test_last_lines() {
+        let text = "one\ntwo\nthree\nfour\n";
+        // blocks smaller than a line, a newline at a block's edge, and one
+        // block for the whole text
+        for block in [1, 2, 3, 4, 5, 64] {
+            let start = |text: &str, n| last_lines(&mut Cursor::new(text), n, block).unwrap();
+            assert_eq!(start(text, 0), 19, "{}", block);
+            assert_eq!(start(text, 1), 14, "{}", block);
+            assert_eq!(start(text, 2), 8, "{}", block);
+            assert_eq!(start(text, 4), 0, "{}", block);
+            assert_eq!(start(text, 10), 0, "{}", block);
+            // no newline at the end
+            assert_eq!(start("one\ntwo", 1), 4, "{}", block);
+            assert_eq!(start("\n\n", 1), 1, "{}", block);
+            assert_eq!(start("", 3), 0, "{}", block);
+        }
+    }
+
+    #[test]
+    fn test_last_bytes() {
+        assert_eq!(last_bytes(&mut Cursor::new("abcdef"), 2).unwrap(), 4);
+        assert_eq!(last_bytes(&mut Cursor::new("abc"), 5).unwrap(), 0);
+    }
+}
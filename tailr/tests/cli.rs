@@ -0,0 +1,194 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+use std::fs;
+use std::io::Write;
+
+type TestResult = Result<(), Box<dyn Error>>;
+
+// expected output is GNU tail's (see mk-outs.sh)
+const PRG: &str = "tailr";
+const EMPTY: &str = "./tests/inputs/empty.txt";
+const ONE: &str = "./tests/inputs/one.txt";
+const TWO: &str = "./tests/inputs/two.txt";
+const THREE: &str = "./tests/inputs/three.txt";
+const TEN: &str = "./tests/inputs/ten.txt";
+// compressed copies : zstd, gzip
+const TEN_ZST: &str = "./tests/inputs/ten.txt.zst";
+const TEN_GZ: &str = "./tests/inputs/ten.txt.gz";
+
+// ----------------------------------------------------------------------------
+fn run(args: &[&str], expected_file: &str) -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(args)
+        .assert()
+        .success()
+        .stdout(fs::read(expected_file)?);
+    Ok(())
+}
+
+// -- the same output from stdin, where nothing can seek
+fn run_stdin(args: &[&str], input_file: &str, expected_file: &str) -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(args)
+        .write_stdin(fs::read(input_file)?)
+        .assert()
+        .success()
+        .stdout(fs::read(expected_file)?);
+    Ok(())
+}
+
+// -- every count mk-outs.sh makes, from the file and from stdin
+fn run_counts(file: &str) -> TestResult {
+    let name = file.rsplit('/').next().unwrap();
+    for (flags, suffix) in [
+        (&[][..], ""),
+        (&["-n", "0"], ".n0"),
+        (&["-n", "1"], ".n1"),
+        (&["-n", "3"], ".n3"),
+        (&["-n", "+2"], ".n+2"),
+        (&["-c", "1"], ".c1"),
+        (&["-c", "4"], ".c4"),
+        (&["-c", "+8"], ".c+8"),
+    ] {
+        let expected = format!("tests/expected/{}{}.out", name, suffix);
+        run(&[flags, &[file]].concat(), &expected)?;
+        run_stdin(flags, file, &expected)?;
+    }
+    Ok(())
+}
+
+// ----------------------------------------------------------------------------
+#[test]
+fn empty() -> TestResult {
+    run_counts(EMPTY)
+}
+
+#[test]
+fn one() -> TestResult {
+    run_counts(ONE)
+}
+
+#[test]
+fn two() -> TestResult {
+    run_counts(TWO)
+}
+
+#[test]
+fn three() -> TestResult {
+    run_counts(THREE)
+}
+
+#[test]
+fn ten() -> TestResult {
+    run_counts(TEN)
+}
+
+#[test]
+fn negative_count() -> TestResult {
+    // -n -3 is -n 3, like tail
+    run(&["-n", "-3", TEN], "tests/expected/ten.txt.n3.out")
+}
+
+// -- `seq 15000` without its last newline : more than one block back from
+// the end (see seek.rs, whose own tests use blocks of a few bytes)
+fn long() -> String {
+    let lines: Vec<String> = (1..=15000).map(|i| i.to_string()).collect();
+    lines.join("\n")
+}
+
+// ----------------------------------------------------------------------------
+#[test]
+fn long_file() -> TestResult {
+    let text = long();
+    let expected = &text[text.find("\n1001\n").unwrap() + 1..];
+    let mut file = tempfile::NamedTempFile::new()?;
+    file.write_all(text.as_bytes())?;
+    Command::cargo_bin(PRG)?
+        .args(["-n", "14000"])
+        .arg(file.path())
+        .assert()
+        .success()
+        .stdout(expected.to_string());
+    Command::cargo_bin(PRG)?
+        .args(["-n", "14000"])
+        .write_stdin(text.as_bytes())
+        .assert()
+        .success()
+        .stdout(expected.to_string());
+    Ok(())
+}
+
+#[test]
+fn compressed() -> TestResult {
+    run(&["-n", "3", TEN_GZ], "tests/expected/ten.txt.n3.out")?;
+    run(&["-c", "4", TEN_ZST], "tests/expected/ten.txt.c4.out")?;
+    run_stdin(&["-n", "+2"], TEN_ZST, "tests/expected/ten.txt.n+2.out")
+}
+
+#[test]
+fn no_decompress() -> TestResult {
+    let raw = fs::read(TEN_ZST)?;
+    Command::cargo_bin(PRG)?
+        .args(["--no-decompress", "-c", "+1", TEN_ZST])
+        .assert()
+        .success()
+        .stdout(raw);
+    Ok(())
+}
+
+// ----------------------------------------------------------------------------
+#[test]
+fn multiple_files() -> TestResult {
+    let all = [EMPTY, ONE, TWO, THREE, TEN];
+    run(&all, "tests/expected/all.out")?;
+    run(&[&["-n", "1"][..], &all].concat(), "tests/expected/all.n1.out")?;
+    run(&[&["-n", "+3"][..], &all].concat(), "tests/expected/all.n+3.out")?;
+    run(&[&["-c", "3"][..], &all].concat(), "tests/expected/all.c3.out")
+}
+
+#[test]
+fn skips_bad_file() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args([ONE, "no-such-file", TWO])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Failed to open no-such-file"))
+        .stdout(predicate::str::contains("==> ./tests/inputs/two.txt <=="));
+    Ok(())
+}
+
+#[test]
+fn dies_bad_counts() -> TestResult {
+    for (args, message, code) in [
+        (&["-n", "x"][..], "illegal line count -- x", 2),
+        (&["-c", "+4k"], "illegal byte count -- +4k", 2),
+        (&["-n", "1", "-c", "1"], "cannot be used with", 1),
+    ] {
+        Command::cargo_bin(PRG)?
+            .args(args)
+            .assert()
+            .code(code)
+            .stderr(predicate::str::contains(message));
+    }
+    Ok(())
+}
+
+#[test]
+fn broken_pipe_exits_quietly() -> TestResult {
+    use std::process::Stdio;
+
+    let mut child = std::process::Command::new(assert_cmd::cargo::cargo_bin(PRG))
+        .args(["-n", "+1"])
+        .env("SIGPIPE_STATUS", "141")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    drop(child.stdout.take());
+    let _ = child.stdin.take().unwrap().write_all(long().repeat(20).as_bytes());
+    let output = child.wait_with_output()?;
+    assert_eq!(output.status.code(), Some(141));
+    assert_eq!(String::from_utf8(output.stderr)?, "");
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use ior::Input;
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Write};
+use std::ops::Deref;
+use std::path::PathBuf;
+
+// -- reading a large regular file vs mapping it, each function fed both
+// ways so the difference is only where the bytes come from
+// - count : the line by line count() every input goes through without the
+// mapping, over a BufReader and over the mapped slice
+// - count_mapped : `wcr -l` / `wcr -c` on a regular file, over the file
+// read into memory and over the mapping
+// run with : cargo bench --bench count
+
+const SIZE_MIB: usize = 256;
+
+// -- ~SIZE_MIB of text lines, written once per run to the temp dir
+fn big_file() -> PathBuf {
+    let path = std::env::temp_dir().join(format!("wcr-bench-{}MiB.txt", SIZE_MIB));
+    if fs::metadata(&path)
+        .map(|m| m.len() as usize >= SIZE_MIB << 20)
+        .unwrap_or(false)
+    {
+        return path;
+    }
+    let mut out = BufWriter::new(File::create(&path).unwrap());
+    let line = "The quick brown fox jumps over the lazy dog, again and again.\n";
+    for _ in 0..(SIZE_MIB << 20) / line.len() + 1 {
+        out.write_all(line.as_bytes()).unwrap();
+    }
+    out.flush().unwrap();
+    path
+}
+
+// -- the mapping ior::open_input() hands out for a regular file
+fn mapped(filename: &str) -> impl Deref<Target = [u8]> {
+    match ior::open_input(filename, false).unwrap() {
+        Input::Mapped(map) => map,
+        Input::Stream(_) => panic!("{} was not mapped", filename),
+    }
+}
+
+fn bench_count(c: &mut Criterion) {
+    let path = big_file();
+    let filename = path.to_str().unwrap();
+    let size = fs::metadata(&path).unwrap().len();
+
+    let mut group = c.benchmark_group("count");
+    group.throughput(Throughput::Bytes(size));
+    group.sample_size(10);
+    group.bench_function("buffered", |b| {
+        b.iter(|| wcr::count(BufReader::new(File::open(filename).unwrap())).unwrap())
+    });
+    group.bench_function("mapped", |b| b.iter(|| wcr::count(&mapped(filename)[..]).unwrap()));
+    group.finish();
+
+    let mut group = c.benchmark_group("count_mapped");
+    group.throughput(Throughput::Bytes(size));
+    group.sample_size(10);
+    group.bench_function("read", |b| b.iter(|| wcr::count_mapped(&fs::read(filename).unwrap())));
+    group.bench_function("mapped", |b| b.iter(|| wcr::count_mapped(&mapped(filename))));
+    group.finish();
+}
+
+criterion_group!(benches, bench_count);
+criterion_main!(benches);
@@ -1,5 +1,11 @@
 #!/usr/bin/env bash
 
+# column widths follow GNU wc (coreutils), not BSD wc's fixed 8 columns
+# - on macOS : `brew install coreutils` and run with WC=gwc
+# - a UTF-8 locale so -m and -w count multibyte chars like wcr does
+WC="${WC:-wc}"
+export LC_ALL=C.UTF-8
+
 ROOT="tests/inputs"
 FILES="$ROOT/empty.txt $ROOT/fox.txt $ROOT/atlamal.txt"
 OUT_DIR="tests/expected"
@@ -8,29 +14,59 @@ OUT_DIR="tests/expected"
 
 for FILE in $FILES; do
   BASENAME=$(basename "$FILE")
-  wc $FILE > ${OUT_DIR}/${BASENAME}.out
-  wc -l   $FILE > ${OUT_DIR}/${BASENAME}.l.out
-  wc -w   $FILE > ${OUT_DIR}/${BASENAME}.w.out
-  wc -c   $FILE > ${OUT_DIR}/${BASENAME}.c.out
-  wc -m   $FILE > ${OUT_DIR}/${BASENAME}.m.out
-  wc -lwm $FILE > ${OUT_DIR}/${BASENAME}.lwm.out
-  wc -wc  $FILE > ${OUT_DIR}/${BASENAME}.wc.out
-  wc -wm  $FILE > ${OUT_DIR}/${BASENAME}.wm.out
-  wc -wl  $FILE > ${OUT_DIR}/${BASENAME}.wl.out
-  wc -cl  $FILE > ${OUT_DIR}/${BASENAME}.cl.out
-  wc -ml  $FILE > ${OUT_DIR}/${BASENAME}.ml.out
+  $WC $FILE > ${OUT_DIR}/${BASENAME}.out
+  $WC -l   $FILE > ${OUT_DIR}/${BASENAME}.l.out
+  $WC -w   $FILE > ${OUT_DIR}/${BASENAME}.w.out
+  $WC -c   $FILE > ${OUT_DIR}/${BASENAME}.c.out
+  $WC -m   $FILE > ${OUT_DIR}/${BASENAME}.m.out
+  $WC -lwm $FILE > ${OUT_DIR}/${BASENAME}.lwm.out
+  $WC -wc  $FILE > ${OUT_DIR}/${BASENAME}.wc.out
+  $WC -wm  $FILE > ${OUT_DIR}/${BASENAME}.wm.out
+  $WC -wl  $FILE > ${OUT_DIR}/${BASENAME}.wl.out
+  $WC -cl  $FILE > ${OUT_DIR}/${BASENAME}.cl.out
+  $WC -ml  $FILE > ${OUT_DIR}/${BASENAME}.ml.out
 done
 
-wc < "$ROOT/atlamal.txt" > "$OUT_DIR/atlamal.txt.stdin.out"
-
-wc      $FILES > $OUT_DIR/all.out
-wc -l   $FILES > $OUT_DIR/all.l.out
-wc -w   $FILES > $OUT_DIR/all.w.out
-wc -c   $FILES > $OUT_DIR/all.c.out
-wc -m   $FILES > $OUT_DIR/all.m.out
-wc -lwm $FILES > $OUT_DIR/all.lwm.out
-wc -wc  $FILES > $OUT_DIR/all.wc.out
-wc -wm  $FILES > $OUT_DIR/all.wm.out
-wc -wl  $FILES > $OUT_DIR/all.wl.out
-wc -cl  $FILES > $OUT_DIR/all.cl.out
-wc -ml  $FILES > $OUT_DIR/all.ml.out
+# Latin-1 and cut off UTF-8 : -m counts only the bytes that make valid
+# chars, -l and -c every byte
+printf 'Caf\xe9 cr\xe8me br\xfbl\xe9e\n\xff\xfe\nna\xc3\xafve \xe2\x82\n' > "$ROOT/latin1.txt"
+$WC -m  "$ROOT/latin1.txt" > "$OUT_DIR/latin1.txt.m.out"
+$WC -ml "$ROOT/latin1.txt" > "$OUT_DIR/latin1.txt.ml.out"
+$WC -cl "$ROOT/latin1.txt" > "$OUT_DIR/latin1.txt.cl.out"
+
+# piped, not redirected : a pipe has no size so GNU wc pads to 7 columns
+cat "$ROOT/atlamal.txt" | $WC > "$OUT_DIR/atlamal.txt.stdin.out"
+
+$WC      $FILES > $OUT_DIR/all.out
+$WC -l   $FILES > $OUT_DIR/all.l.out
+$WC -w   $FILES > $OUT_DIR/all.w.out
+$WC -c   $FILES > $OUT_DIR/all.c.out
+$WC -m   $FILES > $OUT_DIR/all.m.out
+$WC -lwm $FILES > $OUT_DIR/all.lwm.out
+$WC -wc  $FILES > $OUT_DIR/all.wc.out
+$WC -wm  $FILES > $OUT_DIR/all.wm.out
+$WC -wl  $FILES > $OUT_DIR/all.wl.out
+$WC -cl  $FILES > $OUT_DIR/all.cl.out
+$WC -ml  $FILES > $OUT_DIR/all.ml.out
+
+# the same files as a NUL separated list, for --files0-from
+printf '%s\0' $FILES > $ROOT/all.files0
+
+# [recursive] wc has no directory walk or subtotals, so these are hand
+# checked against `find $TREE -type f | sort | xargs wc` and kept in git :
+# - tree.r.out, tree.r.include.out, tree.rl.gitignore.out
+#
+# [code-stats] no wc equivalent either, hand checked line by line :
+# - comments.code-stats.out, tree.code-stats.out
+#
+# [freq] roughly `tr -s " " "\n" | sort | uniq -c | sort -rn`, hand checked :
+# - all.freq.words.out
+#
+# [total] --total is missing from older coreutils, these are kept in git :
+# - all.total-only.out, all.l.total-only.out, all.total-never.out,
+#   fox.txt.l.total-always.out
+
+# [compressed] wc doesn't decompress, counts come from `zcat | wc` :
+# - atlamal.txt.gz.out (widths from the decoded byte count)
+gzip -nc "$ROOT/atlamal.txt" > "$ROOT/atlamal.txt.gz"
+$WC -c "$ROOT/atlamal.txt.gz" > "$OUT_DIR/atlamal.txt.gz.c.no-decompress.out"
@@ -0,0 +1,60 @@
+use std::io;
+use thiserror::Error;
+
+// == everything wcr can fail with
+// - bad arguments stop wcr before any output, with their own exit status
+// (see exit_code())
+// - an input that can't be opened or read is reported and left out of the
+// totals, the other inputs are still counted
+// - invalid UTF-8 is not an error : it counts as one char per bad sequence
+#[derive(Debug, Error)]
+pub enum Error {
+    // --top / --min-length that isn't a valid number
+    #[error("illegal {what} -- {value}")]
+    InvalidCount { what: &'static str, value: String },
+
+    #[error("invalid word mode -- {0}")]
+    InvalidWordMode(String),
+
+    #[error("invalid word regex -- {0}")]
+    InvalidRegex(#[source] regex::Error),
+
+    #[error("invalid glob -- {0}")]
+    InvalidGlob(#[source] globset::Error),
+
+    #[error("[{path}]: --> [error] {source}")]
+    OpenFailed { path: String, source: io::Error },
+
+    // --code-stats on stdin, or on a file whose extension isn't known
+    #[error("[{path}]: --> [error] no known language for --code-stats")]
+    UnknownLanguage { path: String },
+
+    #[error("[{path}]: --> [error] {source}")]
+    ReadFailed { path: String, source: io::Error },
+
+    // stdout, or the sink given to run_with()
+    #[error("write error: {0}")]
+    WriteFailed(#[source] io::Error),
+}
+
+impl ior::Failure for Error {
+    fn exit_code(&self) -> i32 {
+        match self {
+            Error::InvalidCount { .. }
+            | Error::InvalidWordMode(_)
+            | Error::InvalidRegex(_)
+            | Error::InvalidGlob(_) => 2,
+            Error::OpenFailed { .. } | Error::UnknownLanguage { .. } => 3,
+            Error::ReadFailed { .. } => 4,
+            // 5 is headr and catr's invalid UTF-8
+            Error::WriteFailed(_) => 6,
+        }
+    }
+
+    fn write_error(&self) -> Option<&io::Error> {
+        match self {
+            Error::WriteFailed(e) => Some(e),
+            _ => None,
+        }
+    }
+}
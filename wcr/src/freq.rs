@@ -0,0 +1,128 @@
+use crate::WordMode;
+use std::collections::HashMap;
+
+// -- what --freq tallies
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FreqKind {
+    Words,
+    Chars,
+    Lines,
+}
+
+// -- --freq options as parsed from the command line
+#[derive(Debug, Clone)]
+pub struct FreqConfig {
+    pub kind: FreqKind,
+    pub top: usize,
+    pub ignore_case: bool,
+    // items shorter than this (in chars) are not tallied
+    pub min_length: usize,
+}
+
+// -- item -> occurrences, merged across every file counted
+#[derive(Debug)]
+pub struct Histogram {
+    config: FreqConfig,
+    counts: HashMap<String, usize>,
+}
+
+impl FreqKind {
+    pub fn parse(val: &str) -> Option<FreqKind> {
+        match val {
+            "words" => Some(FreqKind::Words),
+            "chars" => Some(FreqKind::Chars),
+            "lines" => Some(FreqKind::Lines),
+            _ => None,
+        }
+    }
+}
+
+impl Histogram {
+    pub fn new(config: &FreqConfig) -> Histogram {
+        Histogram {
+            config: config.clone(),
+            counts: HashMap::new(),
+        }
+    }
+
+    // -- tally one line as read by count_with(), newline included
+    // - words are split with the same WordMode used for -w
+    // - chars skips whitespace, lines drops the line ending
+    pub fn add_line(&mut self, line: &str, word_mode: &WordMode) {
+        match self.config.kind {
+            FreqKind::Words => {
+                for word in word_mode.words(line) {
+                    self.add(word);
+                }
+            }
+            FreqKind::Chars => {
+                let mut buf = [0; 4];
+                for c in line.chars().filter(|c| !c.is_whitespace()) {
+                    self.add(c.encode_utf8(&mut buf));
+                }
+            }
+            FreqKind::Lines => self.add(line.trim_end_matches(['\n', '\r'])),
+        }
+    }
+
+    fn add(&mut self, item: &str) {
+        if item.chars().count() < self.config.min_length {
+            return;
+        }
+        let item = match self.config.ignore_case {
+            true => item.to_lowercase(),
+            false => item.to_string(),
+        };
+        *self.counts.entry(item).or_insert(0) += 1;
+    }
+
+    // -- most frequent first, ties broken alphabetically so output is stable
+    pub fn top(&self) -> Vec<(&str, usize)> {
+        let mut items: Vec<(&str, usize)> = self
+            .counts
+            .iter()
+            .map(|(item, count)| (item.as_str(), *count))
+            .collect();
+        items.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        items.truncate(self.config.top);
+        items
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FreqConfig, FreqKind, Histogram};
+    use crate::WordMode;
+
+    fn histogram(kind: FreqKind, ignore_case: bool, min_length: usize) -> Histogram {
+        Histogram::new(&FreqConfig {
+            kind,
+            top: 3,
+            ignore_case,
+            min_length,
+        })
+    }
+
+    #[test]
+    fn test_top_words() {
+        let mut hist = histogram(FreqKind::Words, true, 2);
+        for line in ["The cat and the hat\n", "THE a cat\n"] {
+            hist.add_line(line, &WordMode::Whitespace);
+        }
+        // `a` is below min_length, `and` / `hat` tie and sort by name
+        assert_eq!(hist.top(), vec![("the", 3), ("cat", 2), ("and", 1)]);
+    }
+
+    #[test]
+    fn test_top_chars_and_lines() {
+        let mut hist = histogram(FreqKind::Chars, false, 0);
+        hist.add_line("aba b\n", &WordMode::Whitespace);
+        assert_eq!(hist.top(), vec![("a", 2), ("b", 2)]);
+
+        let mut hist = histogram(FreqKind::Lines, false, 0);
+        for line in ["x\n", "y\r\n", "x"] {
+            hist.add_line(line, &WordMode::Whitespace);
+        }
+        assert_eq!(hist.top(), vec![("x", 2), ("y", 1)]);
+    }
+}
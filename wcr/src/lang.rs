@@ -0,0 +1,211 @@
+use std::path::Path;
+
+// -- languages known to --code-stats, detected from the file extension
+// - Ord so totals print in a stable order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Language {
+    C,
+    Markdown,
+    Python,
+    Rust,
+    Shell,
+    Toml,
+}
+
+// -- what a single line turned out to hold
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineKind {
+    Code,
+    Comment,
+    Blank,
+}
+
+// -- comment markers for one language
+// - line : comment runs to the end of the line
+// - block : (open, close) pair, may span lines
+struct Syntax {
+    line: &'static [&'static str],
+    block: Option<(&'static str, &'static str)>,
+}
+
+impl Language {
+    pub fn from_path(path: &Path) -> Option<Language> {
+        let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+        match ext.as_str() {
+            "c" | "h" => Some(Language::C),
+            "md" | "markdown" => Some(Language::Markdown),
+            "py" => Some(Language::Python),
+            "rs" => Some(Language::Rust),
+            "sh" | "bash" | "zsh" => Some(Language::Shell),
+            "toml" => Some(Language::Toml),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Language::C => "C",
+            Language::Markdown => "Markdown",
+            Language::Python => "Python",
+            Language::Rust => "Rust",
+            Language::Shell => "Shell",
+            Language::Toml => "TOML",
+        }
+    }
+
+    fn syntax(&self) -> Syntax {
+        match self {
+            Language::C | Language::Rust => Syntax {
+                line: &["//"],
+                block: Some(("/*", "*/")),
+            },
+            Language::Python | Language::Shell | Language::Toml => Syntax {
+                line: &["#"],
+                block: None,
+            },
+            Language::Markdown => Syntax {
+                line: &[],
+                block: Some(("<!--", "-->")),
+            },
+        }
+    }
+
+    // -- classify one line, `in_block` carries an open block comment from
+    // the previous line to the next
+    // - a line holding ANY code outside of comments counts as code
+    // - comment markers inside string literals are not special cased
+    pub fn classify(&self, line: &str, in_block: &mut bool) -> LineKind {
+        let syntax = self.syntax();
+        let mut rest = line.trim();
+        if rest.is_empty() && !*in_block {
+            return LineKind::Blank;
+        }
+
+        let mut has_code = false;
+        loop {
+            if *in_block {
+                // block comment is open : skip to its close, if on this line
+                let (_, close) = syntax.block.unwrap_or_default();
+                match rest.find(close) {
+                    Some(i) => {
+                        rest = rest[i + close.len()..].trim_start();
+                        *in_block = false;
+                    }
+                    None => break,
+                }
+            }
+            if rest.is_empty() || syntax.line.iter().any(|m| rest.starts_with(m)) {
+                break;
+            }
+            match syntax.block {
+                Some((open, _)) if rest.starts_with(open) => {
+                    rest = &rest[open.len()..];
+                    *in_block = true;
+                }
+                Some((open, _)) => {
+                    // code up to the next comment, if any
+                    has_code = true;
+                    match next_comment(rest, open, syntax.line) {
+                        Some((i, true)) => {
+                            rest = &rest[i + open.len()..];
+                            *in_block = true;
+                        }
+                        _ => break,
+                    }
+                }
+                None => {
+                    has_code = true;
+                    break;
+                }
+            }
+        }
+
+        if has_code {
+            LineKind::Code
+        } else {
+            LineKind::Comment
+        }
+    }
+}
+
+// -- earliest comment marker in `text`, true when it opens a block
+fn next_comment(text: &str, open: &str, line: &[&str]) -> Option<(usize, bool)> {
+    let block = text.find(open).map(|i| (i, true));
+    let line = line
+        .iter()
+        .filter_map(|m| text.find(m))
+        .min()
+        .map(|i| (i, false));
+    match (block, line) {
+        (Some(b), Some(l)) => Some(if l.0 < b.0 { l } else { b }),
+        (b, l) => b.or(l),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Language, LineKind};
+    use std::path::Path;
+
+    fn classify_all(lang: Language, lines: &[&str]) -> Vec<LineKind> {
+        let mut in_block = false;
+        lines
+            .iter()
+            .map(|line| lang.classify(line, &mut in_block))
+            .collect()
+    }
+
+    #[test]
+    fn test_from_path() {
+        assert_eq!(
+            Language::from_path(Path::new("src/lib.rs")),
+            Some(Language::Rust)
+        );
+        assert_eq!(
+            Language::from_path(Path::new("mk-outs.sh")),
+            Some(Language::Shell)
+        );
+        assert_eq!(
+            Language::from_path(Path::new("README.MD")),
+            Some(Language::Markdown)
+        );
+        assert_eq!(Language::from_path(Path::new("notes.txt")), None);
+        assert_eq!(Language::from_path(Path::new("Makefile")), None);
+    }
+
+    #[test]
+    fn test_classify_rust() {
+        use LineKind::*;
+        let lines = [
+            "// line comment",
+            "fn main() { // trailing",
+            "",
+            "    /* block",
+            "       still block */",
+            "    let x = 1; /* inline */",
+            "    /* open */ let y = 2;",
+            "}",
+        ];
+        assert_eq!(
+            classify_all(Language::Rust, &lines),
+            vec![Comment, Code, Blank, Comment, Comment, Code, Code, Code]
+        );
+    }
+
+    #[test]
+    fn test_classify_hash_and_markdown() {
+        use LineKind::*;
+        assert_eq!(
+            classify_all(
+                Language::Shell,
+                &["#!/bin/bash", "  # note", "echo hi # x", " "]
+            ),
+            vec![Comment, Comment, Code, Blank]
+        );
+        assert_eq!(
+            classify_all(Language::Markdown, &["# Title", "<!--", "", "-->", "text"]),
+            // blank lines inside a block comment count as comment
+            vec![Code, Comment, Comment, Comment, Code]
+        );
+    }
+}
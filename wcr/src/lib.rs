@@ -5,84 +5,561 @@ use clap::{
     App,
     Arg,
 };
-use std::error::Error;
-use std::fs::File;
-use std::io::{self, BufRead, BufReader};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use ignore::WalkBuilder;
+use ior::Input;
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::ops::AddAssign;
+use std::path::Path;
+use std::str::SplitWhitespace;
+use unicode_segmentation::{UnicodeSegmentation, UnicodeWords};
 
-type MyResult<T> = Result<T, Box<dyn Error>>;
+mod error;
+mod freq;
+mod lang;
+mod progress;
 
+pub use error::Error;
+pub use freq::{FreqConfig, FreqKind};
+use freq::Histogram;
+use lang::{Language, LineKind};
+use progress::Progress;
+
+type MyResult<T> = Result<T, Error>;
+
+// -- what get_args() parses, or built by hand when wcr is used as a
+// library : Config { chars: true, ..Config::default() }
 #[derive(Debug)]
 pub struct Config {
-    files: Vec<String>,
-    lines: bool,
-    words: bool,
-    bytes: bool,
-    chars: bool,
+    pub files: Vec<String>,
+    pub lines: bool,
+    pub words: bool,
+    pub bytes: bool,
+    pub chars: bool,
+    // directory walk : only consulted when a FILE arg is a directory
+    pub recursive: bool,
+    pub include: Option<GlobSet>,
+    pub exclude: Option<GlobSet>,
+    pub gitignore: bool,
+    // report code/comment/blank lines per language instead of wc rows
+    pub code_stats: bool,
+    pub word_mode: WordMode,
+    // top-K items instead of wc rows
+    pub freq: Option<FreqConfig>,
+    pub total: TotalMode,
+    // live counts on STDERR while reading
+    pub progress: bool,
+    // decode gzip, bzip2, xz and zstd input (off with --no-decompress)
+    pub decompress: bool,
+}
+
+// -- when the total row is printed
+// - Auto : more than one file, like wc
+// - Only : the total row alone, without its "total" label
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TotalMode {
+    Auto,
+    Always,
+    Only,
+    Never,
+}
+
+impl Default for Config {
+    // -- wc's defaults : lines, words and bytes of stdin
+    fn default() -> Config {
+        Config {
+            files: vec!["-".to_string()],
+            lines: true,
+            words: true,
+            bytes: true,
+            chars: false,
+            recursive: false,
+            include: None,
+            exclude: None,
+            gitignore: false,
+            code_stats: false,
+            word_mode: WordMode::Whitespace,
+            freq: None,
+            total: TotalMode::Auto,
+            progress: false,
+            decompress: true,
+        }
+    }
+}
+
+impl Config {
+    // --code-stats and --freq replace the usual per-file and total rows
+    fn shows_rows(&self) -> bool {
+        !self.code_stats && self.freq.is_none()
+    }
+
+    // -- the total row, once `num_files` files were counted
+    fn shows_total(&self, num_files: usize) -> bool {
+        match self.total {
+            // print total if more than one file was processed
+            TotalMode::Auto => self.files.len() > 1 || num_files > 1,
+            TotalMode::Always | TotalMode::Only => true,
+            TotalMode::Never => false,
+        }
+    }
+
+    // only -c and / or -l : a mapped file can be counted without decoding
+    fn needs_text(&self) -> bool {
+        self.words || self.chars || !self.shows_rows()
+    }
 }
 
-#[derive(Debug, PartialEq)]
+// -- what counts as a word for -w
+// - Whitespace : runs of non-whitespace, like wc
+// - Unicode : UAX #29 word boundaries, punctuation is not a word and each
+// CJK ideograph is its own word
+// - Regex : every non-overlapping match of the pattern
+#[derive(Debug)]
+pub enum WordMode {
+    Whitespace,
+    Unicode,
+    Regex(Regex),
+}
+
+impl WordMode {
+    fn words<'a>(&'a self, line: &'a str) -> Words<'a> {
+        match self {
+            WordMode::Whitespace => Words::Whitespace(line.split_whitespace()),
+            WordMode::Unicode => Words::Unicode(line.unicode_words()),
+            WordMode::Regex(re) => Words::Regex(re.find_iter(line)),
+        }
+    }
+}
+
+// -- the words of a line per WordMode : an enum, not a Box<dyn Iterator>,
+// so counting a line allocates nothing
+enum Words<'a> {
+    Whitespace(SplitWhitespace<'a>),
+    Unicode(UnicodeWords<'a>),
+    Regex(regex::Matches<'a, 'a>),
+}
+
+impl<'a> Iterator for Words<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        match self {
+            Words::Whitespace(words) => words.next(),
+            Words::Unicode(words) => words.next(),
+            Words::Regex(matches) => matches.next().map(|m| m.as_str()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct FileInfo {
     num_lines: usize,
     num_words: usize,
     num_bytes: usize,
     num_chars: usize,
+    // --code-stats only : lines split by what they hold
+    num_code: usize,
+    num_comments: usize,
+    num_blanks: usize,
+}
+
+// accumulate counts : file -> directory subtotal -> grand total
+impl AddAssign<&FileInfo> for FileInfo {
+    fn add_assign(&mut self, other: &FileInfo) {
+        self.num_lines += other.num_lines;
+        self.num_words += other.num_words;
+        self.num_bytes += other.num_bytes;
+        self.num_chars += other.num_chars;
+        self.num_code += other.num_code;
+        self.num_comments += other.num_comments;
+        self.num_blanks += other.num_blanks;
+    }
+}
+
+// everything accumulated across files for the closing row(s)
+#[derive(Default)]
+struct Totals {
+    info: FileInfo,
+    num_files: usize,
+    // --code-stats : (number of files, counts) per language
+    languages: BTreeMap<Language, (usize, FileInfo)>,
+    // --freq : one histogram merged across all files
+    histogram: Option<Histogram>,
+    // rows are held until every file is counted, column width depends on
+    // the totals
+    rows: Vec<Row>,
+    // stdin or another non regular file was read, see column_width()
+    has_stream: bool,
+    // --progress reports and SIGUSR1 / SIGINFO snapshots, None only for
+    // Totals::default()
+    progress: Option<Progress>,
+}
+
+// one output line : counts and what they belong to, no label for stdin
+struct Row {
+    info: FileInfo,
+    label: Option<String>,
+}
+
+// running subtotal for a directory that is still being walked
+struct DirTotal {
+    path: String,
+    info: FileInfo,
+    num_files: usize,
 }
 
 pub fn run(config: Config) -> MyResult<()> {
-    // @todo : process files wrt arguments and business logic here
-    let mut lines_total = 0;
-    let mut words_total = 0;
-    let mut bytes_total = 0;
-    let mut chars_total = 0;
+    // rows go out in one write, not a println! each, see ior::stdout()
+    let mut out = ior::stdout();
+    // not locked for the whole run : --progress reports from a thread of
+    // its own
+    run_with(&config, &mut out, &mut io::stderr())?;
+    out.flush().map_err(Error::WriteFailed)
+}
+
+// -- run() with the rows and the per-file errors going where the caller
+// says, e.g. into a Vec<u8>
+// - files that fail to open or read are reported to `err` and skipped,
+// --progress still draws on STDERR
+pub fn run_with<W: Write, E: Write>(config: &Config, out: &mut W, err: &mut E) -> MyResult<()> {
+    let mut totals = Totals {
+        histogram: config.freq.as_ref().map(Histogram::new),
+        // always there : a SIGUSR1 snapshot doesn't need --progress
+        progress: Some(Progress::new(config.progress)),
+        ..Totals::default()
+    };
 
     for filename in &config.files {
-        match open(filename) {
-            Err(err) => eprintln!("[{}]: --> [error] {}", filename, err),
-            Ok(file) => {
-                if let Ok(info) = count(file) {
-                    // display count from current file
-                    println!(
-                        "{}{}{}{}{}",
-                        format_field(info.num_lines, config.lines),
-                        format_field(info.num_words, config.words),
-                        format_field(info.num_bytes, config.bytes),
-                        format_field(info.num_chars, config.chars),
-                        if filename == "-" {
-                            // don't print filename if stdin
-                            // @audit : what's the tradeoff between :
-                            // - "".to_string()
-                            // - format!("")
-                            // "".to_string()
-                            format!("")
-                        } else {
-                            format!(" {}", &filename)
-                        },
-                    );
-
-                    // accumulate count across files
-                    lines_total += info.num_lines;
-                    words_total += info.num_words;
-                    bytes_total += info.num_bytes;
-                    chars_total += info.num_chars;
-                }
+        if filename != "-" && Path::new(filename).is_dir() {
+            if config.recursive {
+                walk(filename, config, &mut totals, err);
+            } else {
+                // File::open() succeeds on a directory, read_line() does not
+                let e = Error::OpenFailed {
+                    path: filename.to_string(),
+                    source: io::Error::new(io::ErrorKind::IsADirectory, "Is a directory"),
+                };
+                report(err, &e);
             }
+            continue;
+        }
+        // -- a named input (stdin included) --code-stats can't classify
+        // is reported, walks leave such files out quietly
+        if config.code_stats && Language::from_path(Path::new(filename)).is_none() {
+            let e = Error::UnknownLanguage {
+                path: filename.to_string(),
+            };
+            report(err, &e);
+            continue;
         }
+
+        record(filename, config, &mut totals, err);
     }
 
-    // print total if more than one file was processed
-    if config.files.len() > 1 {
-        println!(
-            "{}{}{}{} total",
-            format_field(lines_total, config.lines),
-            format_field(words_total, config.words),
-            format_field(bytes_total, config.bytes),
-            format_field(chars_total, config.chars),
-        );
+    if config.code_stats {
+        print_code_stats(out, &totals)
+    } else if let Some(histogram) = &totals.histogram {
+        histogram
+            .top()
+            .into_iter()
+            .try_for_each(|(item, count)| writeln!(out, "{} {}", format_field(count, true, 8), item))
+    } else {
+        print_rows(out, config, &totals)
     }
+    .map_err(Error::WriteFailed)
+}
+
+// -- the error sink is best effort, like eprintln! without the panic
+fn report<E: Write>(err: &mut E, e: &dyn std::fmt::Display) {
+    let _ = writeln!(err, "{}", e);
+}
 
+// -- 2nd pass : every count is known, lay out and print the rows
+fn print_rows<W: Write>(out: &mut W, config: &Config, totals: &Totals) -> io::Result<()> {
+    let show_total = config.shows_total(totals.num_files);
+    let rows: &[Row] = match config.total {
+        TotalMode::Only => &[],
+        _ => &totals.rows,
+    };
+
+    let width = column_width(config, totals, rows.len() + show_total as usize);
+    for row in rows {
+        print_row(out, &row.info, config, width, row.label.as_deref())?;
+    }
+    if show_total {
+        let label = match config.total {
+            TotalMode::Only => None,
+            _ => Some("total"),
+        };
+        print_row(out, &totals.info, config, width, label)?;
+    }
     Ok(())
 }
 
+// -- GNU wc column width
+// - a lone number (one row, one count) is not padded at all
+// - otherwise wide enough for the total byte count, which bounds every
+// other count, and at least 7 when reading stdin or a pipe whose size
+// isn't known up front
+fn column_width(config: &Config, totals: &Totals, num_rows: usize) -> usize {
+    let num_columns = [config.lines, config.words, config.bytes, config.chars]
+        .iter()
+        .filter(|v| **v)
+        .count();
+    if num_columns == 1 && num_rows == 1 {
+        return 1;
+    }
+    let min_width = if totals.has_stream { 7 } else { 1 };
+    totals.info.num_bytes.to_string().len().max(min_width)
+}
+
+// -- open and count a single file, queue its row and add it to the totals
+// - failures are reported to `err` and yield None
+fn record<E: Write>(
+    filename: &str,
+    config: &Config,
+    totals: &mut Totals,
+    err: &mut E,
+) -> Option<FileInfo> {
+    let lang = Language::from_path(Path::new(filename));
+    // --code-stats : files in languages we can't classify are left out
+    // (run_with() reports the named ones)
+    if config.code_stats && lang.is_none() {
+        return None;
+    }
+
+    let lang = lang.filter(|_| config.code_stats);
+    if let Some(progress) = totals.progress.as_mut() {
+        progress.start(filename);
+    }
+    let counted = open(filename, config.decompress).and_then(|input| match input {
+        Input::Mapped(map) if !config.needs_text() => {
+            Ok(count_mapped_with(&map, totals.progress.as_mut()))
+        }
+        input => {
+            let reader = input.into_reader();
+            let reader: Box<dyn BufRead> = match &totals.progress {
+                Some(progress) => Box::new(progress.track(reader)),
+                None => reader,
+            };
+            count_with(
+                reader,
+                &config.word_mode,
+                lang,
+                totals.histogram.as_mut(),
+                totals.progress.as_mut(),
+            )
+            .map_err(|source| Error::ReadFailed {
+                path: filename.to_string(),
+                source,
+            })
+        }
+    });
+    if let Some(progress) = totals.progress.as_mut() {
+        progress.finish();
+    }
+    let info = match counted {
+        Err(e) => {
+            report(err, &e);
+            return None;
+        }
+        Ok(info) => info,
+    };
+
+    if config.shows_rows() {
+        totals.rows.push(Row {
+            info: info.clone(),
+            // don't print filename if stdin
+            label: Some(filename.to_string()).filter(|f| f != "-"),
+        });
+    }
+    if !is_regular(filename) {
+        totals.has_stream = true;
+    }
+
+    // accumulate count across files
+    totals.info += &info;
+    totals.num_files += 1;
+    if let (true, Some(lang)) = (config.code_stats, lang) {
+        let (num_files, lang_info) = totals.languages.entry(lang).or_default();
+        *num_files += 1;
+        *lang_info += &info;
+    }
+    Some(info)
+}
+
+// -- walk a directory depth first, queuing a row per file and a subtotal
+// row as each directory is left
+fn walk<E: Write>(root: &str, config: &Config, totals: &mut Totals, err: &mut E) {
+    let exclude = config.exclude.clone();
+    let prefix = Path::new(root).to_path_buf();
+    let walker = WalkBuilder::new(root)
+        // only honor the filters that were asked for
+        .standard_filters(false)
+        .git_ignore(config.gitignore)
+        .git_exclude(config.gitignore)
+        .require_git(false)
+        .ignore(false)
+        // deterministic output order
+        .sort_by_file_name(|a, b| a.cmp(b))
+        // prune excluded directories instead of walking them
+        .filter_entry(move |entry| match &exclude {
+            Some(set) => {
+                entry.depth() == 0
+                    || !set.is_match(relative(entry.path(), &prefix))
+            }
+            None => true,
+        })
+        .build();
+
+    // stack of directories currently open, innermost last
+    let mut dirs: Vec<DirTotal> = vec![];
+
+    for result in walker {
+        let entry = match result {
+            Err(e) => {
+                report(err, &format_args!("[{}]: --> [error] {}", root, e));
+                continue;
+            }
+            Ok(entry) => entry,
+        };
+
+        // leaving directories : every dir deeper or level with this entry
+        while dirs.len() > entry.depth() {
+            close_dir(&mut dirs, config, totals);
+        }
+
+        let path = entry.path().display().to_string();
+        if entry.file_type().is_some_and(|t| t.is_dir()) {
+            dirs.push(DirTotal {
+                path,
+                info: FileInfo::default(),
+                num_files: 0,
+            });
+            continue;
+        }
+
+        if !is_selected(relative(entry.path(), Path::new(root)), config) {
+            continue;
+        }
+
+        if let Some(info) = record(&path, config, totals, err) {
+            if let Some(dir) = dirs.last_mut() {
+                dir.info += &info;
+                dir.num_files += 1;
+            }
+        }
+    }
+
+    while !dirs.is_empty() {
+        close_dir(&mut dirs, config, totals);
+    }
+}
+
+// -- pop the innermost directory, queue its subtotal and roll it up
+fn close_dir(dirs: &mut Vec<DirTotal>, config: &Config, totals: &mut Totals) {
+    if let Some(dir) = dirs.pop() {
+        // skip directories where nothing matched
+        if dir.num_files == 0 {
+            return;
+        }
+        // the only directory given : its subtotal would repeat the total
+        let root_is_total = dirs.is_empty()
+            && config.files.len() == 1
+            && config.shows_total(dir.num_files);
+        // --code-stats and --freq don't report per directory
+        if config.shows_rows() && !root_is_total {
+            totals.rows.push(Row {
+                info: dir.info.clone(),
+                label: Some(format!("{}/ subtotal", dir.path.trim_end_matches('/'))),
+            });
+        }
+        if let Some(parent) = dirs.last_mut() {
+            parent.info += &dir.info;
+            parent.num_files += dir.num_files;
+        }
+    }
+}
+
+// -- globs are matched relative to the directory given on the command line
+// - `wcr -r src --exclude 'bin/**'` skips src/bin/*
+fn relative<'a>(path: &'a Path, root: &Path) -> &'a Path {
+    path.strip_prefix(root).unwrap_or(path)
+}
+
+// -- a file is counted when it matches any --include and no --exclude
+fn is_selected(path: &Path, config: &Config) -> bool {
+    let included = match &config.include {
+        Some(set) => set.is_match(path),
+        None => true,
+    };
+    let excluded = match &config.exclude {
+        Some(set) => set.is_match(path),
+        None => false,
+    };
+    included && !excluded
+}
+
+fn print_row<W: Write>(
+    out: &mut W,
+    info: &FileInfo,
+    config: &Config,
+    width: usize,
+    label: Option<&str>,
+) -> io::Result<()> {
+    match label {
+        Some(label) => writeln!(out, "{} {}", format_counts(info, config, width), label),
+        None => writeln!(out, "{}", format_counts(info, config, width)),
+    }
+}
+
+// -- one row per language, then a total row
+// - blank lines inside block comments count as comments
+fn print_code_stats<W: Write>(out: &mut W, totals: &Totals) -> io::Result<()> {
+    writeln!(
+        out,
+        "{:>8}{:>8}{:>8}{:>8}{:>8} language",
+        "files", "lines", "code", "comment", "blank"
+    )?;
+    for (lang, (num_files, info)) in &totals.languages {
+        writeln!(out, "{} {}", format_code_stats(*num_files, info), lang.name())?;
+    }
+    writeln!(
+        out,
+        "{} total",
+        format_code_stats(totals.num_files, &totals.info)
+    )
+}
+
+fn format_code_stats(num_files: usize, info: &FileInfo) -> String {
+    format!(
+        "{}{}{}{}{}",
+        format_field(num_files, true, 8),
+        format_field(info.num_lines, true, 8),
+        format_field(info.num_code, true, 8),
+        format_field(info.num_comments, true, 8),
+        format_field(info.num_blanks, true, 8),
+    )
+}
+
+// -- shown counts, each padded to `width` and separated by one space
+fn format_counts(info: &FileInfo, config: &Config, width: usize) -> String {
+    [
+        format_field(info.num_lines, config.lines, width),
+        format_field(info.num_words, config.words, width),
+        format_field(info.num_bytes, config.bytes, width),
+        format_field(info.num_chars, config.chars, width),
+    ]
+    .iter()
+    .filter(|field| !field.is_empty())
+    .cloned()
+    .collect::<Vec<_>>()
+    .join(" ")
+}
+
 pub fn get_args() -> MyResult<Config> {
     // [args] parsing
     let matches = App::new("wcr")
@@ -115,6 +592,60 @@ pub fn get_args() -> MyResult<Config> {
                 .short("m")
                 .long("chars"),
         )
+        .arg(
+            Arg::with_name("code_stats")
+                .takes_value(false)
+                .help("Show code, comment and blank lines per language")
+                .long("code-stats"),
+        )
+        .arg(
+            Arg::with_name("exclude")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .help("Skip files matching GLOB when recursing")
+                .long("exclude")
+                .value_name("GLOB"),
+        )
+        .arg(
+            Arg::with_name("files0_from")
+                .takes_value(true)
+                .conflicts_with("files") // << the names come from F instead
+                .help("Read input file names from F, NUL separated (- for stdin)")
+                .long("files0-from")
+                .value_name("F"),
+        )
+        .arg(
+            Arg::with_name("freq")
+                .takes_value(true)
+                .possible_values(&["words", "chars", "lines"])
+                .conflicts_with("code_stats")
+                .help("Show the most frequent items instead of counts")
+                .long("freq")
+                .value_name("KIND"),
+        )
+        .arg(
+            Arg::with_name("gitignore")
+                .takes_value(false)
+                .help("Honor .gitignore rules when recursing")
+                .long("gitignore"),
+        )
+        .arg(
+            Arg::with_name("ignore_case")
+                .takes_value(false)
+                .requires("freq")
+                .help("Fold case when counting --freq items")
+                .long("ignore-case"),
+        )
+        .arg(
+            Arg::with_name("include")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .help("Only count files matching GLOB when recursing")
+                .long("include")
+                .value_name("GLOB"),
+        )
         .arg(
             Arg::with_name("lines")
                 .takes_value(false)
@@ -122,6 +653,58 @@ pub fn get_args() -> MyResult<Config> {
                 .short("l")
                 .long("lines"),
         )
+        .arg(
+            Arg::with_name("min_length")
+                .takes_value(true)
+                .requires("freq")
+                .help("Skip --freq items shorter than LEN chars")
+                .long("min-length")
+                .value_name("LEN"),
+        )
+        .arg(
+            Arg::with_name("no_decompress")
+                .takes_value(false)
+                .help("Count compressed input as is, without decoding")
+                .long("no-decompress"),
+        )
+        .arg(
+            Arg::with_name("progress")
+                .takes_value(false)
+                .help("Show live counts and throughput on STDERR")
+                .long("progress"),
+        )
+        .arg(
+            Arg::with_name("recursive")
+                .takes_value(false)
+                .help("Count files in directories recursively")
+                .short("r")
+                .long("recursive"),
+        )
+        .arg(
+            Arg::with_name("top")
+                .takes_value(true)
+                .requires("freq")
+                .help("Number of --freq items to show [default: 10]")
+                .long("top")
+                .value_name("K"),
+        )
+        .arg(
+            Arg::with_name("total")
+                .takes_value(true)
+                .possible_values(&["auto", "always", "only", "never"])
+                .default_value("auto")
+                .help("When to print a line with total counts")
+                .long("total")
+                .value_name("WHEN"),
+        )
+        .arg(
+            Arg::with_name("word_mode")
+                .takes_value(true)
+                .default_value("whitespace")
+                .help("Word definition : whitespace, unicode or regex:PATTERN")
+                .long("word-mode")
+                .value_name("MODE"),
+        )
         .arg(
             Arg::with_name("words")
                 .takes_value(false)
@@ -134,13 +717,72 @@ pub fn get_args() -> MyResult<Config> {
     // [io] file(s) check
     // default -> '-' which signifies STD_IN flow
     // - so we can safely unwrap or default
-    let files = matches.values_of_lossy("files").unwrap_or_default();
+    // - or from a NUL separated list : `findr src -print0 | wcr --files0-from -`
+    let files = match matches.value_of("files0_from") {
+        Some(list) => {
+            let reader = open(list, false)?.into_reader();
+            let names = ior::read_file_list(reader).map_err(|source| Error::ReadFailed {
+                path: list.to_string(),
+                source,
+            })?;
+            ior::utf8_file_names(names, &mut io::stderr())
+        }
+        None => matches.values_of_lossy("files").unwrap_or_default(),
+    };
 
     // [flag] check
     let mut lines = matches.is_present("lines");
     let mut words = matches.is_present("words");
     let mut bytes = matches.is_present("bytes");
     let chars = matches.is_present("chars");
+    let recursive = matches.is_present("recursive");
+    let gitignore = matches.is_present("gitignore");
+    let code_stats = matches.is_present("code_stats");
+    // has default arg and safe to unwrap
+    let word_mode = parse_word_mode(matches.value_of("word_mode").unwrap())?;
+
+    // possible_values() already rejected anything else
+    let total = match matches.value_of("total") {
+        Some("always") => TotalMode::Always,
+        Some("only") => TotalMode::Only,
+        Some("never") => TotalMode::Never,
+        _ => TotalMode::Auto,
+    };
+
+    // [freq] options only matter once a KIND is given
+    // - possible_values() already rejected anything FreqKind can't parse
+    let top = matches
+        .value_of("top")
+        .map(parse_positive_int)
+        .transpose()
+        .map_err(|value| Error::InvalidCount {
+            what: "top count",
+            value,
+        })?
+        .unwrap_or(10);
+    let min_length = matches
+        .value_of("min_length")
+        .map(|val| {
+            val.parse().map_err(|_| Error::InvalidCount {
+                what: "min length",
+                value: val.to_string(),
+            })
+        })
+        .transpose()?
+        .unwrap_or(0);
+    let freq = matches
+        .value_of("freq")
+        .and_then(FreqKind::parse)
+        .map(|kind| FreqConfig {
+            kind,
+            top,
+            ignore_case: matches.is_present("ignore_case"),
+            min_length,
+        });
+
+    // [glob] filters, validated up front so a typo fails before any output
+    let include = build_globs(matches.values_of_lossy("include"))?;
+    let exclude = build_globs(matches.values_of_lossy("exclude"))?;
 
     // @udit-ok : Explain what this is doing
     // ANSWER : mimics default behaviour of Unix wc command
@@ -179,56 +821,176 @@ pub fn get_args() -> MyResult<Config> {
         words,
         bytes,
         chars,
+        recursive,
+        include,
+        exclude,
+        gitignore,
+        code_stats,
+        word_mode,
+        freq,
+        total,
+        progress: matches.is_present("progress"),
+        decompress: !matches.is_present("no_decompress"),
     })
 }
 
+// -- the offending value comes back as is, get_args() says which count
+fn parse_positive_int(val: &str) -> Result<usize, String> {
+    match val.parse() {
+        Ok(n) if n > 0 => Ok(n),
+        _ => Err(val.to_string()),
+    }
+}
+
+fn parse_word_mode(val: &str) -> MyResult<WordMode> {
+    match val {
+        "whitespace" => Ok(WordMode::Whitespace),
+        "unicode" => Ok(WordMode::Unicode),
+        _ => match val.strip_prefix("regex:") {
+            Some(pattern) => Ok(WordMode::Regex(
+                Regex::new(pattern).map_err(Error::InvalidRegex)?,
+            )),
+            None => Err(Error::InvalidWordMode(val.to_string())),
+        },
+    }
+}
+
+// -- compile --include/--exclude patterns, None when none were given
+fn build_globs(patterns: Option<Vec<String>>) -> MyResult<Option<GlobSet>> {
+    match patterns {
+        None => Ok(None),
+        Some(patterns) => {
+            let mut builder = GlobSetBuilder::new();
+            for pattern in &patterns {
+                let glob = Glob::new(pattern).map_err(Error::InvalidGlob)?;
+                builder.add(glob);
+            }
+            Ok(Some(builder.build().map_err(Error::InvalidGlob)?))
+        }
+    }
+}
+
 // @udit-ok : Explain impl BufRead
 // ANSWER : file can be any type that implements BufRead
 // - BufReader, Cursor are compatible
-pub fn count(mut file: impl BufRead) -> MyResult<FileInfo> {
-    let mut num_lines = 0;
-    let mut num_words = 0;
-    let mut num_bytes = 0;
-    let mut num_chars = 0;
-    let mut line = String::new();
+// - read errors come back as is, the caller knows which file it was
+pub fn count(file: impl BufRead) -> io::Result<FileInfo> {
+    count_with(file, &WordMode::Whitespace, None, None, None)
+}
+
+// -- same pass as count(), splitting words per `word_mode`, classifying
+// each line when the language is known : code, comment or blank,
+// tallying items into `histogram` for --freq and showing the counts so far
+// through `progress`
+// - crate only : lang, freq and progress are wcr's own, a library caller
+// goes through count() or run_with()
+pub(crate) fn count_with(
+    mut file: impl BufRead,
+    word_mode: &WordMode,
+    lang: Option<Language>,
+    mut histogram: Option<&mut Histogram>,
+    mut progress: Option<&mut Progress>,
+) -> io::Result<FileInfo> {
+    // filled in as lines are read, so progress can report it mid file
+    let mut info = FileInfo::default();
+    // block comment left open by the previous line
+    let mut in_block = false;
+    // raw bytes, not String : undecoded input (--no-decompress) or other
+    // binary data still counts
+    let mut buf = Vec::new();
 
     // implement code to actually count here
     loop {
-        let line_bytes = file.read_line(&mut line)?;
+        let line_bytes = file.read_until(b'\n', &mut buf)?;
         // @audit : explain the reason on breaking loop on value 0 specifically
         if line_bytes == 0 {
             break;
         }
-        num_bytes += line_bytes;
-        num_lines += 1;
-        num_words += line.split_whitespace().count();
-        num_chars += line.chars().count();
+        let line = String::from_utf8_lossy(&buf);
+        info.num_bytes += line_bytes;
+        info.num_lines += 1;
+        info.num_words += word_mode.words(&line).count();
+        // a byte that isn't part of a valid UTF-8 char is no char at all,
+        // like GNU wc -m
+        info.num_chars += buf
+            .utf8_chunks()
+            .map(|chunk| chunk.valid().chars().count())
+            .sum::<usize>();
+        if let Some(lang) = lang {
+            match lang.classify(&line, &mut in_block) {
+                LineKind::Code => info.num_code += 1,
+                LineKind::Comment => info.num_comments += 1,
+                LineKind::Blank => info.num_blanks += 1,
+            }
+        }
+        if let Some(histogram) = histogram.as_deref_mut() {
+            histogram.add_line(&line, word_mode);
+        }
+        if let Some(progress) = progress.as_deref_mut() {
+            progress.tick(&info);
+        }
         // @audit : explain why we are clearing the line here
-        line.clear();
+        buf.clear();
     }
 
-    Ok(FileInfo {
-        num_lines,
-        num_words,
-        num_bytes,
-        num_chars,
-    })
+    Ok(info)
+}
+
+// -- -c / -l straight from the bytes of a memory mapped file
+// - no UTF-8 decoding and no per line read_until() : a newline scan the
+// compiler vectorizes
+// - a last line without a newline counts, same as count_with()
+pub fn count_mapped(bytes: &[u8]) -> FileInfo {
+    count_mapped_with(bytes, None)
+}
+
+// bytes of a mapping counted between two progress updates
+const MAPPED_CHUNK: usize = 1 << 20;
+
+// -- count_mapped(), a chunk at a time so `progress` sees the bytes and
+// lines go by instead of 0 until the whole file is done
+fn count_mapped_with(bytes: &[u8], mut progress: Option<&mut Progress>) -> FileInfo {
+    let mut info = FileInfo::default();
+    for chunk in bytes.chunks(MAPPED_CHUNK) {
+        info.num_lines += chunk.iter().filter(|&&b| b == b'\n').count();
+        info.num_bytes += chunk.len();
+        if let Some(progress) = progress.as_deref_mut() {
+            progress.tick(&info);
+            progress.advance(chunk.len());
+        }
+    }
+    let partial = !bytes.is_empty() && !bytes.ends_with(b"\n");
+    info.num_lines += partial as usize;
+    info
 }
 
 // @udit-ok : Explain what is happening with BufReader -> BufRead Result
 // ANSWER : open returns a boxed type that implements BufRead
 // - BufReader has BufRead impl
-fn open(filename: &str) -> MyResult<Box<dyn BufRead>> {
-    match filename {
-        "-" => Ok(Box::new(BufReader::new(io::stdin()))),
-        _ => Ok(Box::new(BufReader::new(File::open(filename)?))),
-    }
+// - compressed input is decoded on the fly, see ior::open_input()
+// - regular files come back memory mapped, Input::into_reader() reads them
+// line by line like any other BufRead
+fn open(filename: &str, decompress: bool) -> MyResult<Input> {
+    ior::open_input(filename, decompress).map_err(|source| Error::OpenFailed {
+        path: filename.to_string(),
+        source,
+    })
+}
+
+// -- false for pipes, ttys and other streams, whose size isn't known
+// - stdin redirected from a file (`wcr < file`) is regular
+fn is_regular(filename: &str) -> bool {
+    let path = match filename {
+        "-" => "/dev/stdin",
+        _ => filename,
+    };
+    fs::metadata(path).map(|m| m.is_file()).unwrap_or(false)
 }
 
-fn format_field(value: usize, show: bool) -> String {
+fn format_field(value: usize, show: bool, width: usize) -> String {
     if show {
         // @audit : explain why no ; needed
-        format!("{:>8}", value)
+        format!("{:>width$}", value)
     } else {
         "".to_string()
     }
@@ -241,7 +1003,10 @@ mod tests {
     // @audit : Explain the tradeoff between
     // - use super::format_field
     // - use cargo::format_field
-    use super::{count, format_field, FileInfo};
+    use super::{
+        count, count_mapped, count_with, format_field, parse_word_mode, record, run_with, Config,
+        FileInfo, Progress, TotalMode, Totals, WordMode,
+    };
     use std::io::Cursor;
 
     #[test]
@@ -254,16 +1019,127 @@ mod tests {
             num_words: 10,
             num_chars: 48,
             num_bytes: 48,
+            ..FileInfo::default()
         };
 
         assert_eq!(info.unwrap(), expected);
     }
 
+    #[test]
+    fn test_count_mapped() {
+        // same lines and bytes as the line by line count
+        for text in ["", "one\n", "one\ntwo", "a\r\nb\n\n"] {
+            let info = count(Cursor::new(text)).unwrap();
+            let mapped = count_mapped(text.as_bytes());
+            assert_eq!(mapped.num_lines, info.num_lines, "{:?}", text);
+            assert_eq!(mapped.num_bytes, info.num_bytes, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn test_mapped_progress() {
+        // -l of a regular file goes by the mapping, its bytes and lines
+        // still reach the progress reports and snapshots
+        let filename = "tests/inputs/atlamal.txt";
+        let config = Config {
+            words: false,
+            bytes: false,
+            ..Config::default()
+        };
+        let mut totals = Totals {
+            progress: Some(Progress::new(false)),
+            ..Totals::default()
+        };
+        let info = record(filename, &config, &mut totals, &mut vec![]).unwrap();
+        let len = std::fs::metadata(filename).unwrap().len() as usize;
+        assert_eq!(totals.progress.unwrap().counts(), (info.num_lines, len));
+    }
+
+    #[test]
+    fn test_count_unicode_words() {
+        // punctuation runs are not words, each ideograph is
+        let text = "Hello -- world ... 你好世界 can't\n";
+        let info = count_with(Cursor::new(text), &WordMode::Unicode, None, None, None);
+        assert_eq!(info.unwrap().num_words, 7);
+
+        // whitespace mode counts the same text as wc would
+        let info = count(Cursor::new(text));
+        assert_eq!(info.unwrap().num_words, 6);
+    }
+
+    #[test]
+    fn test_count_regex_words() {
+        // identifiers only : `x1`, `foo_bar`, `baz`
+        let mode = parse_word_mode(r"regex:[A-Za-z_]\w*").unwrap();
+        let text = "x1 = foo_bar(2, baz);\n42\n";
+        let info = count_with(Cursor::new(text), &mode, None, None, None);
+        assert_eq!(info.unwrap().num_words, 3);
+    }
+
+    #[test]
+    fn test_parse_word_mode() {
+        assert!(matches!(
+            parse_word_mode("whitespace"),
+            Ok(WordMode::Whitespace)
+        ));
+        assert!(matches!(parse_word_mode("unicode"), Ok(WordMode::Unicode)));
+        assert!(matches!(
+            parse_word_mode("regex:\\d+"),
+            Ok(WordMode::Regex(_))
+        ));
+
+        let res = parse_word_mode("regex:(");
+        assert!(res
+            .unwrap_err()
+            .to_string()
+            .starts_with("invalid word regex"));
+        let res = parse_word_mode("words");
+        assert_eq!(res.unwrap_err().to_string(), "invalid word mode -- words");
+    }
+
+    #[test]
+    fn test_run_with() {
+        // a file that can't be opened is reported and left out of the total
+        let config = Config {
+            files: vec![
+                "tests/inputs/fox.txt".to_string(),
+                "no-such-file".to_string(),
+                "tests/inputs/empty.txt".to_string(),
+            ],
+            ..Config::default()
+        };
+        let (mut out, mut err) = (vec![], vec![]);
+        run_with(&config, &mut out, &mut err).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            " 1  9 48 tests/inputs/fox.txt\n 0  0  0 tests/inputs/empty.txt\n 1  9 48 total\n"
+        );
+        assert!(String::from_utf8(err)
+            .unwrap()
+            .starts_with("[no-such-file]: --> [error]"));
+
+        // a lone count isn't padded
+        let config = Config {
+            files: vec!["tests/inputs/fox.txt".to_string()],
+            lines: false,
+            words: false,
+            bytes: false,
+            chars: true,
+            total: TotalMode::Never,
+            ..Config::default()
+        };
+        let mut out = vec![];
+        run_with(&config, &mut out, &mut vec![]).unwrap();
+        assert_eq!(out, b"48 tests/inputs/fox.txt\n");
+    }
+
     #[test]
     fn test_format_field() {
-        assert_eq!(format_field(1, false), "");
+        assert_eq!(format_field(1, false, 8), "");
         #[rustfmt::skip] // line up spaces
-        assert_eq!(format_field( 3, true), "       3");
-        assert_eq!(format_field(10, true), "      10");
+        assert_eq!(format_field( 3, true, 8), "       3");
+        assert_eq!(format_field(10, true, 8), "      10");
+        // never truncated below the value's own width
+        assert_eq!(format_field(10, true, 1), "10");
     }
 }
@@ -1,6 +1,13 @@
+use ior::Failure;
+
 fn main() {
     if let Err(e) = wcr::get_args().and_then(wcr::run){
+        // stdout closed before the counts got out, see ior::PipePolicy
+        if e.is_broken_pipe() {
+            std::process::exit(ior::PipePolicy::from_env().exit_code());
+        }
         eprintln!("{}", e);
-        std::process::exit(1);
+        // each kind of failure has its own status, see wcr::Error
+        std::process::exit(e.exit_code());
     }
 }
@@ -0,0 +1,275 @@
+use crate::FileInfo;
+use std::io::{self, BufRead, IsTerminal, Read, Write};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+// how often --progress reports while a file is being counted
+const INTERVAL: Duration = Duration::from_secs(1);
+// how soon the reporter notices a SIGUSR1 / SIGINFO
+const POLL: Duration = Duration::from_millis(50);
+
+// -- live counts on STDERR while a (slow) input is still being read
+// - --progress : every INTERVAL, redrawn in place on a terminal, one line
+// per report otherwise so logs stay readable
+// - SIGUSR1 (and SIGINFO / ctrl-T on BSD and macOS) : one snapshot line on
+// demand, like dd, with or without --progress : the handlers only set a
+// flag, so `kill -USR1` no longer ends a plain wcr
+// - reports come from a thread of their own, not from the read loop : a
+// stalled pipe still gets them, and bytes show up as they're read (see
+// Tracked), not once their line ends
+pub struct Progress {
+    shared: Arc<Shared>,
+    reporter: Option<JoinHandle<()>>,
+}
+
+// -- what the read loop updates and the reporter reads
+struct Shared {
+    lines: AtomicUsize,
+    words: AtomicUsize,
+    // read so far, a line that hasn't ended yet included
+    bytes: AtomicUsize,
+    // set from the signal handler, cleared once the snapshot is printed
+    requested: Arc<AtomicBool>,
+    // --progress : a report every INTERVAL, not only snapshots
+    live: bool,
+    done: AtomicBool,
+    input: Mutex<Current>,
+}
+
+// -- the input being counted, and what's on the terminal for it
+struct Current {
+    // None between inputs : nothing to report
+    label: Option<String>,
+    started: Instant,
+    last: Instant,
+    tty: bool,
+    // a report was drawn in place and still needs clearing
+    drawn: bool,
+}
+
+impl Progress {
+    pub fn new(live: bool) -> Progress {
+        let shared = Arc::new(Shared {
+            lines: AtomicUsize::new(0),
+            words: AtomicUsize::new(0),
+            bytes: AtomicUsize::new(0),
+            requested: requested(),
+            live,
+            done: AtomicBool::new(false),
+            input: Mutex::new(Current {
+                label: None,
+                started: Instant::now(),
+                last: Instant::now(),
+                tty: io::stderr().is_terminal(),
+                drawn: false,
+            }),
+        });
+        let reporter = {
+            let shared = Arc::clone(&shared);
+            thread::spawn(move || shared.report_until_done())
+        };
+        Progress {
+            shared,
+            reporter: Some(reporter),
+        }
+    }
+
+    // -- a new input : counts back to 0, restart the clock used for
+    // throughput
+    pub fn start(&mut self, filename: &str) {
+        for count in [&self.shared.lines, &self.shared.words, &self.shared.bytes] {
+            count.store(0, Ordering::Relaxed);
+        }
+        let mut input = self.shared.lock();
+        input.label = Some(match filename {
+            "-" => "stdin".to_string(),
+            _ => filename.to_string(),
+        });
+        input.started = Instant::now();
+        input.last = input.started;
+    }
+
+    // -- `reader` with every byte it hands out counted
+    pub fn track<R: BufRead>(&self, reader: R) -> Tracked<R> {
+        Tracked {
+            inner: reader,
+            shared: Arc::clone(&self.shared),
+        }
+    }
+
+    // -- called once per line with the counts so far : two atomic stores
+    pub fn tick(&mut self, info: &FileInfo) {
+        self.shared.lines.store(info.num_lines, Ordering::Relaxed);
+        self.shared.words.store(info.num_words, Ordering::Relaxed);
+    }
+
+    // -- `bytes` more read without a reader to track, e.g. a chunk of a
+    // memory mapped file
+    pub fn advance(&mut self, bytes: usize) {
+        self.shared.bytes.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    // -- (lines, bytes) counted so far in the current input
+    #[cfg(test)]
+    pub fn counts(&self) -> (usize, usize) {
+        let lines = self.shared.lines.load(Ordering::Relaxed);
+        (lines, self.shared.bytes.load(Ordering::Relaxed))
+    }
+
+    // -- input done : wipe an in place report so it doesn't linger
+    pub fn finish(&mut self) {
+        let mut input = self.shared.lock();
+        input.label = None;
+        input.clear();
+    }
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        self.shared.done.store(true, Ordering::Relaxed);
+        if let Some(reporter) = self.reporter.take() {
+            let _ = reporter.join();
+        }
+    }
+}
+
+impl Shared {
+    // -- a poisoned lock only means a report panicked half way, what's in
+    // it is still good to draw from
+    fn lock(&self) -> MutexGuard<'_, Current> {
+        self.input.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn report_until_done(&self) {
+        while !self.done.load(Ordering::Relaxed) {
+            thread::sleep(POLL);
+            let snapshot = self.requested.swap(false, Ordering::Relaxed);
+            let mut input = self.lock();
+            let Some(label) = &input.label else { continue };
+            let report = self.report(label, input.started);
+            if snapshot {
+                input.clear();
+                eprintln!("{}", report);
+            }
+            if self.live && input.last.elapsed() >= INTERVAL {
+                input.last = Instant::now();
+                input.draw(&report);
+            }
+        }
+    }
+
+    fn report(&self, label: &str, started: Instant) -> String {
+        let bytes = self.bytes.load(Ordering::Relaxed);
+        let secs = started.elapsed().as_secs_f64();
+        format!(
+            "{}: {} lines, {} words, {} bytes, {}/s",
+            label,
+            self.lines.load(Ordering::Relaxed),
+            self.words.load(Ordering::Relaxed),
+            bytes,
+            format_rate(bytes as f64 / secs.max(f64::EPSILON)),
+        )
+    }
+}
+
+impl Current {
+    fn draw(&mut self, report: &str) {
+        let mut stderr = io::stderr().lock();
+        // progress is best effort, a failed write to STDERR is not an error
+        let _ = match self.tty {
+            true => write!(stderr, "\r\x1b[K{}", report),
+            false => writeln!(stderr, "{}", report),
+        };
+        let _ = stderr.flush();
+        self.drawn = self.tty;
+    }
+
+    fn clear(&mut self) {
+        if self.drawn {
+            eprint!("\r\x1b[K");
+            self.drawn = false;
+        }
+    }
+}
+
+// -- a reader counting the bytes it hands out, for Progress to report
+pub struct Tracked<R> {
+    inner: R,
+    shared: Arc<Shared>,
+}
+
+impl<R: BufRead> Read for Tracked<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.shared.bytes.fetch_add(n, Ordering::Relaxed);
+        Ok(n)
+    }
+}
+
+impl<R: BufRead> BufRead for Tracked<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.shared.bytes.fetch_add(amt, Ordering::Relaxed);
+        self.inner.consume(amt);
+    }
+}
+
+// -- the flag SIGUSR1 / SIGINFO set, their handlers installed once per
+// process however many runs there are
+fn requested() -> Arc<AtomicBool> {
+    static REQUESTED: OnceLock<Arc<AtomicBool>> = OnceLock::new();
+    let requested = REQUESTED.get_or_init(|| {
+        let requested = Arc::new(AtomicBool::new(false));
+        register_signals(&requested);
+        requested
+    });
+    Arc::clone(requested)
+}
+
+#[cfg(unix)]
+fn register_signals(requested: &Arc<AtomicBool>) {
+    use signal_hook::{consts::SIGUSR1, flag};
+    // a snapshot is a nicety : without the handler wcr still counts
+    let _ = flag::register(SIGUSR1, Arc::clone(requested));
+    #[cfg(any(
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd",
+        target_os = "dragonfly"
+    ))]
+    let _ = flag::register(signal_hook::consts::SIGINFO, Arc::clone(requested));
+}
+
+#[cfg(not(unix))]
+fn register_signals(_requested: &Arc<AtomicBool>) {}
+
+// -- bytes per second, scaled to a readable unit
+fn format_rate(rate: f64) -> String {
+    let units = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut rate = rate;
+    let mut unit = 0;
+    while rate >= 1024.0 && unit < units.len() - 1 {
+        rate /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", rate, units[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::format_rate;
+
+    #[test]
+    fn test_format_rate() {
+        assert_eq!(format_rate(0.0), "0.0 B");
+        assert_eq!(format_rate(1536.0), "1.5 KiB");
+        assert_eq!(format_rate(3.0 * 1024.0 * 1024.0 * 1024.0), "3.0 GiB");
+    }
+}
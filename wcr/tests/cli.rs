@@ -9,6 +9,13 @@ const PRG: &str = "wcr";
 const EMPTY: &str = "tests/inputs/empty.txt";
 const FOX: &str = "tests/inputs/fox.txt";
 const ATLAMAL: &str = "tests/inputs/atlamal.txt";
+const TREE: &str = "tests/inputs/tree";
+const ATLAMAL_GZ: &str = "tests/inputs/atlamal.txt.gz";
+const COMMENTS_RS: &str = "tests/inputs/comments.rs";
+const COMMENTS_SH: &str = "tests/inputs/comments.sh";
+// Latin-1 and cut off UTF-8, see mk-outs.sh
+const LATIN1: &str = "tests/inputs/latin1.txt";
+const ALL_FILES0: &str = "tests/inputs/all.files0";
 
 // --------------------------------------------------------------------------80
 
@@ -133,12 +140,62 @@ fn atlama_stdin() -> TestResult {
     Ok(())
 }
 
+// --------------------------------------------------------------------------80
+// [invalid UTF-8] bytes outside a valid char count for -c, not for -m
+#[test]
+fn latin1_chars() -> TestResult {
+    run(&["-m", LATIN1], "tests/expected/latin1.txt.m.out")
+}
+
+#[test]
+fn latin1_chars_lines() -> TestResult {
+    run(&["-m", "-l", LATIN1], "tests/expected/latin1.txt.ml.out")
+}
+
+#[test]
+fn latin1_bytes_lines() -> TestResult {
+    run(&["-c", "-l", LATIN1], "tests/expected/latin1.txt.cl.out")
+}
+
+#[test]
+fn latin1_stdin_chars() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .arg("-m")
+        .write_stdin(fs::read(LATIN1)?)
+        .assert()
+        .success()
+        .stdout("22\n");
+    Ok(())
+}
+
 // --------------------------------------------------------------------------80
 #[test]
 fn test_all() -> TestResult {
     run(&[EMPTY, FOX, ATLAMAL], "tests/expected/all.out")
 }
 
+#[test]
+fn test_all_files0_from() -> TestResult {
+    // the same three files, listed NUL separated like findr -print0 writes
+    run(&["--files0-from", ALL_FILES0], "tests/expected/all.out")?;
+    Command::cargo_bin(PRG)?
+        .args(["--files0-from", "-", "-l"])
+        .write_stdin(fs::read(ALL_FILES0)?)
+        .assert()
+        .success()
+        .stdout(fs::read_to_string("tests/expected/all.l.out")?);
+    Ok(())
+}
+
+#[test]
+fn dies_files0_from_with_files() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["--files0-from", ALL_FILES0, FOX])
+        .assert()
+        .failure();
+    Ok(())
+}
+
 #[test]
 fn test_all_lines() -> TestResult {
     run(&["-l", EMPTY, FOX, ATLAMAL], "tests/expected/all.l.out")
@@ -169,6 +226,279 @@ fn test_all_bytes_lines() -> TestResult {
     run(&["-cl", EMPTY, FOX, ATLAMAL], "tests/expected/all.cl.out")
 }
 
+#[test]
+fn test_all_total_only() -> TestResult {
+    run(
+        &["--total=only", EMPTY, FOX, ATLAMAL],
+        "tests/expected/all.total-only.out",
+    )
+}
+
+#[test]
+fn test_all_lines_total_only() -> TestResult {
+    // a lone number is not padded
+    run(
+        &["-l", "--total", "only", EMPTY, FOX, ATLAMAL],
+        "tests/expected/all.l.total-only.out",
+    )
+}
+
+#[test]
+fn test_all_total_never() -> TestResult {
+    run(
+        &["--total=never", EMPTY, FOX, ATLAMAL],
+        "tests/expected/all.total-never.out",
+    )
+}
+
+#[test]
+fn fox_lines_total_always() -> TestResult {
+    run(
+        &["-l", "--total=always", FOX],
+        "tests/expected/fox.txt.l.total-always.out",
+    )
+}
+
+#[test]
+fn atlamal_stdin_progress() -> TestResult {
+    // reports go to STDERR only, counts on STDOUT are unchanged
+    let input = fs::read_to_string(ATLAMAL)?;
+    let expected = fs::read_to_string("tests/expected/atlamal.txt.stdin.out")?;
+    Command::cargo_bin(PRG)?
+        .arg("--progress")
+        .write_stdin(input)
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+#[test]
+fn stalled_stdin_progress() -> TestResult {
+    use std::io::Write;
+    use std::process::Stdio;
+    use std::time::Duration;
+
+    // a line that hasn't ended, then nothing for a while : still reported,
+    // its bytes included, one line per report when STDERR isn't a tty
+    let mut child = std::process::Command::new(assert_cmd::cargo::cargo_bin(PRG))
+        .arg("--progress")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let mut stdin = child.stdin.take().unwrap();
+    stdin.write_all(b"one two\nthree")?;
+    stdin.flush()?;
+    std::thread::sleep(Duration::from_millis(1500));
+    drop(stdin);
+    let output = child.wait_with_output()?;
+    assert!(output.status.success());
+    let stderr = String::from_utf8(output.stderr)?;
+    assert!(
+        stderr.starts_with("stdin: 1 lines, 2 words, 13 bytes, "),
+        "{:?}",
+        stderr
+    );
+    assert_eq!(String::from_utf8(output.stdout)?, "      2       3      13\n");
+    Ok(())
+}
+
+#[test]
+fn sigusr1_snapshot_without_progress() -> TestResult {
+    use std::io::Write;
+    use std::process::Stdio;
+    use std::time::Duration;
+
+    // like dd : SIGUSR1 asks for one snapshot, it doesn't end wcr
+    let mut child = std::process::Command::new(assert_cmd::cargo::cargo_bin(PRG))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let mut stdin = child.stdin.take().unwrap();
+    stdin.write_all(b"one two\nthree")?;
+    stdin.flush()?;
+    std::thread::sleep(Duration::from_millis(500));
+    let killed = std::process::Command::new("kill")
+        .args(["-USR1", &child.id().to_string()])
+        .status()?;
+    assert!(killed.success());
+    std::thread::sleep(Duration::from_millis(500));
+    drop(stdin);
+    let output = child.wait_with_output()?;
+    assert_eq!(output.status.code(), Some(0));
+    let stderr = String::from_utf8(output.stderr)?;
+    assert!(
+        stderr.starts_with("stdin: 1 lines, 2 words, 13 bytes, "),
+        "{:?}",
+        stderr
+    );
+    assert_eq!(stderr.lines().count(), 1, "{:?}", stderr);
+    assert_eq!(String::from_utf8(output.stdout)?, "      2       3      13\n");
+    Ok(())
+}
+
+// --------------------------------------------------------------------------80
+// [compressed] counts of the decoded text, unless --no-decompress
+#[test]
+fn atlamal_gz() -> TestResult {
+    run(&[ATLAMAL_GZ], "tests/expected/atlamal.txt.gz.out")
+}
+
+#[test]
+fn atlamal_gz_no_decompress() -> TestResult {
+    run(
+        &["-c", "--no-decompress", ATLAMAL_GZ],
+        "tests/expected/atlamal.txt.gz.c.no-decompress.out",
+    )
+}
+
+// --------------------------------------------------------------------------80
+// [recursive] expected outputs are NOT generated by wc, which has no subtotals
+#[test]
+fn tree_recursive() -> TestResult {
+    run(&["-r", TREE], "tests/expected/tree.r.out")
+}
+
+#[test]
+fn tree_recursive_roots() -> TestResult {
+    // one root : no subtotal repeating the total, several : one per root
+    let src = format!("{}/src", TREE);
+    let docs = format!("{}/docs", TREE);
+    Command::cargo_bin(PRG)?
+        .args(["-r", &src])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("src/ subtotal").not());
+    Command::cargo_bin(PRG)?
+        .args(["-r", &src, &docs])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("  7  21  95 tests/inputs/tree/src/ subtotal\n"))
+        .stdout(predicate::str::contains("  2   9  45 tests/inputs/tree/docs/ subtotal\n"));
+    Ok(())
+}
+
+#[test]
+fn tree_recursive_include_exclude() -> TestResult {
+    run(
+        &["-r", "--include", "*.rs", "--exclude", "build/**", TREE],
+        "tests/expected/tree.r.include.out",
+    )
+}
+
+#[test]
+fn tree_recursive_gitignore() -> TestResult {
+    run(
+        &["--recursive", "--lines", "--gitignore", TREE],
+        "tests/expected/tree.rl.gitignore.out",
+    )
+}
+
+#[test]
+fn code_stats() -> TestResult {
+    // fox.txt has no known language : reported and left out
+    let expected = fs::read_to_string("tests/expected/comments.code-stats.out")?;
+    Command::cargo_bin(PRG)?
+        .args(["--code-stats", COMMENTS_RS, COMMENTS_SH, FOX])
+        .assert()
+        .success()
+        .stdout(expected)
+        .stderr(format!(
+            "[{}]: --> [error] no known language for --code-stats\n",
+            FOX
+        ));
+    Ok(())
+}
+
+#[test]
+fn code_stats_stdin() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .arg("--code-stats")
+        .write_stdin("fn main() {}\n")
+        .assert()
+        .success()
+        .stderr("[-]: --> [error] no known language for --code-stats\n");
+    Ok(())
+}
+
+#[test]
+fn tree_code_stats() -> TestResult {
+    run(
+        &["--code-stats", "-r", "--gitignore", TREE],
+        "tests/expected/tree.code-stats.out",
+    )
+}
+
+#[test]
+fn freq_words_merged() -> TestResult {
+    // one histogram across both files
+    run(
+        &[
+            "--freq",
+            "words",
+            "--ignore-case",
+            "--min-length",
+            "3",
+            "--top",
+            "5",
+            FOX,
+            ATLAMAL,
+        ],
+        "tests/expected/all.freq.words.out",
+    )
+}
+
+#[test]
+fn dies_top_without_freq() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["--top", "3", FOX])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--freq <KIND>"));
+    Ok(())
+}
+
+#[test]
+fn skips_dir_without_recursive() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args([TREE, FOX])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Is a directory"))
+        .stdout(predicate::str::contains("total"));
+    Ok(())
+}
+
+#[test]
+fn dies_bad_glob() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["-r", "--include", "[", TREE])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid glob"));
+    Ok(())
+}
+
+#[test]
+fn exit_code_bad_args() -> TestResult {
+    // every bad argument value is status 2, see wcr::Error
+    for (args, error) in [
+        (&["--freq", "words", "--top", "0", FOX][..], "illegal top count -- 0"),
+        (&["--freq", "words", "--min-length", "x", FOX], "illegal min length -- x"),
+        (&["--word-mode", "regex:(", FOX], "invalid word regex"),
+        (&["--word-mode", "bogus", FOX], "invalid word mode -- bogus"),
+    ] {
+        Command::cargo_bin(PRG)?
+            .args(args)
+            .assert()
+            .code(2)
+            .stderr(predicate::str::contains(error));
+    }
+    Ok(())
+}
+
 // --------------------------------------------------------------------------80
 
 #[test]
@@ -193,7 +523,7 @@ fn skips_bad_file() -> TestResult {
 #[test]
 fn dies_chars_and_bytes() -> TestResult {
     Command::cargo_bin(PRG)?
-        .args(&["-m", "-c"])
+        .args(["-m", "-c"])
         .assert()
         .failure()
         // @audit : Explain how to determine this predicate string
@@ -218,3 +548,34 @@ fn dies_chars_and_bytes() -> TestResult {
 //
 // [x] [multi-files] => [total] # lines | words | byte
 // [x] [file-error] => Nonexistent files note warning to STDERR as files process
+// [x] [recursive] => per-file rows, per-directory subtotals, include/exclude
+// [x] [code-stats] => code | comment | blank lines per language
+// [x] [freq] => top-K words | chars | lines merged across files
+// [x] [layout] => GNU column widths, --total=auto|always|only|never
+// [x] [progress] => live counts on STDERR, STDOUT unchanged
+// [x] [compressed] => gzip | bzip2 | xz | zstd decoded by magic bytes
+
+// --------------------------------------------------------------------------80
+
+// the reader is gone before the counts are printed : no panic, no
+// message, exit 0 or $SIGPIPE_STATUS=141
+#[test]
+fn broken_pipe_exits_quietly() -> TestResult {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    for (policy, code) in [("", 0), ("141", 141)] {
+        let mut child = std::process::Command::new(assert_cmd::cargo::cargo_bin(PRG))
+            .env("SIGPIPE_STATUS", policy)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        drop(child.stdout.take());
+        child.stdin.take().unwrap().write_all(&fs::read(FOX)?)?;
+        let output = child.wait_with_output()?;
+        assert_eq!(output.status.code(), Some(code));
+        assert_eq!(String::from_utf8(output.stderr)?, "");
+    }
+    Ok(())
+}
@@ -0,0 +1,63 @@
+use difftest::both;
+use proptest::prelude::*;
+
+// wcr against GNU wc ($WC overrides, gwc on macOS), see difftest for how
+// the two are run and compared
+const PRG: &str = "wcr";
+const WC: (&str, &str) = ("WC", "wc");
+
+// -- difftest::input() with its last line always ended
+fn terminated() -> impl Strategy<Value = Vec<u8>> {
+    difftest::input().prop_map(|mut input| {
+        if !input.is_empty() && !input.ends_with(b"\n") {
+            input.push(b'\n');
+        }
+        input
+    })
+}
+
+// -- the counts wcr and wc agree on, in any combination wcr takes : -m and
+// -c conflict in wcr, as in BSD wc
+fn flags() -> impl Strategy<Value = Vec<&'static str>> {
+    prop::sample::subsequence(vec!["-l", "-m", "-c"], 1..=3)
+        .prop_filter("-m with -c", |flags| !(flags.contains(&"-m") && flags.contains(&"-c")))
+}
+
+proptest! {
+    #[test]
+    fn bytes_match_wc(input in difftest::input(), from_file in any::<bool>()) {
+        let Some((ours, theirs)) = both(PRG, WC, &["-c"], &input, from_file) else { return Ok(()) };
+        prop_assert_eq!((ours.status.code(), ours.stdout), (theirs.status.code(), theirs.stdout));
+    }
+
+    #[test]
+    fn chars_match_wc(input in difftest::input(), from_file in any::<bool>()) {
+        let Some((ours, theirs)) = both(PRG, WC, &["-m"], &input, from_file) else { return Ok(()) };
+        prop_assert_eq!((ours.status.code(), ours.stdout), (theirs.status.code(), theirs.stdout));
+    }
+
+    #[test]
+    #[ignore = "known failure: wcr -l counts a last line without a newline, wc counts \
+                newlines"]
+    fn lines_match_wc(input in difftest::input(), from_file in any::<bool>()) {
+        let Some((ours, theirs)) = both(PRG, WC, &["-l"], &input, from_file) else { return Ok(()) };
+        prop_assert_eq!((ours.status.code(), ours.stdout), (theirs.status.code(), theirs.stdout));
+    }
+
+    #[test]
+    #[ignore = "known failure: wcr -w splits on Rust's whitespace (U+00A0, U+2028 ...) \
+                and counts words of unprintable chars, wc goes by glibc's iswspace() \
+                and iswprint()"]
+    fn words_match_wc(input in difftest::input(), from_file in any::<bool>()) {
+        let Some((ours, theirs)) = both(PRG, WC, &["-w"], &input, from_file) else { return Ok(()) };
+        prop_assert_eq!((ours.status.code(), ours.stdout), (theirs.status.code(), theirs.stdout));
+    }
+
+    // -- the counts and their column widths, on the input lines_match_wc's
+    // known failure doesn't touch
+    #[test]
+    fn columns_match_wc(input in terminated(), flags in flags(), from_file in any::<bool>()) {
+        let Some((ours, theirs)) = both(PRG, WC, &flags, &input, from_file) else { return Ok(()) };
+        prop_assert_eq!((ours.status.code(), ours.stdout), (theirs.status.code(), theirs.stdout));
+    }
+}
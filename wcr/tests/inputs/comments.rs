@@ -0,0 +1,10 @@
+// count lines by kind
+/*
+ * block comment
+ */
+
+fn main() {
+    let x = 1; // trailing comment is still code
+
+    /* inline */ println!("{}", x);
+}
@@ -0,0 +1,4 @@
+#!/usr/bin/env bash
+
+# say hello
+echo "hello" # trailing
@@ -0,0 +1 @@
+generated output